@@ -1,4 +1,5 @@
-PRAGMA foreign_keys = ON;
+-- Baseline schema. IF NOT EXISTS lets databases created before migrations
+-- were tracked adopt version 1 without changes.
 
 CREATE TABLE IF NOT EXISTS accounts (
   id INTEGER PRIMARY KEY AUTOINCREMENT,
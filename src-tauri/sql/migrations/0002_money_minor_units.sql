@@ -0,0 +1,92 @@
+-- Store every amount as an INTEGER count of the currency's minor unit.
+-- SQLite cannot change a column's type in place, so the money-bearing tables
+-- are rebuilt. Scale factors mirror models::money::minor_unit_exponent.
+
+CREATE TABLE accounts_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  type TEXT NOT NULL CHECK (type IN ('cash','debit','credit','savings','investment')),
+  subtype TEXT,
+  currency TEXT NOT NULL DEFAULT 'PHP',
+  opening_balance INTEGER NOT NULL DEFAULT 0,
+  credit_limit INTEGER,
+  billing_cycle_day INTEGER,
+  payment_due_day INTEGER,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO accounts_new (id, name, type, subtype, currency, opening_balance, credit_limit,
+  billing_cycle_day, payment_due_day, is_active, created_at, updated_at)
+SELECT id, name, type, subtype, currency,
+  CAST(ROUND(opening_balance * CASE
+    WHEN currency IN ('JPY','KRW','VND','CLP','ISK') THEN 1
+    WHEN currency IN ('BHD','KWD','OMR','JOD','TND') THEN 1000
+    ELSE 100
+  END) AS INTEGER),
+  CAST(ROUND(credit_limit * CASE
+    WHEN currency IN ('JPY','KRW','VND','CLP','ISK') THEN 1
+    WHEN currency IN ('BHD','KWD','OMR','JOD','TND') THEN 1000
+    ELSE 100
+  END) AS INTEGER),
+  billing_cycle_day, payment_due_day, is_active, created_at, updated_at
+FROM accounts;
+
+CREATE TABLE transfers_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  from_account_id INTEGER NOT NULL REFERENCES accounts(id),
+  to_account_id INTEGER NOT NULL REFERENCES accounts(id),
+  amount INTEGER NOT NULL,
+  date TEXT NOT NULL,
+  notes TEXT,
+  transfer_type TEXT NOT NULL DEFAULT 'regular' CHECK (transfer_type IN ('regular','credit_payment')),
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO transfers_new (id, from_account_id, to_account_id, amount, date, notes, transfer_type, created_at)
+SELECT tr.id, tr.from_account_id, tr.to_account_id,
+  CAST(ROUND(tr.amount * CASE
+    WHEN a.currency IN ('JPY','KRW','VND','CLP','ISK') THEN 1
+    WHEN a.currency IN ('BHD','KWD','OMR','JOD','TND') THEN 1000
+    ELSE 100
+  END) AS INTEGER),
+  tr.date, tr.notes, tr.transfer_type, tr.created_at
+FROM transfers tr
+LEFT JOIN accounts a ON a.id = tr.from_account_id;
+
+CREATE TABLE transactions_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts(id),
+  category_id INTEGER REFERENCES categories(id),
+  transfer_id INTEGER REFERENCES transfers(id),
+  type TEXT NOT NULL CHECK (type IN ('income','expense')),
+  amount INTEGER NOT NULL,
+  date TEXT NOT NULL,
+  notes TEXT,
+  is_recurring INTEGER NOT NULL DEFAULT 0,
+  recurrence_frequency TEXT CHECK (recurrence_frequency IN ('daily','weekly','monthly','yearly')),
+  next_due_date TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO transactions_new (id, account_id, category_id, transfer_id, type, amount, date, notes,
+  is_recurring, recurrence_frequency, next_due_date, created_at, updated_at)
+SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type,
+  CAST(ROUND(t.amount * CASE
+    WHEN a.currency IN ('JPY','KRW','VND','CLP','ISK') THEN 1
+    WHEN a.currency IN ('BHD','KWD','OMR','JOD','TND') THEN 1000
+    ELSE 100
+  END) AS INTEGER),
+  t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date, t.created_at, t.updated_at
+FROM transactions t
+LEFT JOIN accounts a ON a.id = t.account_id;
+
+DROP TABLE transactions;
+DROP TABLE transfers;
+DROP TABLE accounts;
+
+ALTER TABLE accounts_new RENAME TO accounts;
+ALTER TABLE transfers_new RENAME TO transfers;
+ALTER TABLE transactions_new RENAME TO transactions;
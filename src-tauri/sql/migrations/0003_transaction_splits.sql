@@ -0,0 +1,27 @@
+-- Split a transaction across several categories. When a transaction has
+-- splits, category reports use the split lines instead of the parent row.
+
+CREATE TABLE transaction_splits (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  transaction_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+  category_id INTEGER REFERENCES categories(id),
+  amount INTEGER NOT NULL,
+  memo TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_transaction_splits_transaction ON transaction_splits(transaction_id);
+
+-- One row per categorized line: each split, or the transaction itself when it
+-- has none. All category aggregation should read from here.
+CREATE VIEW category_lines AS
+SELECT
+  t.id AS transaction_id,
+  t.account_id,
+  t.transfer_id,
+  t.type,
+  t.date,
+  CASE WHEN s.id IS NULL THEN t.category_id ELSE s.category_id END AS category_id,
+  CASE WHEN s.id IS NULL THEN t.amount ELSE s.amount END AS amount
+FROM transactions t
+LEFT JOIN transaction_splits s ON s.transaction_id = t.id;
@@ -0,0 +1,22 @@
+-- Canonical merchants/counterparties. Raw strings from manual entry or bank
+-- imports are mapped to a payee through its aliases.
+
+CREATE TABLE payees (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- `pattern` is stored in normalized form (see `commands::payees::normalize_key`)
+-- and matches any raw name whose normalized words contain it.
+CREATE TABLE payee_aliases (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  payee_id INTEGER NOT NULL REFERENCES payees(id) ON DELETE CASCADE,
+  pattern TEXT NOT NULL UNIQUE,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+ALTER TABLE transactions ADD COLUMN payee_id INTEGER REFERENCES payees(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_payee ON transactions(payee_id);
+CREATE INDEX idx_payee_aliases_payee ON payee_aliases(payee_id);
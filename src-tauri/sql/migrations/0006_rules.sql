@@ -0,0 +1,31 @@
+-- Categorization rules. Every non-NULL condition column must match; every
+-- non-NULL action column is applied. Higher `priority` runs first.
+
+CREATE TABLE rules (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  priority INTEGER NOT NULL DEFAULT 0,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  -- Conditions
+  notes_contains TEXT,
+  payee_contains TEXT,
+  amount_min INTEGER,
+  amount_max INTEGER,
+  amount_currency TEXT,
+  account_id INTEGER REFERENCES accounts(id) ON DELETE CASCADE,
+  tx_type TEXT CHECK (tx_type IN ('income','expense')),
+  -- Actions
+  set_category_id INTEGER REFERENCES categories(id),
+  set_payee_id INTEGER REFERENCES payees(id) ON DELETE SET NULL,
+  set_notes TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  CHECK ((amount_min IS NULL AND amount_max IS NULL) OR amount_currency IS NOT NULL)
+);
+
+-- "Add tag" actions.
+CREATE TABLE rule_tags (
+  rule_id INTEGER NOT NULL REFERENCES rules(id) ON DELETE CASCADE,
+  tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+  PRIMARY KEY (rule_id, tag_id)
+);
@@ -0,0 +1,32 @@
+-- Bank CSV import: saved column mappings and the batches they produced.
+
+CREATE TABLE import_profiles (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  delimiter TEXT,                              -- NULL = detect
+  has_header INTEGER NOT NULL DEFAULT 1,
+  skip_rows INTEGER NOT NULL DEFAULT 0,        -- preamble lines before the header
+  date_column INTEGER NOT NULL,                -- 0-based column indexes
+  date_format TEXT NOT NULL,                   -- e.g. 'MM/DD/YYYY', 'DD-MMM-YY'
+  description_column INTEGER,
+  amount_column INTEGER,                       -- one signed column, or
+  debit_column INTEGER,                        -- separate money-out /
+  credit_column INTEGER,                       -- money-in columns
+  negate_amounts INTEGER NOT NULL DEFAULT 0,   -- signed column uses + for spending
+  decimal_separator TEXT NOT NULL DEFAULT '.',
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  CHECK (amount_column IS NOT NULL OR debit_column IS NOT NULL OR credit_column IS NOT NULL)
+);
+
+CREATE TABLE import_batches (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+  source TEXT NOT NULL,                        -- 'csv', ...
+  file_name TEXT,
+  row_count INTEGER NOT NULL DEFAULT 0,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+ALTER TABLE transactions ADD COLUMN import_batch_id INTEGER REFERENCES import_batches(id);
+
+CREATE INDEX idx_transactions_import_batch ON transactions(import_batch_id);
@@ -0,0 +1,11 @@
+-- OFX/QFX and QIF import. `external_id` holds the bank's transaction id
+-- (OFX FITID) so overlapping statements can be re-imported safely.
+
+ALTER TABLE transactions ADD COLUMN external_id TEXT;
+
+CREATE UNIQUE INDEX idx_transactions_external
+  ON transactions(account_id, external_id) WHERE external_id IS NOT NULL;
+
+ALTER TABLE accounts ADD COLUMN ofx_account_id TEXT;
+
+CREATE UNIQUE INDEX idx_accounts_ofx ON accounts(ofx_account_id) WHERE ofx_account_id IS NOT NULL;
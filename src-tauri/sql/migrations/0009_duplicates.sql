@@ -0,0 +1,12 @@
+-- Duplicate detection. `duplicate_of` flags an imported row that probably
+-- repeats an existing one; dismissed pairs are never suggested again.
+
+ALTER TABLE transactions ADD COLUMN duplicate_of INTEGER REFERENCES transactions(id) ON DELETE SET NULL;
+
+CREATE TABLE duplicate_dismissals (
+  transaction_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+  other_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  PRIMARY KEY (transaction_id, other_id),
+  CHECK (transaction_id < other_id)
+);
@@ -0,0 +1,19 @@
+-- Receipts and documents. Files are stored as blobs so they share the
+-- database's encryption and travel with every backup. Each attachment
+-- belongs to exactly one transaction or transfer.
+
+CREATE TABLE attachments (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  transaction_id INTEGER REFERENCES transactions(id) ON DELETE CASCADE,
+  transfer_id INTEGER REFERENCES transfers(id) ON DELETE CASCADE,
+  file_name TEXT NOT NULL,
+  mime_type TEXT NOT NULL,
+  size INTEGER NOT NULL,                       -- bytes
+  data BLOB NOT NULL,
+  thumbnail BLOB,                              -- PNG, images only
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  CHECK ((transaction_id IS NULL) != (transfer_id IS NULL))
+);
+
+CREATE INDEX idx_attachments_transaction ON attachments(transaction_id);
+CREATE INDEX idx_attachments_transfer ON attachments(transfer_id);
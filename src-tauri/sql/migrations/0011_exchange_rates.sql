@@ -0,0 +1,20 @@
+-- Dated exchange rates for converting totals into the base currency. A rate
+-- applies from its date until the next one for the same pair.
+
+CREATE TABLE exchange_rates (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  base TEXT NOT NULL,                          -- one unit of `base` ...
+  quote TEXT NOT NULL,                         -- ... is worth `rate` units of `quote`
+  rate REAL NOT NULL CHECK (rate > 0),
+  date TEXT NOT NULL,
+  source TEXT NOT NULL DEFAULT 'manual',       -- 'manual' | 'csv'
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (base, quote, date),
+  CHECK (base != quote)
+);
+
+-- Small key/value preferences that belong with the data (e.g. base_currency).
+CREATE TABLE app_settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Transfers between accounts in different currencies. `amount` stays the
+-- amount leaving the source account (in its currency); `to_amount` is what
+-- arrived in the destination account (in its currency). Existing transfers
+-- were always single-currency, so both sides were equal.
+ALTER TABLE transfers ADD COLUMN to_amount INTEGER NOT NULL DEFAULT 0;
+UPDATE transfers SET to_amount = amount;
+
+-- Optional fee charged on the source account, posted as an ordinary
+-- categorized expense so it shows up in spending reports.
+ALTER TABLE transfers ADD COLUMN fee_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL;
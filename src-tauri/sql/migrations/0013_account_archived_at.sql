@@ -0,0 +1,5 @@
+-- When an account was archived, so history can keep counting it for the
+-- period it was open. Accounts archived before this migration use their
+-- last update as the best available date.
+ALTER TABLE accounts ADD COLUMN archived_at TEXT;
+UPDATE accounts SET archived_at = DATE(updated_at) WHERE is_active = 0;
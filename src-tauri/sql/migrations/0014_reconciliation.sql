@@ -0,0 +1,25 @@
+-- Statement reconciliation. Transactions move from 'uncleared' to 'cleared'
+-- when ticked off against a statement, and to 'reconciled' when a session
+-- balancing that statement is finished; reconciled rows are locked.
+
+CREATE TABLE reconciliations (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+  statement_date TEXT NOT NULL,
+  statement_balance INTEGER NOT NULL,          -- minor units of the account's currency
+  status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open','completed')),
+  cleared_balance INTEGER,                     -- set when completed
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  completed_at TEXT
+);
+
+CREATE INDEX idx_reconciliations_account ON reconciliations(account_id, statement_date);
+
+-- At most one session in progress per account.
+CREATE UNIQUE INDEX idx_reconciliations_open ON reconciliations(account_id) WHERE status = 'open';
+
+ALTER TABLE transactions ADD COLUMN status TEXT NOT NULL DEFAULT 'uncleared'
+  CHECK (status IN ('uncleared','cleared','reconciled'));
+ALTER TABLE transactions ADD COLUMN reconciliation_id INTEGER REFERENCES reconciliations(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_reconciliation ON transactions(reconciliation_id);
@@ -0,0 +1,30 @@
+-- Credit card installment plans. Converting a purchase posts a credit that
+-- takes it off the card balance, then each month an installment of principal
+-- (plus add-on interest, if any) is billed, the way card statements show it.
+-- The purchase itself stays as the spending; conversion and principal rows
+-- only move the card balance and are left out of spending reports.
+
+CREATE TABLE installment_plans (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+  purchase_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+  description TEXT,
+  principal INTEGER NOT NULL CHECK (principal > 0),
+  term_months INTEGER NOT NULL CHECK (term_months > 0),
+  monthly_add_on_rate REAL NOT NULL DEFAULT 0 CHECK (monthly_add_on_rate >= 0),  -- percent of principal per month
+  processing_fee INTEGER NOT NULL DEFAULT 0 CHECK (processing_fee >= 0),
+  category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,              -- for interest and fees
+  start_date TEXT NOT NULL,                    -- first installment is billed on this day
+  status TEXT NOT NULL DEFAULT 'active' CHECK (status IN ('active','paid_off','completed')),
+  paid_off_date TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_installment_plans_account ON installment_plans(account_id);
+
+ALTER TABLE transactions ADD COLUMN installment_plan_id INTEGER REFERENCES installment_plans(id) ON DELETE SET NULL;
+ALTER TABLE transactions ADD COLUMN installment_kind TEXT
+  CHECK (installment_kind IN ('conversion','principal','interest','fee'));
+ALTER TABLE transactions ADD COLUMN installment_number INTEGER;
+
+CREATE INDEX idx_transactions_installment_plan ON transactions(installment_plan_id);
@@ -0,0 +1,52 @@
+-- Loan accounts. The type CHECK on accounts cannot be altered in place, so
+-- the table is rebuilt with 'loan' allowed; ids are kept, so every foreign
+-- key pointing at accounts stays valid.
+
+CREATE TABLE accounts_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  type TEXT NOT NULL CHECK (type IN ('cash','debit','credit','savings','investment','loan')),
+  subtype TEXT,
+  currency TEXT NOT NULL DEFAULT 'PHP',
+  opening_balance INTEGER NOT NULL DEFAULT 0,
+  credit_limit INTEGER,
+  billing_cycle_day INTEGER,
+  payment_due_day INTEGER,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  ofx_account_id TEXT,
+  archived_at TEXT
+);
+
+INSERT INTO accounts_new (id, name, type, subtype, currency, opening_balance, credit_limit,
+  billing_cycle_day, payment_due_day, is_active, created_at, updated_at, ofx_account_id, archived_at)
+SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
+  billing_cycle_day, payment_due_day, is_active, created_at, updated_at, ofx_account_id, archived_at
+FROM accounts;
+
+DROP TABLE accounts;
+ALTER TABLE accounts_new RENAME TO accounts;
+
+CREATE UNIQUE INDEX idx_accounts_ofx ON accounts(ofx_account_id) WHERE ofx_account_id IS NOT NULL;
+
+-- Repayment terms of a loan account. Like a credit card, a loan's balance is
+-- the amount owed; the original principal and rate here drive the
+-- amortization schedule.
+CREATE TABLE loans (
+  account_id INTEGER PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+  principal INTEGER NOT NULL CHECK (principal > 0),
+  annual_rate REAL NOT NULL DEFAULT 0 CHECK (annual_rate >= 0),   -- percent per year
+  term_payments INTEGER NOT NULL CHECK (term_payments > 0),
+  payment_frequency TEXT NOT NULL DEFAULT 'monthly'
+    CHECK (payment_frequency IN ('weekly','biweekly','monthly','quarterly')),
+  first_payment_date TEXT NOT NULL,
+  interest_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Interest charged to the loan as part of a payment into it, posted as a
+-- categorized expense on the loan account (like a transfer's fee on the
+-- source account).
+ALTER TABLE transfers ADD COLUMN interest_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL;
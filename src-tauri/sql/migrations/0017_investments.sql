@@ -0,0 +1,42 @@
+-- Securities held in investment accounts. Each trade moves cash in or out of
+-- the account through an ordinary transaction (linked by trade_id), so the
+-- account balance stays the cash balance; holdings are rebuilt from trades
+-- and valued from the local price table.
+
+CREATE TABLE securities (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  symbol TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  name TEXT,
+  currency TEXT NOT NULL DEFAULT 'PHP',
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Closing price per unit in major units of the security's currency (unit
+-- prices such as fund NAVs often carry more decimals than the currency).
+CREATE TABLE security_prices (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  security_id INTEGER NOT NULL REFERENCES securities(id) ON DELETE CASCADE,
+  date TEXT NOT NULL,
+  price REAL NOT NULL CHECK (price > 0),
+  source TEXT NOT NULL DEFAULT 'manual',       -- 'manual' | 'csv'
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (security_id, date)
+);
+
+CREATE TABLE investment_trades (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+  security_id INTEGER NOT NULL REFERENCES securities(id),
+  kind TEXT NOT NULL CHECK (kind IN ('buy','sell','dividend')),
+  date TEXT NOT NULL,
+  quantity REAL NOT NULL DEFAULT 0 CHECK (quantity >= 0),
+  price REAL NOT NULL DEFAULT 0 CHECK (price >= 0),
+  fees INTEGER NOT NULL DEFAULT 0 CHECK (fees >= 0),
+  amount INTEGER NOT NULL,                     -- cash paid (buy) or received (sell, dividend), fees included
+  notes TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_investment_trades_account ON investment_trades(account_id, security_id, date);
+
+ALTER TABLE transactions ADD COLUMN trade_id INTEGER REFERENCES investment_trades(id) ON DELETE CASCADE;
@@ -0,0 +1,37 @@
+-- Terms of a savings instrument (Pag-IBIG MP2, a time deposit) held in a
+-- savings account. Contributions are ordinary deposits into the account;
+-- the plan only says what was promised, so shortfalls and the value at
+-- maturity can be worked out.
+
+CREATE TABLE savings_plans (
+  account_id INTEGER PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL DEFAULT 'other' CHECK (kind IN ('mp2','time_deposit','other')),
+  start_date TEXT NOT NULL,
+  term_months INTEGER NOT NULL CHECK (term_months > 0),          -- lock-in; matures this many months after start
+  contribution_amount INTEGER NOT NULL DEFAULT 0 CHECK (contribution_amount >= 0),
+  contribution_frequency TEXT NOT NULL DEFAULT 'monthly'
+    CHECK (contribution_frequency IN ('once','monthly','quarterly','annually')),
+  annual_rate REAL NOT NULL DEFAULT 0 CHECK (annual_rate >= 0),   -- percent per year, for years with no declared rate
+  payout_mode TEXT NOT NULL DEFAULT 'compounding' CHECK (payout_mode IN ('compounding','annual_payout')),
+  payout_account_id INTEGER REFERENCES accounts(id) ON DELETE SET NULL,
+  dividend_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Dividend or interest rate declared for one calendar year (MP2 rates are
+-- announced after the year ends).
+CREATE TABLE savings_rates (
+  account_id INTEGER NOT NULL REFERENCES savings_plans(account_id) ON DELETE CASCADE,
+  year INTEGER NOT NULL,
+  rate REAL NOT NULL CHECK (rate >= 0),                           -- percent per year
+  PRIMARY KEY (account_id, year)
+);
+
+-- Income transactions that credit a plan's dividends or interest, so they
+-- are not counted as contributions. Paid-out dividends land in the payout
+-- account, so the plan's account is kept alongside.
+CREATE TABLE savings_dividends (
+  transaction_id INTEGER PRIMARY KEY REFERENCES transactions(id) ON DELETE CASCADE,
+  account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE
+);
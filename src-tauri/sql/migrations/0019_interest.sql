@@ -0,0 +1,30 @@
+-- Interest earned by an asset account, or finance charges on a credit card.
+
+CREATE TABLE interest_settings (
+  account_id INTEGER PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+  rate REAL NOT NULL CHECK (rate >= 0),                           -- percent per year
+  rate_type TEXT NOT NULL DEFAULT 'apr' CHECK (rate_type IN ('apr','apy')),
+  day_count TEXT NOT NULL DEFAULT 'actual/365' CHECK (day_count IN ('actual/365','actual/360','30/360')),
+  compounding TEXT NOT NULL DEFAULT 'monthly' CHECK (compounding IN ('daily','monthly','quarterly','annually')),
+  withholding_tax_rate REAL NOT NULL DEFAULT 0
+    CHECK (withholding_tax_rate >= 0 AND withholding_tax_rate <= 100),  -- percent of gross interest
+  category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+  tax_category_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Interest or a finance charge posted for one period. A period that overlaps
+-- a posted one is skipped, so accruing the same dates twice posts nothing
+-- new; deleting the posted transaction frees the period again.
+CREATE TABLE interest_postings (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+  period_start TEXT NOT NULL,
+  period_end TEXT NOT NULL,
+  transaction_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+  tax_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_interest_postings_account ON interest_postings(account_id, period_end);
@@ -0,0 +1,16 @@
+-- Monthly spending limit for one expense category, in the base currency.
+-- With rollover on, what was left of the previous month's budget (or the
+-- amount it ran over) is carried into this month.
+
+CREATE TABLE budgets (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+  period TEXT NOT NULL,                                           -- 'YYYY-MM'
+  amount INTEGER NOT NULL CHECK (amount >= 0),
+  rollover INTEGER NOT NULL DEFAULT 0 CHECK (rollover IN (0, 1)),
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  UNIQUE (category_id, period)
+);
+
+CREATE INDEX idx_budgets_period ON budgets(period);
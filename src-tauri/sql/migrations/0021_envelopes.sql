@@ -0,0 +1,18 @@
+-- Zero-based envelope budgeting. Income lands in a "to be assigned" pool;
+-- each move takes money from the pool or an envelope (an expense category)
+-- and puts it into another one for a month. A NULL side is the pool.
+-- Envelope mode itself is the app_settings key 'envelope_budgeting'.
+
+CREATE TABLE envelope_moves (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  period TEXT NOT NULL,                                           -- 'YYYY-MM'
+  from_category_id INTEGER REFERENCES categories(id) ON DELETE CASCADE,
+  to_category_id INTEGER REFERENCES categories(id) ON DELETE CASCADE,
+  amount INTEGER NOT NULL CHECK (amount > 0),                     -- base currency
+  notes TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  CHECK (from_category_id IS NOT NULL OR to_category_id IS NOT NULL),
+  CHECK (from_category_id IS NULL OR to_category_id IS NULL OR from_category_id <> to_category_id)
+);
+
+CREATE INDEX idx_envelope_moves_period ON envelope_moves(period);
@@ -0,0 +1,6 @@
+-- Categories nest under a parent (e.g. "Groceries" under "Food & Dining").
+-- Top-level categories have no parent. Cycles are refused by the commands
+-- that set the parent, since SQLite cannot check them declaratively.
+ALTER TABLE categories ADD COLUMN parent_id INTEGER REFERENCES categories(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_categories_parent ON categories(parent_id);
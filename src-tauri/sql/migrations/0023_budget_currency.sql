@@ -0,0 +1,7 @@
+-- Budgets remember the currency they were set in, so changing the base
+-- currency converts them instead of reading the stored minor units as the
+-- new currency. Budgets so far were set in the base currency of the time.
+ALTER TABLE budgets ADD COLUMN currency TEXT NOT NULL DEFAULT 'PHP';
+
+UPDATE budgets
+SET currency = COALESCE((SELECT value FROM app_settings WHERE key = 'base_currency'), 'PHP');
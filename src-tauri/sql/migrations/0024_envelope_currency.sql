@@ -0,0 +1,7 @@
+-- Envelope moves remember the currency they were made in, so changing the
+-- base currency converts past assignments instead of reinterpreting them.
+-- Moves so far were made in the base currency of the time.
+ALTER TABLE envelope_moves ADD COLUMN currency TEXT NOT NULL DEFAULT 'PHP';
+
+UPDATE envelope_moves
+SET currency = COALESCE((SELECT value FROM app_settings WHERE key = 'base_currency'), 'PHP');
@@ -1,34 +1,109 @@
+use rusqlite::OptionalExtension;
 use tauri::State;
-use crate::db::DbState;
-use crate::models::{Account, AccountWithBalance};
+use crate::db::{with_transaction, DbState};
+use crate::models::{normalize_currency, Account, AccountWithBalance, Money, DEFAULT_CURRENCY};
 use crate::logic::balance::compute_balance;
+use crate::logic::csv_import::parse_date;
 
-fn row_to_account(row: &rusqlite::Row) -> rusqlite::Result<Account> {
+/// Column list shared by every query that feeds `row_to_account`.
+pub(crate) const ACCOUNT_SELECT: &str = "SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
+         billing_cycle_day, payment_due_day, is_active, created_at, updated_at, ofx_account_id
+         FROM accounts";
+
+pub(crate) fn row_to_account(row: &rusqlite::Row) -> rusqlite::Result<Account> {
+    let currency: String = row.get(4)?;
     Ok(Account {
         id: row.get(0)?,
         name: row.get(1)?,
         account_type: row.get(2)?,
         subtype: row.get(3)?,
-        currency: row.get(4)?,
-        opening_balance: row.get(5)?,
-        credit_limit: row.get(6)?,
+        opening_balance: Money::new(row.get(5)?, currency.clone()),
+        credit_limit: row
+            .get::<_, Option<i64>>(6)?
+            .map(|minor| Money::new(minor, currency.clone())),
+        currency,
         billing_cycle_day: row.get(7)?,
         payment_due_day: row.get(8)?,
         is_active: row.get(9)?,
         created_at: row.get(10)?,
         updated_at: row.get(11)?,
+        ofx_account_id: row.get(12)?,
     })
 }
 
+/// Condition on `accounts` for "open on date `?1`": created or already
+/// transacting by then, and not yet archived.
+pub(crate) const OPEN_ON_DATE: &str = "(DATE(accounts.created_at) <= ?1
+         OR EXISTS (SELECT 1 FROM transactions t WHERE t.account_id = accounts.id AND t.date <= ?1))
+         AND (accounts.archived_at IS NULL OR accounts.archived_at > ?1)";
+
+/// Attaches the balance as of the end of `as_of`, or the current balance.
+pub(crate) fn with_balance(
+    conn: &rusqlite::Connection,
+    account: Account,
+    as_of: Option<&str>,
+) -> AccountWithBalance {
+    let balance = compute_balance(conn, &account, as_of);
+    AccountWithBalance {
+        id: account.id,
+        name: account.name,
+        account_type: account.account_type,
+        subtype: account.subtype,
+        currency: account.currency,
+        opening_balance: account.opening_balance,
+        credit_limit: account.credit_limit,
+        billing_cycle_day: account.billing_cycle_day,
+        payment_due_day: account.payment_due_day,
+        is_active: account.is_active,
+        created_at: account.created_at,
+        updated_at: account.updated_at,
+        ofx_account_id: account.ofx_account_id,
+        balance,
+    }
+}
+
+/// Currency of the given account, used to validate incoming amounts.
+pub(crate) fn account_currency(conn: &rusqlite::Connection, account_id: i64) -> Result<String, String> {
+    conn.query_row("SELECT currency FROM accounts WHERE id = ?1", [account_id], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// Links (or with `None`, unlinks) the OFX `ACCTID` that routes imports to
+/// this account.
+pub(crate) fn link_ofx_account_id(
+    conn: &rusqlite::Connection,
+    account_id: i64,
+    ofx_account_id: Option<&str>,
+) -> Result<(), String> {
+    let ofx_account_id = ofx_account_id.map(str::trim).filter(|id| !id.is_empty());
+    if let Some(id) = ofx_account_id {
+        let linked: Option<String> = conn
+            .query_row(
+                "SELECT name FROM accounts WHERE ofx_account_id = ?1 AND id != ?2",
+                rusqlite::params![id, account_id],
+                |r| r.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if let Some(name) = linked {
+            return Err(format!("OFX account {} is already linked to {}", id, name));
+        }
+    }
+    conn.execute(
+        "UPDATE accounts SET ofx_account_id = ?1, updated_at = datetime('now') WHERE id = ?2",
+        rusqlite::params![ofx_account_id, account_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn list_accounts(state: State<DbState>) -> Result<Vec<AccountWithBalance>, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
-             billing_cycle_day, payment_due_day, is_active, created_at, updated_at
-             FROM accounts ORDER BY name",
+            &format!("{ACCOUNT_SELECT} ORDER BY name"),
         )
         .map_err(|e| e.to_string())?;
 
@@ -39,60 +114,56 @@ pub fn list_accounts(state: State<DbState>) -> Result<Vec<AccountWithBalance>, S
         .collect();
 
     let result = accounts
-        .iter()
-        .map(|a| {
-            let balance = compute_balance(conn, a);
-            AccountWithBalance {
-                id: a.id,
-                name: a.name.clone(),
-                account_type: a.account_type.clone(),
-                subtype: a.subtype.clone(),
-                currency: a.currency.clone(),
-                opening_balance: a.opening_balance,
-                credit_limit: a.credit_limit,
-                billing_cycle_day: a.billing_cycle_day,
-                payment_due_day: a.payment_due_day,
-                is_active: a.is_active,
-                created_at: a.created_at.clone(),
-                updated_at: a.updated_at.clone(),
-                balance,
-            }
-        })
+        .into_iter()
+        .map(|a| with_balance(conn, a, None))
         .collect();
 
     Ok(result)
 }
 
+/// Accounts that were open at the end of `date`, with their balances then.
+/// All of them were active on that date, whatever their state is now.
+pub(crate) fn accounts_as_of(
+    conn: &rusqlite::Connection,
+    date: &str,
+) -> Result<Vec<AccountWithBalance>, String> {
+    let mut stmt = conn
+        .prepare(&format!("{ACCOUNT_SELECT} WHERE {OPEN_ON_DATE} ORDER BY name"))
+        .map_err(|e| e.to_string())?;
+
+    let accounts: Vec<Account> = stmt
+        .query_map([date], |row| row_to_account(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(accounts
+        .into_iter()
+        .map(|a| AccountWithBalance { is_active: 1, ..with_balance(conn, a, Some(date)) })
+        .collect())
+}
+
+/// `list_accounts` as it would have looked at the end of `date`.
+#[tauri::command]
+pub fn get_accounts_as_of(state: State<DbState>, date: String) -> Result<Vec<AccountWithBalance>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    accounts_as_of(conn, &parse_date(&date, "YYYY-MM-DD")?)
+}
+
 #[tauri::command]
 pub fn get_account(state: State<DbState>, id: i64) -> Result<AccountWithBalance, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     let account = conn
         .query_row(
-            "SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
-             billing_cycle_day, payment_due_day, is_active, created_at, updated_at
-             FROM accounts WHERE id = ?1",
+            &format!("{ACCOUNT_SELECT} WHERE id = ?1"),
             [id],
             |row| row_to_account(row),
         )
         .map_err(|e| e.to_string())?;
 
-    let balance = compute_balance(conn, &account);
-    Ok(AccountWithBalance {
-        id: account.id,
-        name: account.name,
-        account_type: account.account_type,
-        subtype: account.subtype,
-        currency: account.currency,
-        opening_balance: account.opening_balance,
-        credit_limit: account.credit_limit,
-        billing_cycle_day: account.billing_cycle_day,
-        payment_due_day: account.payment_due_day,
-        is_active: account.is_active,
-        created_at: account.created_at,
-        updated_at: account.updated_at,
-        balance,
-    })
+    Ok(with_balance(conn, account, None))
 }
 
 #[tauri::command]
@@ -101,47 +172,40 @@ pub fn create_account(
     name: String,
     account_type: String,
     subtype: Option<String>,
-    opening_balance: f64,
-    credit_limit: Option<f64>,
+    opening_balance: Money,
+    credit_limit: Option<Money>,
     billing_cycle_day: Option<i64>,
     payment_due_day: Option<i64>,
+    currency: Option<String>,
 ) -> Result<AccountWithBalance, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    // The currency is fixed at creation; amounts are stored in its minor units.
+    let currency = match currency {
+        Some(ref code) => normalize_currency(code)?,
+        None => DEFAULT_CURRENCY.to_string(),
+    };
+    opening_balance.require_currency(&currency)?;
+    if let Some(ref limit) = credit_limit {
+        limit.require_currency(&currency)?;
+    }
     conn.execute(
-        "INSERT INTO accounts (name, type, subtype, opening_balance, credit_limit, billing_cycle_day, payment_due_day)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        rusqlite::params![name, account_type, subtype, opening_balance, credit_limit, billing_cycle_day, payment_due_day],
+        "INSERT INTO accounts (name, type, subtype, currency, opening_balance, credit_limit, billing_cycle_day, payment_due_day)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![name, account_type, subtype, currency, opening_balance.minor, credit_limit.map(|m| m.minor), billing_cycle_day, payment_due_day],
     )
     .map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
     let account = conn
         .query_row(
-            "SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
-             billing_cycle_day, payment_due_day, is_active, created_at, updated_at
-             FROM accounts WHERE id = ?1",
+            &format!("{ACCOUNT_SELECT} WHERE id = ?1"),
             [id],
             |row| row_to_account(row),
         )
         .map_err(|e| e.to_string())?;
 
-    let balance = compute_balance(conn, &account);
-    Ok(AccountWithBalance {
-        id: account.id,
-        name: account.name,
-        account_type: account.account_type,
-        subtype: account.subtype,
-        currency: account.currency,
-        opening_balance: account.opening_balance,
-        credit_limit: account.credit_limit,
-        billing_cycle_day: account.billing_cycle_day,
-        payment_due_day: account.payment_due_day,
-        is_active: account.is_active,
-        created_at: account.created_at,
-        updated_at: account.updated_at,
-        balance,
-    })
+    Ok(with_balance(conn, account, None))
 }
 
 #[tauri::command]
@@ -151,47 +215,50 @@ pub fn update_account(
     name: String,
     account_type: String,
     subtype: Option<String>,
-    opening_balance: f64,
-    credit_limit: Option<f64>,
+    opening_balance: Money,
+    credit_limit: Option<Money>,
     billing_cycle_day: Option<i64>,
     payment_due_day: Option<i64>,
 ) -> Result<AccountWithBalance, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let currency = account_currency(conn, id)?;
+    opening_balance.require_currency(&currency)?;
+    if let Some(ref limit) = credit_limit {
+        limit.require_currency(&currency)?;
+    }
     conn.execute(
         "UPDATE accounts SET name=?1, type=?2, subtype=?3, opening_balance=?4,
          credit_limit=?5, billing_cycle_day=?6, payment_due_day=?7,
          updated_at=datetime('now') WHERE id=?8",
-        rusqlite::params![name, account_type, subtype, opening_balance, credit_limit, billing_cycle_day, payment_due_day, id],
+        rusqlite::params![name, account_type, subtype, opening_balance.minor, credit_limit.map(|m| m.minor), billing_cycle_day, payment_due_day, id],
     )
     .map_err(|e| e.to_string())?;
 
     let account = conn
         .query_row(
-            "SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
-             billing_cycle_day, payment_due_day, is_active, created_at, updated_at
-             FROM accounts WHERE id = ?1",
+            &format!("{ACCOUNT_SELECT} WHERE id = ?1"),
             [id],
             |row| row_to_account(row),
         )
         .map_err(|e| e.to_string())?;
 
-    let balance = compute_balance(conn, &account);
-    Ok(AccountWithBalance {
-        id: account.id,
-        name: account.name,
-        account_type: account.account_type,
-        subtype: account.subtype,
-        currency: account.currency,
-        opening_balance: account.opening_balance,
-        credit_limit: account.credit_limit,
-        billing_cycle_day: account.billing_cycle_day,
-        payment_due_day: account.payment_due_day,
-        is_active: account.is_active,
-        created_at: account.created_at,
-        updated_at: account.updated_at,
-        balance,
-    })
+    Ok(with_balance(conn, account, None))
+}
+
+#[tauri::command]
+pub fn link_ofx_account(
+    state: State<DbState>,
+    id: i64,
+    ofx_account_id: Option<String>,
+) -> Result<AccountWithBalance, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    link_ofx_account_id(conn, id, ofx_account_id.as_deref())?;
+    let account = conn
+        .query_row(&format!("{ACCOUNT_SELECT} WHERE id = ?1"), [id], |row| row_to_account(row))
+        .map_err(|e| e.to_string())?;
+    Ok(with_balance(conn, account, None))
 }
 
 #[tauri::command]
@@ -199,7 +266,7 @@ pub fn archive_account(state: State<DbState>, id: i64) -> Result<(), String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     conn.execute(
-        "UPDATE accounts SET is_active = 0, updated_at = datetime('now') WHERE id = ?1",
+        "UPDATE accounts SET is_active = 0, archived_at = DATE('now'), updated_at = datetime('now') WHERE id = ?1",
         [id],
     )
     .map_err(|e| e.to_string())?;
@@ -211,7 +278,7 @@ pub fn restore_account(state: State<DbState>, id: i64) -> Result<(), String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     conn.execute(
-        "UPDATE accounts SET is_active = 1, updated_at = datetime('now') WHERE id = ?1",
+        "UPDATE accounts SET is_active = 1, archived_at = NULL, updated_at = datetime('now') WHERE id = ?1",
         [id],
     )
     .map_err(|e| e.to_string())?;
@@ -220,11 +287,51 @@ pub fn restore_account(state: State<DbState>, id: i64) -> Result<(), String> {
 
 #[tauri::command]
 pub fn delete_account(state: State<DbState>, id: i64) -> Result<(), String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    conn.execute("DELETE FROM transactions WHERE account_id = ?1", [id])
-        .map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM accounts WHERE id = ?1", [id])
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    with_transaction(&state, |conn| {
+        conn.execute("DELETE FROM transactions WHERE account_id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM accounts WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn accounts_as_of_shows_the_accounts_and_balances_of_that_day() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, opening_balance, created_at) VALUES ('Bank', 'debit', 100000, '2024-06-01 08:00:00');
+             INSERT INTO accounts (name, type, created_at) VALUES ('Card', 'credit', '2024-12-20 08:00:00');
+             INSERT INTO accounts (name, type, created_at, is_active, archived_at)
+               VALUES ('Old Wallet', 'cash', '2024-01-01 08:00:00', 0, '2025-01-10');
+             INSERT INTO accounts (name, type, created_at) VALUES ('New Savings', 'savings', '2025-01-05 08:00:00');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 25000, '2024-12-31');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 10000, '2025-01-02');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'expense', 4000, '2024-12-28');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'income', 4000, '2025-01-03');",
+        )
+        .unwrap();
+
+        let accounts = accounts_as_of(&conn, "2024-12-31").unwrap();
+        let summary: Vec<(&str, i64, i64)> = accounts
+            .iter()
+            .map(|a| (a.name.as_str(), a.balance.minor, a.is_active))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("Bank", 75000, 1), ("Card", 4000, 1), ("Old Wallet", 0, 1)]
+        );
+
+        let names: Vec<String> = accounts_as_of(&conn, "2025-01-10")
+            .unwrap()
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(names, vec!["Bank", "Card", "New Savings"]);
+    }
 }
@@ -0,0 +1,232 @@
+use rusqlite::Connection;
+use tauri::ipc::Response;
+use tauri::{AppHandle, State};
+use crate::db::{with_transaction, DbState};
+use crate::commands::imports::file_name;
+use crate::logic::attachments::{
+    detect_mime, make_thumbnail, MAX_ATTACHMENT_BYTES, MAX_TOTAL_ATTACHMENT_BYTES,
+};
+use crate::models::Attachment;
+
+/// Metadata only; the blobs are fetched one at a time on demand.
+const ATTACHMENT_SELECT: &str = "SELECT id, transaction_id, transfer_id, file_name, mime_type, size,
+         thumbnail IS NOT NULL, created_at
+         FROM attachments";
+
+fn row_to_attachment(row: &rusqlite::Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: row.get(0)?,
+        transaction_id: row.get(1)?,
+        transfer_id: row.get(2)?,
+        file_name: row.get(3)?,
+        mime_type: row.get(4)?,
+        size: row.get(5)?,
+        has_thumbnail: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+/// Stores `bytes` against exactly one transaction or transfer after checking
+/// the type and size limits. Images get a thumbnail.
+pub(crate) fn insert_attachment(
+    conn: &Connection,
+    transaction_id: Option<i64>,
+    transfer_id: Option<i64>,
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<Attachment, String> {
+    if transaction_id.is_some() == transfer_id.is_some() {
+        return Err("Attach the file to either a transaction or a transfer".into());
+    }
+    if bytes.len() as u64 > MAX_ATTACHMENT_BYTES {
+        return Err(format!(
+            "Attachments are limited to {} MB",
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        ));
+    }
+    let mime = detect_mime(bytes)
+        .ok_or_else(|| "Only images (PNG, JPEG, GIF, WebP) and PDFs can be attached".to_string())?;
+    let stored: i64 = conn
+        .query_row("SELECT COALESCE(SUM(size), 0) FROM attachments", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if stored + bytes.len() as i64 > MAX_TOTAL_ATTACHMENT_BYTES {
+        return Err("Attachment storage is full; delete some attachments first".into());
+    }
+
+    conn.execute(
+        "INSERT INTO attachments (transaction_id, transfer_id, file_name, mime_type, size, data, thumbnail)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            transaction_id,
+            transfer_id,
+            file_name,
+            mime,
+            bytes.len() as i64,
+            bytes,
+            make_thumbnail(bytes, mime)
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row(
+        &format!("{ATTACHMENT_SELECT} WHERE id = ?1"),
+        [conn.last_insert_rowid()],
+        |row| row_to_attachment(row),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Opens a file picker for a receipt or document and returns the chosen path.
+#[tauri::command]
+pub async fn pick_attachment_file(app: AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let path = app
+        .dialog()
+        .file()
+        .add_filter("Receipt or document", &["png", "jpg", "jpeg", "gif", "webp", "pdf"])
+        .blocking_pick_file();
+
+    Ok(path.map(|p| p.to_string()))
+}
+
+/// Lists the attachments of one transaction or transfer.
+#[tauri::command]
+pub fn list_attachments(
+    state: State<DbState>,
+    transaction_id: Option<i64>,
+    transfer_id: Option<i64>,
+) -> Result<Vec<Attachment>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{ATTACHMENT_SELECT} WHERE transaction_id IS ?1 AND transfer_id IS ?2 ORDER BY id"
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let attachments = stmt
+        .query_map(rusqlite::params![transaction_id, transfer_id], |row| row_to_attachment(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(attachments)
+}
+
+#[tauri::command]
+pub fn add_attachment(
+    state: State<DbState>,
+    transaction_id: Option<i64>,
+    transfer_id: Option<i64>,
+    file_path: String,
+) -> Result<Attachment, String> {
+    let size = std::fs::metadata(&file_path).map_err(|e| e.to_string())?.len();
+    if size > MAX_ATTACHMENT_BYTES {
+        return Err(format!(
+            "Attachments are limited to {} MB",
+            MAX_ATTACHMENT_BYTES / (1024 * 1024)
+        ));
+    }
+    let bytes = std::fs::read(&file_path).map_err(|e| e.to_string())?;
+    let name = file_name(&file_path).unwrap_or_else(|| "attachment".to_string());
+    with_transaction(&state, |conn| {
+        insert_attachment(conn, transaction_id, transfer_id, &name, &bytes)
+    })
+}
+
+/// Returns the file's raw bytes.
+#[tauri::command]
+pub fn get_attachment_data(state: State<DbState>, id: i64) -> Result<Response, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let data: Vec<u8> = conn
+        .query_row("SELECT data FROM attachments WHERE id = ?1", [id], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    Ok(Response::new(data))
+}
+
+/// Returns the PNG thumbnail of an image attachment.
+#[tauri::command]
+pub fn get_attachment_thumbnail(state: State<DbState>, id: i64) -> Result<Response, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let thumbnail: Option<Vec<u8>> = conn
+        .query_row("SELECT thumbnail FROM attachments WHERE id = ?1", [id], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    thumbnail
+        .map(Response::new)
+        .ok_or_else(|| "This attachment has no thumbnail".to_string())
+}
+
+/// Writes a decrypted copy of the attachment to a location the user picks.
+#[tauri::command]
+pub async fn save_attachment(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    id: i64,
+) -> Result<(), String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let (name, data): (String, Vec<u8>) = {
+        let guard = state.0.lock().map_err(|e| e.to_string())?;
+        let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+        conn.query_row(
+            "SELECT file_name, data FROM attachments WHERE id = ?1",
+            [id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?
+    };
+
+    let path = app
+        .dialog()
+        .file()
+        .set_file_name(&name)
+        .blocking_save_file();
+
+    if let Some(file_path) = path {
+        std::fs::write(file_path.to_string(), data).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_attachment(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM attachments WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn attachments_are_validated_and_follow_their_transaction() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Cash', 'cash');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 5000, '2025-01-05');",
+        )
+        .unwrap();
+
+        let receipt = insert_attachment(&conn, Some(1), None, "receipt.pdf", b"%PDF-1.7\n...").unwrap();
+        assert_eq!(receipt.mime_type, "application/pdf");
+        assert!(!receipt.has_thumbnail);
+
+        assert!(insert_attachment(&conn, Some(1), None, "notes.txt", b"plain text").is_err());
+        assert!(insert_attachment(&conn, None, None, "receipt.pdf", b"%PDF-1.7\n").is_err());
+        let too_big = vec![0u8; MAX_ATTACHMENT_BYTES as usize + 1];
+        assert!(insert_attachment(&conn, Some(1), None, "huge.pdf", &too_big).is_err());
+
+        conn.execute("DELETE FROM transactions WHERE id = 1", []).unwrap();
+        let left: i64 = conn
+            .query_row("SELECT COUNT(*) FROM attachments", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(left, 0);
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::{HashMap, HashSet};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::categories::{category_tree, path_to, subtree};
+use crate::commands::currencies::base_currency;
+use crate::commands::reports::{category_spending, BaseConverter};
+use crate::models::{Budget, BudgetAlert, BudgetLine, BudgetStatus, Money};
+
+/// Share of the available budget at which an alert is raised.
+pub const ALERT_THRESHOLDS: [i64; 2] = [80, 100];
+
+/// Furthest back a rollover chain is followed, in months.
+const MAX_ROLLOVER_MONTHS: usize = 120;
+
+const BUDGET_SELECT: &str = "SELECT b.id, b.category_id, c.name, b.period, b.amount, b.currency, b.rollover,
+         b.created_at, b.updated_at
+         FROM budgets b
+         JOIN categories c ON c.id = b.category_id";
+
+fn row_to_budget(row: &rusqlite::Row) -> rusqlite::Result<Budget> {
+    Ok(Budget {
+        id: row.get(0)?,
+        category_id: row.get(1)?,
+        category_name: row.get(2)?,
+        period: row.get(3)?,
+        amount: Money::new(row.get(4)?, row.get::<_, String>(5)?),
+        rollover: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+pub(crate) fn period(year: i64, month: i64) -> Result<String, String> {
+    if !(1..=12).contains(&month) {
+        return Err("Months run from 1 to 12".into());
+    }
+    Ok(format!("{:04}-{:02}", year, month))
+}
+
+pub(crate) fn previous_period(period: &str) -> String {
+    let year: i64 = period[0..4].parse().unwrap_or(0);
+    let month: i64 = period[5..7].parse().unwrap_or(1);
+    if month == 1 {
+        format!("{:04}-12", year - 1)
+    } else {
+        format!("{:04}-{:02}", year, month - 1)
+    }
+}
+
+pub(crate) fn next_period(period: &str) -> String {
+    let year: i64 = period[0..4].parse().unwrap_or(0);
+    let month: i64 = period[5..7].parse().unwrap_or(1);
+    if month == 12 {
+        format!("{:04}-01", year + 1)
+    } else {
+        format!("{:04}-{:02}", year, month + 1)
+    }
+}
+
+fn budgets_for(conn: &Connection, period: &str) -> Result<Vec<Budget>, String> {
+    let mut stmt = conn
+        .prepare(&format!("{BUDGET_SELECT} WHERE b.period = ?1 ORDER BY c.name"))
+        .map_err(|e| e.to_string())?;
+    let budgets = stmt
+        .query_map([period], |row| row_to_budget(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(budgets)
+}
+
+fn find_budget(conn: &Connection, category_id: i64, period: &str) -> Result<Option<Budget>, String> {
+    conn.query_row(
+        &format!("{BUDGET_SELECT} WHERE b.category_id = ?1 AND b.period = ?2"),
+        rusqlite::params![category_id, period],
+        |row| row_to_budget(row),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub(crate) fn set_budget_amount(
+    conn: &Connection,
+    category_id: i64,
+    period: &str,
+    amount: &Money,
+    rollover: bool,
+) -> Result<Budget, String> {
+    let currency = base_currency(conn)?;
+    amount.require_currency(&currency)?;
+    if amount.minor < 0 {
+        return Err("Budgets cannot be negative".into());
+    }
+    let direction: Option<String> = conn
+        .query_row("SELECT direction FROM categories WHERE id = ?1", [category_id], |r| r.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match direction.as_deref() {
+        None => return Err("Category not found".into()),
+        Some("income") => return Err("Budgets are for expense categories".into()),
+        Some(_) => {}
+    }
+    conn.execute(
+        "INSERT INTO budgets (category_id, period, amount, currency, rollover) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(category_id, period) DO UPDATE SET
+           amount = excluded.amount, currency = excluded.currency, rollover = excluded.rollover,
+           updated_at = datetime('now')",
+        rusqlite::params![category_id, period, amount.minor, currency, rollover],
+    )
+    .map_err(|e| e.to_string())?;
+    find_budget(conn, category_id, period)?.ok_or_else(|| "Budget was not saved".to_string())
+}
+
+/// Copies every budget from one month to another. Budgets already set in
+/// the target month are kept unless `overwrite` is set. Returns how many
+/// were written.
+pub(crate) fn copy_period(conn: &Connection, from: &str, to: &str, overwrite: bool) -> Result<i64, String> {
+    if from == to {
+        return Err("Pick a different month to copy to".into());
+    }
+    let conflict = if overwrite {
+        "DO UPDATE SET amount = excluded.amount, currency = excluded.currency, rollover = excluded.rollover,
+           updated_at = datetime('now')"
+    } else {
+        "DO NOTHING"
+    };
+    let copied = conn
+        .execute(
+            &format!(
+                "INSERT INTO budgets (category_id, period, amount, currency, rollover)
+                 SELECT category_id, ?2, amount, currency, rollover FROM budgets WHERE period = ?1
+                 ON CONFLICT(category_id, period) {conflict}"
+            ),
+            [from, to],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(copied as i64)
+}
+
+/// Expense per category for a month, from the same aggregation as the
+/// spending breakdown, remembered per month. A budget on a parent category
+/// covers its subcategories too.
+struct SpendingByMonth<'a> {
+    conn: &'a Connection,
+    fx: BaseConverter,
+    months: HashMap<String, HashMap<i64, i64>>,
+    subtrees: HashMap<i64, Vec<i64>>,
+}
+
+impl SpendingByMonth<'_> {
+    fn spent(&mut self, period: &str, category_id: i64) -> Result<i64, String> {
+        if !self.months.contains_key(period) {
+            let totals = category_spending(self.conn, &mut self.fx, period)?
+                .into_iter()
+                .filter_map(|spend| spend.category_id.map(|id| (id, spend.amount.minor)))
+                .collect();
+            self.months.insert(period.to_string(), totals);
+        }
+        if !self.subtrees.contains_key(&category_id) {
+            self.subtrees.insert(category_id, subtree(self.conn, category_id)?);
+        }
+        let totals = &self.months[period];
+        Ok(self.subtrees[&category_id].iter().filter_map(|id| totals.get(id)).sum())
+    }
+
+    /// The budget's amount in the base currency, at the rate on the first of
+    /// its month.
+    fn budgeted(&mut self, budget: &Budget) -> i64 {
+        let first_day = format!("{}-01", budget.period);
+        self.fx.convert(budget.amount.minor, &budget.amount.currency, &first_day)
+    }
+}
+
+/// What a rolling-over budget brings into `period`: the previous month's
+/// budget less its spending, plus whatever that month carried in turn. The
+/// chain stops at a month without a budget or one that does not roll over.
+fn carried_into(spending: &mut SpendingByMonth, budget: &Budget) -> Result<i64, String> {
+    if !budget.rollover {
+        return Ok(0);
+    }
+    let mut chain = Vec::new();
+    let mut period = budget.period.clone();
+    while chain.len() < MAX_ROLLOVER_MONTHS {
+        period = previous_period(&period);
+        let Some(previous) = find_budget(spending.conn, budget.category_id, &period)? else {
+            break;
+        };
+        let rolls = previous.rollover;
+        chain.push(previous);
+        if !rolls {
+            break;
+        }
+    }
+    let mut carried = 0;
+    for previous in chain.iter().rev() {
+        carried += spending.budgeted(previous) - spending.spent(&previous.period, budget.category_id)?;
+    }
+    Ok(carried)
+}
+
+/// Spending against each budget of `year`-`month`, with `today` deciding how
+/// much of the month has gone by for the end-of-month projection.
+pub(crate) fn budget_status(conn: &Connection, year: i64, month: i64, today: &str) -> Result<BudgetStatus, String> {
+    let period = period(year, month)?;
+    let days_in_month: i64 = conn
+        .query_row(
+            "SELECT CAST(strftime('%d', DATE(?1 || '-01', '+1 month', '-1 day')) AS INTEGER)",
+            [&period],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let days_elapsed = match today[0..7].cmp(period.as_str()) {
+        std::cmp::Ordering::Greater => days_in_month,
+        std::cmp::Ordering::Equal => today[8..10].parse().unwrap_or(days_in_month),
+        std::cmp::Ordering::Less => 0,
+    };
+
+    let mut spending = SpendingByMonth {
+        conn,
+        fx: BaseConverter::load(conn)?,
+        months: HashMap::new(),
+        subtrees: HashMap::new(),
+    };
+    let mut lines = Vec::new();
+    let mut alerts = Vec::new();
+    for budget in budgets_for(conn, &period)? {
+        let carried = carried_into(&mut spending, &budget)?;
+        let budgeted = spending.budgeted(&budget);
+        let available = budgeted + carried;
+        let spent = spending.spent(&period, budget.category_id)?;
+        let percent_used = (available > 0).then(|| spent as f64 * 100.0 / available as f64);
+        let projected = if days_elapsed > 0 {
+            (spent as f64 * days_in_month as f64 / days_elapsed as f64).round() as i64
+        } else {
+            spent
+        };
+        let money = |minor: i64| spending.fx.money(minor);
+
+        let crossed = ALERT_THRESHOLDS.iter().rev().copied().find(|&threshold| match percent_used {
+            Some(percent) => percent >= threshold as f64,
+            None => spent > 0,
+        });
+        if let Some(threshold) = crossed {
+            alerts.push(BudgetAlert {
+                budget_id: budget.id,
+                category_id: budget.category_id,
+                category_name: budget.category_name.clone(),
+                threshold,
+                percent_used,
+                spent: money(spent),
+                available: money(available),
+            });
+        }
+        lines.push(BudgetLine {
+            budget_id: budget.id,
+            category_id: budget.category_id,
+            category_name: budget.category_name,
+            amount: money(budgeted),
+            carried_over: money(carried),
+            available: money(available),
+            spent: money(spent),
+            remaining: money(available - spent),
+            percent_used,
+            projected_spend: money(projected),
+            projected_overspend: money((projected - available).max(0)),
+        });
+    }
+    spending.fx.require_complete()?;
+    alerts.sort_by(|a, b| {
+        b.threshold
+            .cmp(&a.threshold)
+            .then(b.percent_used.unwrap_or(f64::MAX).total_cmp(&a.percent_used.unwrap_or(f64::MAX)))
+    });
+
+    // A subcategory's budget is part of its budgeted parent's, so the totals
+    // only count the top-most budget on each branch.
+    let budgeted: HashSet<i64> = lines.iter().map(|line| line.category_id).collect();
+    let tree = category_tree(conn)?;
+    let top_most: Vec<&BudgetLine> = lines
+        .iter()
+        .filter(|line| {
+            let path = path_to(&tree, line.category_id);
+            !path[..path.len() - 1].iter().any(|id| budgeted.contains(id))
+        })
+        .collect();
+
+    Ok(BudgetStatus {
+        year,
+        month,
+        days_in_month,
+        days_elapsed,
+        total_available: spending.fx.money(top_most.iter().map(|line| line.available.minor).sum()),
+        total_spent: spending.fx.money(top_most.iter().map(|line| line.spent.minor).sum()),
+        categories: lines,
+        alerts,
+    })
+}
+
+#[tauri::command]
+pub fn list_budgets(state: State<DbState>, year: i64, month: i64) -> Result<Vec<Budget>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    budgets_for(conn, &period(year, month)?)
+}
+
+/// Sets a category's budget for one month, replacing any already set.
+#[tauri::command]
+pub fn set_budget(
+    state: State<DbState>,
+    category_id: i64,
+    year: i64,
+    month: i64,
+    amount: Money,
+    rollover: Option<bool>,
+) -> Result<Budget, String> {
+    with_transaction(&state, |conn| {
+        set_budget_amount(conn, category_id, &period(year, month)?, &amount, rollover.unwrap_or(false))
+    })
+}
+
+#[tauri::command]
+pub fn delete_budget(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM budgets WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn copy_budgets(
+    state: State<DbState>,
+    from_year: i64,
+    from_month: i64,
+    to_year: i64,
+    to_month: i64,
+    overwrite: Option<bool>,
+) -> Result<i64, String> {
+    with_transaction(&state, |conn| {
+        copy_period(conn, &period(from_year, from_month)?, &period(to_year, to_month)?, overwrite.unwrap_or(false))
+    })
+}
+
+#[tauri::command]
+pub fn get_budget_status(state: State<DbState>, year: i64, month: i64) -> Result<BudgetStatus, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let today: String = conn
+        .query_row("SELECT DATE('now')", [], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    budget_status(conn, year, month, &today)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn status_rolls_over_projects_and_alerts() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food', 'expense');
+             INSERT INTO categories (name, direction) VALUES ('Transport', 'expense');
+             INSERT INTO categories (name, direction) VALUES ('Salary', 'income');
+             INSERT INTO accounts (name, type, opening_balance) VALUES ('Bank', 'debit', 100000000);
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 1, 'expense', 800000, '2025-01-10');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 1, 'expense', 900000, '2025-02-05');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 2, 'expense', 150000, '2025-02-08');",
+        )
+        .unwrap();
+        let php = |minor: i64| Money::new(minor, "PHP");
+        assert!(set_budget_amount(&conn, 3, "2025-01", &php(100), false).is_err());
+        set_budget_amount(&conn, 1, "2025-01", &php(1_000_000), false).unwrap();
+        set_budget_amount(&conn, 2, "2025-01", &php(500_000), false).unwrap();
+
+        assert_eq!(copy_period(&conn, "2025-01", "2025-02", false).unwrap(), 2);
+        set_budget_amount(&conn, 1, "2025-02", &php(1_000_000), true).unwrap();
+        assert_eq!(copy_period(&conn, "2025-01", "2025-02", false).unwrap(), 0);
+
+        // January left ₱2,000.00 of the food budget, so February has ₱12,000.00.
+        let status = budget_status(&conn, 2025, 2, "2025-02-14").unwrap();
+        let food = &status.categories[0];
+        assert_eq!(food.category_name, "Food");
+        assert_eq!(food.carried_over.minor, 200_000);
+        assert_eq!(food.available.minor, 1_200_000);
+        assert_eq!(food.spent.minor, 900_000);
+        assert_eq!(food.percent_used, Some(75.0));
+        // ₱9,000.00 in 14 days is on pace for ₱18,000.00 by the 28th.
+        assert_eq!(food.projected_spend.minor, 1_800_000);
+        assert_eq!(food.projected_overspend.minor, 600_000);
+        assert!(status.alerts.is_empty());
+
+        let january = budget_status(&conn, 2025, 1, "2025-02-14").unwrap();
+        assert_eq!(january.days_elapsed, 31);
+        assert_eq!(january.alerts.len(), 1);
+        assert_eq!(january.alerts[0].category_name, "Food");
+        assert_eq!(january.alerts[0].threshold, 80);
+
+        conn.execute(
+            "INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 1, 'expense', 400000, '2025-02-20')",
+            [],
+        )
+        .unwrap();
+        let status = budget_status(&conn, 2025, 2, "2025-02-20").unwrap();
+        assert_eq!(status.alerts[0].threshold, 100);
+        assert_eq!(status.total_spent.minor, 1_450_000);
+    }
+
+    #[test]
+    fn budgets_convert_when_the_base_currency_changes() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food', 'expense');
+             INSERT INTO exchange_rates (base, quote, rate, date) VALUES ('PHP', 'JPY', 2.5, '2025-01-01');",
+        )
+        .unwrap();
+        set_budget_amount(&conn, 1, "2025-03", &Money::new(500_000, "PHP"), false).unwrap();
+        conn.execute("INSERT INTO app_settings (key, value) VALUES ('base_currency', 'JPY')", [])
+            .unwrap();
+
+        // ₱5,000.00 is ¥12,500, not ¥500,000.
+        let budget = &budgets_for(&conn, "2025-03").unwrap()[0];
+        assert_eq!(budget.amount, Money::new(500_000, "PHP"));
+        let status = budget_status(&conn, 2025, 3, "2025-03-10").unwrap();
+        assert_eq!(status.categories[0].available, Money::new(12_500, "JPY"));
+        assert!(set_budget_amount(&conn, 1, "2025-03", &Money::new(500_000, "PHP"), false).is_err());
+    }
+
+    #[test]
+    fn a_parent_budget_counts_its_subcategories() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food & Dining', 'expense');
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Groceries', 'expense', 1);
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Coffee', 'expense', 2);
+             INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 1, 'expense', 50000, '2025-03-01');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 2, 'expense', 300000, '2025-03-02');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 3, 'expense', 30000, '2025-03-06');",
+        )
+        .unwrap();
+        set_budget_amount(&conn, 1, "2025-03", &Money::new(500_000, "PHP"), false).unwrap();
+        set_budget_amount(&conn, 2, "2025-03", &Money::new(300_000, "PHP"), false).unwrap();
+
+        let status = budget_status(&conn, 2025, 3, "2025-03-31").unwrap();
+        assert_eq!(status.categories[0].category_name, "Food & Dining");
+        assert_eq!(status.categories[0].spent.minor, 380_000);
+        assert_eq!(status.categories[1].spent.minor, 330_000);
+        assert_eq!(status.alerts[0].category_name, "Groceries");
+        // Groceries is part of Food & Dining, so nothing is counted twice.
+        assert_eq!(status.total_spent.minor, 380_000);
+        assert_eq!(status.total_available.minor, 500_000);
+    }
+}
@@ -1,7 +1,12 @@
+use std::collections::{HashMap, HashSet};
+use rusqlite::{Connection, OptionalExtension};
 use tauri::State;
 use crate::db::DbState;
 use crate::models::Category;
 
+const CATEGORY_SELECT: &str = "SELECT id, name, direction, icon, is_archived, created_at, parent_id
+         FROM categories";
+
 fn row_to_category(row: &rusqlite::Row) -> rusqlite::Result<Category> {
     Ok(Category {
         id: row.get(0)?,
@@ -10,18 +15,103 @@ fn row_to_category(row: &rusqlite::Row) -> rusqlite::Result<Category> {
         icon: row.get(3)?,
         is_archived: row.get(4)?,
         created_at: row.get(5)?,
+        parent_id: row.get(6)?,
     })
 }
 
+fn find_category(conn: &Connection, id: i64) -> Result<Option<Category>, String> {
+    conn.query_row(&format!("{CATEGORY_SELECT} WHERE id = ?1"), [id], |row| row_to_category(row))
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+/// A child has its parent's direction, or any direction under a 'both'
+/// parent.
+fn directions_nest(parent: &str, child: &str) -> bool {
+    parent == "both" || parent == child
+}
+
+/// Checks that category `id` (None for one not created yet) with `direction`
+/// may sit under `parent_id`: the parent exists, fits the direction and is
+/// not the category itself or one of its descendants.
+fn check_parent(conn: &Connection, id: Option<i64>, direction: &str, parent_id: i64) -> Result<(), String> {
+    let parent = find_category(conn, parent_id)?.ok_or_else(|| "Parent category not found".to_string())?;
+    if !directions_nest(&parent.direction, direction) {
+        return Err(format!("A {} category cannot sit under {} ({})", direction, parent.name, parent.direction));
+    }
+    if let Some(id) = id {
+        if subtree(conn, id)?.contains(&parent_id) {
+            return Err("A category cannot sit under itself or one of its subcategories".into());
+        }
+    }
+    Ok(())
+}
+
+/// `id` followed by every category nested under it, at any depth.
+pub(crate) fn subtree(conn: &Connection, id: i64) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "WITH RECURSIVE tree(id) AS (
+               SELECT ?1
+               UNION
+               SELECT c.id FROM categories c JOIN tree ON c.parent_id = tree.id
+             )
+             SELECT id FROM tree",
+        )
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+/// Name and parent of every category, for walking the tree in memory.
+pub(crate) fn category_tree(conn: &Connection) -> Result<HashMap<i64, (String, Option<i64>)>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, parent_id FROM categories")
+        .map_err(|e| e.to_string())?;
+    let tree = stmt
+        .query_map([], |r| Ok((r.get(0)?, (r.get(1)?, r.get(2)?))))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(tree)
+}
+
+/// The category's ancestors from the top level down, ending with itself.
+pub(crate) fn path_to(tree: &HashMap<i64, (String, Option<i64>)>, id: i64) -> Vec<i64> {
+    let mut path = vec![id];
+    let mut seen = HashSet::from([id]);
+    while let Some(parent) = tree.get(path.last().unwrap()).and_then(|(_, parent)| *parent) {
+        if !seen.insert(parent) {
+            break;
+        }
+        path.push(parent);
+    }
+    path.reverse();
+    path
+}
+
+/// Moves a category under `parent_id`, or to the top level when `None`.
+/// Its subcategories move with it.
+pub(crate) fn set_parent(conn: &Connection, id: i64, parent_id: Option<i64>) -> Result<Category, String> {
+    let category = find_category(conn, id)?.ok_or_else(|| "Category not found".to_string())?;
+    if let Some(parent_id) = parent_id {
+        check_parent(conn, Some(id), &category.direction, parent_id)?;
+    }
+    conn.execute("UPDATE categories SET parent_id = ?1 WHERE id = ?2", rusqlite::params![parent_id, id])
+        .map_err(|e| e.to_string())?;
+    find_category(conn, id)?.ok_or_else(|| "Category not found".to_string())
+}
+
 #[tauri::command]
 pub fn list_categories(state: State<DbState>) -> Result<Vec<Category>, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, direction, icon, is_archived, created_at
-             FROM categories ORDER BY direction, name",
-        )
+        .prepare(&format!("{CATEGORY_SELECT} ORDER BY direction, name"))
         .map_err(|e| e.to_string())?;
 
     let categories = stmt
@@ -39,22 +129,21 @@ pub fn create_category(
     name: String,
     direction: String,
     icon: Option<String>,
+    parent_id: Option<i64>,
 ) -> Result<Category, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    if let Some(parent_id) = parent_id {
+        check_parent(conn, None, &direction, parent_id)?;
+    }
     conn.execute(
-        "INSERT INTO categories (name, direction, icon) VALUES (?1, ?2, ?3)",
-        rusqlite::params![name, direction, icon],
+        "INSERT INTO categories (name, direction, icon, parent_id) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![name, direction, icon, parent_id],
     )
     .map_err(|e| e.to_string())?;
 
     let id = conn.last_insert_rowid();
-    conn.query_row(
-        "SELECT id, name, direction, icon, is_archived, created_at FROM categories WHERE id = ?1",
-        [id],
-        |row| row_to_category(row),
-    )
-    .map_err(|e| e.to_string())
+    find_category(conn, id)?.ok_or_else(|| "Category not found".to_string())
 }
 
 #[tauri::command]
@@ -67,18 +156,37 @@ pub fn update_category(
 ) -> Result<Category, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let current = find_category(conn, id)?.ok_or_else(|| "Category not found".to_string())?;
+    if let Some(parent_id) = current.parent_id {
+        check_parent(conn, Some(id), &direction, parent_id)?;
+    }
+    let mut stmt = conn
+        .prepare("SELECT direction FROM categories WHERE parent_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let children: Vec<String> = stmt
+        .query_map([id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    if children.iter().any(|child| !directions_nest(&direction, child)) {
+        return Err("Its subcategories would no longer fit that direction".into());
+    }
     conn.execute(
         "UPDATE categories SET name=?1, direction=?2, icon=?3 WHERE id=?4",
         rusqlite::params![name, direction, icon, id],
     )
     .map_err(|e| e.to_string())?;
 
-    conn.query_row(
-        "SELECT id, name, direction, icon, is_archived, created_at FROM categories WHERE id = ?1",
-        [id],
-        |row| row_to_category(row),
-    )
-    .map_err(|e| e.to_string())
+    find_category(conn, id)?.ok_or_else(|| "Category not found".to_string())
+}
+
+/// Moves a category (with its subcategories) under another, or to the top
+/// level when `parent_id` is omitted.
+#[tauri::command]
+pub fn move_category(state: State<DbState>, id: i64, parent_id: Option<i64>) -> Result<Category, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    set_parent(conn, id, parent_id)
 }
 
 #[tauri::command]
@@ -104,3 +212,33 @@ pub fn restore_category(state: State<DbState>, id: i64) -> Result<(), String> {
     .map_err(|e| e.to_string())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{open_test_db, seed_categories};
+
+    #[test]
+    fn moves_refuse_cycles_and_mismatched_directions() {
+        let conn = open_test_db();
+        seed_categories(&conn).unwrap();
+        let id = |name: &str| -> i64 {
+            conn.query_row("SELECT id FROM categories WHERE name = ?1", [name], |r| r.get(0)).unwrap()
+        };
+        let (food, groceries, restaurants) = (id("Food & Dining"), id("Groceries"), id("Restaurants"));
+        assert_eq!(subtree(&conn, food).unwrap().len(), 4);
+
+        assert!(set_parent(&conn, food, Some(groceries)).is_err());
+        assert!(set_parent(&conn, food, Some(food)).is_err());
+        assert!(set_parent(&conn, groceries, Some(id("Salary"))).is_err());
+
+        let coffee = set_parent(&conn, id("Coffee"), Some(restaurants)).unwrap();
+        assert_eq!(coffee.parent_id, Some(restaurants));
+        assert!(set_parent(&conn, restaurants, Some(coffee.id)).is_err());
+        assert_eq!(path_to(&category_tree(&conn).unwrap(), coffee.id), vec![food, restaurants, coffee.id]);
+
+        let top = set_parent(&conn, restaurants, None).unwrap();
+        assert_eq!(top.parent_id, None);
+        assert_eq!(subtree(&conn, food).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,128 @@
+use rusqlite::Connection;
+use tauri::State;
+use crate::db::DbState;
+use crate::commands::accounts::{row_to_account, ACCOUNT_SELECT};
+use crate::commands::installments::unbilled_principal;
+use crate::logic::balance::compute_balance;
+use crate::logic::credit_cards::{card_statements, next_closing_date};
+use crate::models::{Account, CardPaymentDue, CreditCardSummary, Money};
+
+fn today(conn: &Connection) -> Result<String, String> {
+    conn.query_row("SELECT DATE('now')", [], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+fn days_between(conn: &Connection, from: &str, to: &str) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT CAST(julianday(?2) - julianday(?1) AS INTEGER)",
+        [from, to],
+        |r| r.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+pub(crate) fn card_summary(
+    conn: &Connection,
+    account: &Account,
+    today: &str,
+    cycles: i64,
+) -> Result<CreditCardSummary, String> {
+    if account.account_type != "credit" {
+        return Err(format!("{} is not a credit card", account.name));
+    }
+    let statements = card_statements(conn, account, today, cycles)?;
+    let balance = compute_balance(conn, account, None);
+    let installment_principal = unbilled_principal(conn, account.id)?;
+    let used = balance.minor + installment_principal;
+    let available_credit = account
+        .credit_limit
+        .as_ref()
+        .map(|limit| Money::new(limit.minor - used, limit.currency.clone()));
+    let utilization = account
+        .credit_limit
+        .as_ref()
+        .filter(|limit| limit.minor > 0)
+        .map(|limit| used as f64 / limit.minor as f64 * 100.0);
+    Ok(CreditCardSummary {
+        account_id: account.id,
+        next_closing_date: next_closing_date(conn, account.billing_cycle_day.unwrap_or(1), today)?,
+        balance,
+        credit_limit: account.credit_limit.clone(),
+        available_credit,
+        installment_principal: Money::new(installment_principal, account.currency.clone()),
+        utilization,
+        statements,
+    })
+}
+
+/// Balance, available credit, utilization and the last `cycles` statements
+/// (default 6) of a credit card.
+#[tauri::command]
+pub fn get_credit_card_summary(
+    state: State<DbState>,
+    account_id: i64,
+    cycles: Option<i64>,
+) -> Result<CreditCardSummary, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let account = conn
+        .query_row(
+            &format!("{ACCOUNT_SELECT} WHERE id = ?1"),
+            [account_id],
+            |row| row_to_account(row),
+        )
+        .map_err(|e| e.to_string())?;
+    card_summary(conn, &account, &today(conn)?, cycles.unwrap_or(6).clamp(1, 36))
+}
+
+/// Cards whose latest statement still has money owed and is overdue, due
+/// within `days_ahead` days (default 30), or follows an overdue statement.
+/// Soonest first. Only active cards with a billing cycle day are considered.
+#[tauri::command]
+pub fn list_card_payments_due(
+    state: State<DbState>,
+    days_ahead: Option<i64>,
+) -> Result<Vec<CardPaymentDue>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let today = today(conn)?;
+    let days_ahead = days_ahead.unwrap_or(30);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "{ACCOUNT_SELECT} WHERE type = 'credit' AND is_active = 1 AND billing_cycle_day IS NOT NULL
+             ORDER BY name"
+        ))
+        .map_err(|e| e.to_string())?;
+    let cards: Vec<Account> = stmt
+        .query_map([], |row| row_to_account(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut due = Vec::new();
+    for card in cards {
+        let mut statements = card_statements(conn, &card, &today, 2)?.into_iter();
+        let Some(latest) = statements.next() else { continue };
+        if latest.amount_remaining.minor <= 0 {
+            continue;
+        }
+        let past_due = statements
+            .next()
+            .filter(|previous| previous.status == "overdue")
+            .map(|previous| previous.amount_remaining)
+            .unwrap_or_else(|| Money::new(0, card.currency.clone()));
+        let days_until_due = days_between(conn, &today, &latest.due_date)?;
+        if days_until_due <= days_ahead || past_due.minor > 0 {
+            due.push(CardPaymentDue {
+                account_id: card.id,
+                account_name: card.name.clone(),
+                statement: latest,
+                past_due,
+                days_until_due,
+            });
+        }
+    }
+    due.sort_by(|a, b| a.days_until_due.cmp(&b.days_until_due).then(a.account_name.cmp(&b.account_name)));
+    Ok(due)
+}
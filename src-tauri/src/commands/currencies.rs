@@ -0,0 +1,160 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::imports::read_import_file;
+use crate::logic::csv_import::parse_date;
+use crate::logic::fx::parse_rates_csv;
+use crate::models::{normalize_currency, ExchangeRate, DEFAULT_CURRENCY};
+
+const RATE_SELECT: &str = "SELECT id, base, quote, rate, date, source, created_at FROM exchange_rates";
+
+fn row_to_rate(row: &rusqlite::Row) -> rusqlite::Result<ExchangeRate> {
+    Ok(ExchangeRate {
+        id: row.get(0)?,
+        base: row.get(1)?,
+        quote: row.get(2)?,
+        rate: row.get(3)?,
+        date: row.get(4)?,
+        source: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+/// Currency that totals spanning accounts are reported in.
+pub(crate) fn base_currency(conn: &Connection) -> Result<String, String> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM app_settings WHERE key = 'base_currency'",
+            [],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(value.unwrap_or_else(|| DEFAULT_CURRENCY.to_string()))
+}
+
+/// Stores the rate for `base`/`quote` on `date`, replacing any rate already
+/// entered for that day.
+fn upsert_rate(
+    conn: &Connection,
+    base: &str,
+    quote: &str,
+    rate: f64,
+    date: &str,
+    source: &str,
+) -> Result<i64, String> {
+    if base == quote {
+        return Err("Pick two different currencies".into());
+    }
+    if !(rate.is_finite() && rate > 0.0) {
+        return Err("Rates must be positive".into());
+    }
+    conn.execute(
+        "INSERT INTO exchange_rates (base, quote, rate, date, source) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (base, quote, date) DO UPDATE SET rate = excluded.rate, source = excluded.source",
+        rusqlite::params![base, quote, rate, date, source],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT id FROM exchange_rates WHERE base = ?1 AND quote = ?2 AND date = ?3",
+        rusqlite::params![base, quote, date],
+        |r| r.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_base_currency(state: State<DbState>) -> Result<String, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    base_currency(conn)
+}
+
+#[tauri::command]
+pub fn set_base_currency(state: State<DbState>, currency: String) -> Result<String, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let currency = normalize_currency(&currency)?;
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES ('base_currency', ?1)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        [&currency],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(currency)
+}
+
+/// Lists rates, newest first, optionally for one pair.
+#[tauri::command]
+pub fn list_exchange_rates(
+    state: State<DbState>,
+    base: Option<String>,
+    quote: Option<String>,
+) -> Result<Vec<ExchangeRate>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let base = base.as_deref().map(normalize_currency).transpose()?;
+    let quote = quote.as_deref().map(normalize_currency).transpose()?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{RATE_SELECT} WHERE (?1 IS NULL OR base = ?1) AND (?2 IS NULL OR quote = ?2)
+             ORDER BY date DESC, base, quote"
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let rates = stmt
+        .query_map(rusqlite::params![base, quote], |row| row_to_rate(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(rates)
+}
+
+/// Records that one `base` was worth `rate` `quote` from `date` on.
+#[tauri::command]
+pub fn set_exchange_rate(
+    state: State<DbState>,
+    base: String,
+    quote: String,
+    rate: f64,
+    date: String,
+) -> Result<ExchangeRate, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let id = upsert_rate(
+        conn,
+        &normalize_currency(&base)?,
+        &normalize_currency(&quote)?,
+        rate,
+        &parse_date(&date, "YYYY-MM-DD")?,
+        "manual",
+    )?;
+    conn.query_row(&format!("{RATE_SELECT} WHERE id = ?1"), [id], |row| row_to_rate(row))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_exchange_rate(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM exchange_rates WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Imports `date,base,quote,rate` rows from a CSV file. Rates already stored
+/// for the same pair and day are replaced. Returns the number of rows read.
+#[tauri::command]
+pub fn import_exchange_rates_csv(state: State<DbState>, file_path: String) -> Result<i64, String> {
+    let rows = parse_rates_csv(&read_import_file(&file_path)?)?;
+    if rows.is_empty() {
+        return Err("The file has no exchange rates".into());
+    }
+    with_transaction(&state, |conn| {
+        for row in &rows {
+            upsert_rate(conn, &row.base, &row.quote, row.rate, &row.date, "csv")?;
+        }
+        Ok(rows.len() as i64)
+    })
+}
@@ -1,5 +1,6 @@
 use tauri::{AppHandle, Manager, State};
-use crate::db::DbState;
+use crate::db::{seed_categories, with_transaction, DbState};
+use crate::models::{Money, DEFAULT_CURRENCY};
 
 #[tauri::command]
 pub async fn export_transactions_csv(
@@ -13,33 +14,54 @@ pub async fn export_transactions_csv(
         let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
         let mut stmt = conn
             .prepare(
-                "SELECT t.id, a.name, c.name, t.type, t.amount, t.date, t.notes, t.is_recurring
+                "SELECT t.id, s.id, a.name, c.name, t.type,
+                 CASE WHEN s.id IS NULL THEN t.amount ELSE s.amount END,
+                 t.date, t.notes, s.memo, t.is_recurring, a.currency,
+                 (SELECT GROUP_CONCAT(tg.name, ';') FROM transaction_tags tt
+                  JOIN tags tg ON tg.id = tt.tag_id WHERE tt.transaction_id = t.id),
+                 p.name
                  FROM transactions t
+                 LEFT JOIN transaction_splits s ON s.transaction_id = t.id
                  LEFT JOIN accounts a ON t.account_id = a.id
-                 LEFT JOIN categories c ON t.category_id = c.id
-                 ORDER BY t.date DESC",
+                 LEFT JOIN payees p ON t.payee_id = p.id
+                 LEFT JOIN categories c
+                   ON c.id = CASE WHEN s.id IS NULL THEN t.category_id ELSE s.category_id END
+                 ORDER BY t.date DESC, t.id, s.id",
             )
             .map_err(|e| e.to_string())?;
 
+        // Split transactions produce one row per split line, sharing the parent id.
         let rows: Vec<String> = stmt
             .query_map([], |row| {
                 let id: i64 = row.get(0)?;
-                let account: Option<String> = row.get(1)?;
-                let category: Option<String> = row.get(2)?;
-                let tx_type: String = row.get(3)?;
-                let amount: f64 = row.get(4)?;
-                let date: String = row.get(5)?;
-                let notes: Option<String> = row.get(6)?;
-                let recurring: i64 = row.get(7)?;
+                let split_id: Option<i64> = row.get(1)?;
+                let account: Option<String> = row.get(2)?;
+                let category: Option<String> = row.get(3)?;
+                let tx_type: String = row.get(4)?;
+                let amount = Money::new(
+                    row.get(5)?,
+                    row.get::<_, Option<String>>(10)?.unwrap_or_else(|| DEFAULT_CURRENCY.to_string()),
+                );
+                let date: String = row.get(6)?;
+                let notes: Option<String> = row.get(7)?;
+                let memo: Option<String> = row.get(8)?;
+                let recurring: i64 = row.get(9)?;
+                let tags: Option<String> = row.get(11)?;
+                let payee: Option<String> = row.get(12)?;
                 Ok(format!(
-                    "{},{},{},{},{:.2},{},{},{}",
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{}",
                     id,
+                    split_id.map(|s| s.to_string()).unwrap_or_default(),
                     account.unwrap_or_default(),
+                    payee.unwrap_or_default().replace(',', ";"),
                     category.unwrap_or_default(),
                     tx_type,
-                    amount,
+                    amount.to_decimal_string(),
+                    amount.currency,
                     date,
                     notes.unwrap_or_default().replace(',', ";"),
+                    memo.unwrap_or_default().replace(',', ";"),
+                    tags.unwrap_or_default().replace(',', ";"),
                     recurring
                 ))
             })
@@ -49,7 +71,7 @@ pub async fn export_transactions_csv(
         rows
     };
 
-    let header = "id,account,category,type,amount,date,notes,is_recurring".to_string();
+    let header = "id,split_id,account,payee,category,type,amount,currency,date,notes,memo,tags,is_recurring".to_string();
     let mut csv_lines = vec![header];
     csv_lines.extend(rows);
     let csv = csv_lines.join("\n");
@@ -128,41 +150,37 @@ pub async fn restore_database(
 
 #[tauri::command]
 pub fn reset_all_data(state: State<DbState>) -> Result<(), String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    conn.execute_batch(
-        "PRAGMA foreign_keys = OFF;
-         DELETE FROM transactions;
-         DELETE FROM transfers;
-         DELETE FROM accounts;
-         DELETE FROM categories;
-         PRAGMA foreign_keys = ON;",
-    )
-    .map_err(|e| e.to_string())?;
-
-    let defaults = vec![
-        ("Salary", "income", "💼"),
-        ("Freelance", "income", "💻"),
-        ("Investments", "income", "📈"),
-        ("Other Income", "income", "💰"),
-        ("Food & Dining", "expense", "🍽️"),
-        ("Transportation", "expense", "🚗"),
-        ("Shopping", "expense", "🛍️"),
-        ("Utilities", "expense", "💡"),
-        ("Entertainment", "expense", "🎬"),
-        ("Health", "expense", "🏥"),
-        ("Education", "expense", "📚"),
-        ("Housing", "expense", "🏠"),
-        ("Other Expense", "expense", "💸"),
-    ];
-
-    for (name, direction, icon) in defaults {
-        conn.execute(
-            "INSERT INTO categories (name, direction, icon) VALUES (?1, ?2, ?3)",
-            (name, direction, icon),
+    // Deleted children-first so foreign keys stay satisfied; PRAGMA foreign_keys
+    // cannot be toggled inside the transaction.
+    with_transaction(&state, |conn| {
+        conn.execute_batch(
+            "DELETE FROM attachments;
+             DELETE FROM duplicate_dismissals;
+             DELETE FROM savings_dividends;
+             DELETE FROM interest_postings;
+             DELETE FROM transactions;
+             DELETE FROM installment_plans;
+             DELETE FROM investment_trades;
+             DELETE FROM security_prices;
+             DELETE FROM securities;
+             DELETE FROM reconciliations;
+             DELETE FROM import_batches;
+             DELETE FROM import_profiles;
+             DELETE FROM tags;
+             DELETE FROM rules;
+             DELETE FROM payees;
+             DELETE FROM transfers;
+             DELETE FROM loans;
+             DELETE FROM savings_rates;
+             DELETE FROM savings_plans;
+             DELETE FROM interest_settings;
+             DELETE FROM accounts;
+             DELETE FROM envelope_moves;
+             DELETE FROM budgets;
+             DELETE FROM categories;
+             DELETE FROM exchange_rates;",
         )
         .map_err(|e| e.to_string())?;
-    }
-
-    Ok(())
+        seed_categories(conn).map_err(|e| e.to_string())
+    })
 }
@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use rusqlite::Connection;
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::installments::require_no_plan;
+use crate::commands::investments::require_no_trade;
+use crate::commands::payees::resolve_payee_id;
+use crate::commands::reconciliation::require_unlocked;
+use crate::commands::transactions::{row_to_transaction, TRANSACTION_SELECT};
+use crate::logic::duplicates::{
+    cluster_pairs, score_pair, DuplicateFields, DEFAULT_MIN_SCORE, DEFAULT_WINDOW_DAYS,
+};
+use crate::models::{DuplicateCluster, ImportRow, Transaction};
+
+/// Candidate row: id, days apart, payee id, notes and bank id.
+type Candidate = (i64, i64, Option<i64>, Option<String>, Option<String>);
+
+fn load_transaction(conn: &Connection, id: i64) -> Result<Transaction, String> {
+    conn.query_row(
+        &format!("{TRANSACTION_SELECT} WHERE t.id = ?1"),
+        [id],
+        |row| row_to_transaction(row),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// The row of the same account, type and amount that `fields` most probably
+/// repeats, if any scores at least `DEFAULT_MIN_SCORE`. Ties go to the
+/// closest date, then the oldest row. `exclude_id` is the row itself and
+/// rows of `exclude_batch` come from the same statement, so neither counts.
+#[allow(clippy::too_many_arguments)]
+fn best_duplicate(
+    conn: &Connection,
+    account_id: i64,
+    tx_type: &str,
+    amount: i64,
+    date: &str,
+    fields: &DuplicateFields,
+    exclude_id: Option<i64>,
+    exclude_batch: Option<i64>,
+) -> Result<Option<i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, CAST(ABS(julianday(t.date) - julianday(?4)) AS INTEGER),
+                    t.payee_id, t.notes, t.external_id
+             FROM transactions t
+             WHERE t.account_id = ?1 AND t.type = ?2 AND t.amount = ?3
+               AND t.transfer_id IS NULL
+               AND ABS(julianday(t.date) - julianday(?4)) <= ?5
+               AND t.id IS NOT ?6
+               AND (?7 IS NULL OR t.import_batch_id IS NOT ?7)
+               AND NOT EXISTS (
+                 SELECT 1 FROM duplicate_dismissals d
+                 WHERE d.transaction_id = MIN(t.id, ?6) AND d.other_id = MAX(t.id, ?6))
+             ORDER BY t.id",
+        )
+        .map_err(|e| e.to_string())?;
+    let candidates: Vec<Candidate> = stmt
+        .query_map(
+            rusqlite::params![account_id, tx_type, amount, date, DEFAULT_WINDOW_DAYS, exclude_id, exclude_batch],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+        )
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut best: Option<(i64, i64, i64)> = None;
+    for (id, days_apart, payee_id, notes, external_id) in &candidates {
+        let other = DuplicateFields {
+            payee_id: *payee_id,
+            notes: notes.as_deref(),
+            external_id: external_id.as_deref(),
+        };
+        let score = score_pair(fields, &other, *days_apart);
+        let better = match best {
+            None => true,
+            Some((_, s, d)) => score > s || (score == s && *days_apart < d),
+        };
+        if score >= DEFAULT_MIN_SCORE && better {
+            best = Some((*id, score, *days_apart));
+        }
+    }
+    Ok(best.map(|(id, _, _)| id))
+}
+
+/// Existing transaction an import row probably repeats, for the preview.
+pub(crate) fn probable_duplicate_of_row(
+    conn: &Connection,
+    account_id: i64,
+    row: &ImportRow,
+) -> Result<Option<i64>, String> {
+    let (Some(date), Some(tx_type), Some(amount), None) =
+        (&row.date, &row.tx_type, &row.amount, &row.error)
+    else {
+        return Ok(None);
+    };
+    let payee_id = match row.description {
+        Some(ref description) => resolve_payee_id(conn, description)?,
+        None => None,
+    };
+    let fields = DuplicateFields {
+        payee_id,
+        notes: row.description.as_deref(),
+        external_id: row.external_id.as_deref(),
+    };
+    best_duplicate(conn, account_id, tx_type, amount.minor, date, &fields, None, None)
+}
+
+/// Sets `duplicate_of` on a freshly imported transaction when it probably
+/// repeats one already in the account. Returns whether it was flagged.
+pub(crate) fn flag_probable_duplicate(conn: &Connection, id: i64) -> Result<bool, String> {
+    let tx = load_transaction(conn, id)?;
+    let external_id: Option<String> = conn
+        .query_row("SELECT external_id FROM transactions WHERE id = ?1", [id], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    let fields = DuplicateFields {
+        payee_id: tx.payee_id,
+        notes: tx.notes.as_deref(),
+        external_id: external_id.as_deref(),
+    };
+    let duplicate_of = best_duplicate(
+        conn,
+        tx.account_id,
+        &tx.tx_type,
+        tx.amount.minor,
+        &tx.date,
+        &fields,
+        Some(id),
+        tx.import_batch_id,
+    )?;
+    if duplicate_of.is_some() {
+        conn.execute(
+            "UPDATE transactions SET duplicate_of = ?1 WHERE id = ?2",
+            rusqlite::params![duplicate_of, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(duplicate_of.is_some())
+}
+
+/// Row worth keeping when merging: the one with the most detail attached,
+/// oldest first on a tie.
+fn suggested_keep(transactions: &[Transaction]) -> i64 {
+    let detail = |t: &Transaction| {
+        t.is_split
+            + t.tag_ids.len() as i64
+            + t.category_id.is_some() as i64
+            + t.payee_id.is_some() as i64
+            + (!t.notes.as_deref().unwrap_or("").trim().is_empty()) as i64
+    };
+    transactions
+        .iter()
+        .max_by_key(|t| (detail(t), -t.id))
+        .map(|t| t.id)
+        .unwrap_or_default()
+}
+
+/// Scores every pair of same-account, same-type, same-amount transactions
+/// within `window_days` of each other and groups those scoring at least
+/// `min_score` into clusters. Rows of one import batch are never paired, as
+/// the bank listed them separately, and dismissed pairs are skipped.
+pub(crate) fn find_duplicate_clusters(
+    conn: &Connection,
+    account_id: Option<i64>,
+    window_days: i64,
+    min_score: i64,
+) -> Result<Vec<DuplicateCluster>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.id, b.id, CAST(ABS(julianday(a.date) - julianday(b.date)) AS INTEGER),
+                    a.payee_id, a.notes, a.external_id, b.payee_id, b.notes, b.external_id
+             FROM transactions a
+             JOIN transactions b ON b.account_id = a.account_id AND b.type = a.type
+               AND b.amount = a.amount AND b.id > a.id
+             WHERE a.transfer_id IS NULL AND b.transfer_id IS NULL
+               AND ABS(julianday(a.date) - julianday(b.date)) <= ?1
+               AND (a.import_batch_id IS NULL OR a.import_batch_id IS NOT b.import_batch_id)
+               AND (?2 IS NULL OR a.account_id = ?2)
+               AND NOT EXISTS (
+                 SELECT 1 FROM duplicate_dismissals d
+                 WHERE d.transaction_id = a.id AND d.other_id = b.id)",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, i64, i64, i64)> = stmt
+        .query_map(rusqlite::params![window_days, account_id], |r| {
+            let notes_a: Option<String> = r.get(4)?;
+            let external_a: Option<String> = r.get(5)?;
+            let notes_b: Option<String> = r.get(7)?;
+            let external_b: Option<String> = r.get(8)?;
+            let a = DuplicateFields {
+                payee_id: r.get(3)?,
+                notes: notes_a.as_deref(),
+                external_id: external_a.as_deref(),
+            };
+            let b = DuplicateFields {
+                payee_id: r.get(6)?,
+                notes: notes_b.as_deref(),
+                external_id: external_b.as_deref(),
+            };
+            let days_apart: i64 = r.get(2)?;
+            Ok((r.get(0)?, r.get(1)?, days_apart, score_pair(&a, &b, days_apart)))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter(|&(_, _, _, score)| score >= min_score)
+        .collect();
+
+    let pairs: Vec<(i64, i64)> = rows.iter().map(|&(a, b, _, _)| (a, b)).collect();
+    let mut best_score: HashMap<i64, i64> = HashMap::new();
+    for &(a, b, _, score) in &rows {
+        for id in [a, b] {
+            let entry = best_score.entry(id).or_insert(0);
+            *entry = (*entry).max(score);
+        }
+    }
+
+    cluster_pairs(&pairs)
+        .into_iter()
+        .map(|ids| {
+            let transactions = ids
+                .iter()
+                .map(|&id| load_transaction(conn, id))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(DuplicateCluster {
+                score: ids.iter().filter_map(|id| best_score.get(id)).copied().max().unwrap_or(0),
+                suggested_keep_id: suggested_keep(&transactions),
+                transactions,
+            })
+        })
+        .collect()
+}
+
+/// Folds `duplicate_ids` into `keep_id` and deletes them. Tags are combined,
+/// attachments move over, split lines move over when the kept row has none,
+/// and blank category, payee, notes, bank id and recurrence settings are
+/// filled in from the duplicates.
+pub(crate) fn merge_transactions(
+    conn: &Connection,
+    keep_id: i64,
+    duplicate_ids: &[i64],
+) -> Result<(), String> {
+    let keep = load_transaction(conn, keep_id)?;
+    if keep.transfer_id.is_some() {
+        return Err("Transfer legs cannot be merged".into());
+    }
+    // Every row is checked before anything changes: the kept row is rewritten
+    // and the duplicates are deleted, so none may be locked or owned by a plan
+    // or a trade.
+    let mut duplicates = Vec::with_capacity(duplicate_ids.len());
+    for &id in duplicate_ids {
+        if id == keep_id {
+            return Err("A transaction cannot be merged into itself".into());
+        }
+        let dup = load_transaction(conn, id)?;
+        if dup.transfer_id.is_some() {
+            return Err("Transfer legs cannot be merged".into());
+        }
+        if dup.account_id != keep.account_id || dup.tx_type != keep.tx_type || dup.amount != keep.amount {
+            return Err("Only transactions with the same account, type and amount can be merged".into());
+        }
+        duplicates.push(dup);
+    }
+    for &id in std::iter::once(&keep_id).chain(duplicate_ids) {
+        require_unlocked(conn, &[id])?;
+        require_no_plan(conn, id)?;
+        require_no_trade(conn, id)?;
+    }
+
+    for dup in duplicates {
+        let id = dup.id;
+        conn.execute(
+            "INSERT OR IGNORE INTO transaction_tags (transaction_id, tag_id)
+             SELECT ?1, tag_id FROM transaction_tags WHERE transaction_id = ?2",
+            [keep_id, id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE attachments SET transaction_id = ?1 WHERE transaction_id = ?2",
+            [keep_id, id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE transaction_splits SET transaction_id = ?1
+             WHERE transaction_id = ?2
+               AND NOT EXISTS (SELECT 1 FROM transaction_splits WHERE transaction_id = ?1)",
+            [keep_id, id],
+        )
+        .map_err(|e| e.to_string())?;
+
+        // The bank id moves across so re-imports still recognise the row;
+        // it is cleared first to satisfy the unique index.
+        let (external_id, import_batch_id): (Option<String>, Option<i64>) = conn
+            .query_row("SELECT external_id, import_batch_id FROM transactions WHERE id = ?1", [id], |r| {
+                Ok((r.get(0)?, r.get(1)?))
+            })
+            .map_err(|e| e.to_string())?;
+        conn.execute("UPDATE transactions SET external_id = NULL WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE transactions SET
+               category_id = COALESCE(category_id, ?2),
+               payee_id = COALESCE(payee_id, ?3),
+               notes = CASE WHEN TRIM(COALESCE(notes, '')) = '' THEN ?4 ELSE notes END,
+               external_id = COALESCE(external_id, ?5),
+               recurrence_frequency = CASE WHEN is_recurring = 0 AND ?6 = 1 THEN ?7 ELSE recurrence_frequency END,
+               next_due_date = CASE WHEN is_recurring = 0 AND ?6 = 1 THEN ?8 ELSE next_due_date END,
+               is_recurring = MAX(is_recurring, ?6),
+               status = CASE WHEN status = 'uncleared' THEN ?9 ELSE status END
+             WHERE id = ?1",
+            rusqlite::params![
+                keep_id,
+                dup.category_id,
+                dup.payee_id,
+                dup.notes,
+                external_id,
+                dup.is_recurring,
+                dup.recurrence_frequency,
+                dup.next_due_date,
+                dup.status
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        // A manual entry folded into an imported row makes it the user's own:
+        // rolling back the import must no longer delete it.
+        if import_batch_id.is_none() {
+            conn.execute("UPDATE transactions SET import_batch_id = NULL WHERE id = ?1", [keep_id])
+                .map_err(|e| e.to_string())?;
+        }
+        conn.execute(
+            "UPDATE transactions SET duplicate_of = ?1 WHERE duplicate_of = ?2 AND id != ?1",
+            [keep_id, id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM transactions WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "UPDATE transactions SET duplicate_of = NULL, updated_at = datetime('now') WHERE id = ?1",
+        [keep_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Records that none of `ids` duplicate each other and clears their flags.
+pub(crate) fn dismiss_duplicate_pairs(conn: &Connection, ids: &[i64]) -> Result<(), String> {
+    for (i, &a) in ids.iter().enumerate() {
+        for &b in &ids[i + 1..] {
+            if a == b {
+                continue;
+            }
+            conn.execute(
+                "INSERT OR IGNORE INTO duplicate_dismissals (transaction_id, other_id) VALUES (?1, ?2)",
+                [a.min(b), a.max(b)],
+            )
+            .map_err(|e| e.to_string())?;
+            conn.execute(
+                "UPDATE transactions SET duplicate_of = NULL
+                 WHERE (id = ?1 AND duplicate_of = ?2) OR (id = ?2 AND duplicate_of = ?1)",
+                [a, b],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Reviews probable duplicates. `window_days` defaults to 3 and `min_score`
+/// (0-100) to 60.
+#[tauri::command]
+pub fn find_duplicates(
+    state: State<DbState>,
+    account_id: Option<i64>,
+    window_days: Option<i64>,
+    min_score: Option<i64>,
+) -> Result<Vec<DuplicateCluster>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    find_duplicate_clusters(
+        conn,
+        account_id,
+        window_days.unwrap_or(DEFAULT_WINDOW_DAYS).clamp(0, 31),
+        min_score.unwrap_or(DEFAULT_MIN_SCORE),
+    )
+}
+
+#[tauri::command]
+pub fn merge_duplicates(
+    state: State<DbState>,
+    keep_id: i64,
+    duplicate_ids: Vec<i64>,
+) -> Result<Transaction, String> {
+    with_transaction(&state, |conn| {
+        merge_transactions(conn, keep_id, &duplicate_ids)?;
+        load_transaction(conn, keep_id)
+    })
+}
+
+/// Marks the given transactions as not duplicates of each other.
+#[tauri::command]
+pub fn dismiss_duplicates(state: State<DbState>, transaction_ids: Vec<i64>) -> Result<(), String> {
+    with_transaction(&state, |conn| dismiss_duplicate_pairs(conn, &transaction_ids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    fn seed(conn: &Connection) {
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Bank', 'debit');
+             INSERT INTO payees (name) VALUES ('Jollibee');
+             INSERT INTO tags (name) VALUES ('food');
+             INSERT INTO transactions (account_id, payee_id, type, amount, date, notes)
+               VALUES (1, 1, 'expense', 25000, '2025-01-05', 'Lunch');
+             INSERT INTO transaction_tags (transaction_id, tag_id) VALUES (1, 1);
+             INSERT INTO transactions (account_id, payee_id, type, amount, date, notes, external_id)
+               VALUES (1, 1, 'expense', 25000, '2025-01-06', 'JOLLIBEE #12', 'FIT1');
+             INSERT INTO transaction_splits (transaction_id, amount) VALUES (2, 25000);
+             INSERT INTO transactions (account_id, type, amount, date)
+               VALUES (1, 'expense', 25000, '2025-02-20');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn merge_keeps_one_row_with_tags_splits_and_bank_id() {
+        let conn = open_test_db();
+        seed(&conn);
+
+        let clusters = find_duplicate_clusters(&conn, None, DEFAULT_WINDOW_DAYS, DEFAULT_MIN_SCORE).unwrap();
+        assert_eq!(clusters.len(), 1);
+        let ids: Vec<i64> = clusters[0].transactions.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        merge_transactions(&conn, 1, &[2]).unwrap();
+        let kept = load_transaction(&conn, 1).unwrap();
+        assert_eq!(kept.tag_ids, vec![1]);
+        assert_eq!(kept.is_split, 1);
+        let external_id: Option<String> = conn
+            .query_row("SELECT external_id FROM transactions WHERE id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(external_id.as_deref(), Some("FIT1"));
+        assert!(load_transaction(&conn, 2).is_err());
+        assert!(find_duplicate_clusters(&conn, None, DEFAULT_WINDOW_DAYS, DEFAULT_MIN_SCORE)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn merging_into_an_imported_row_survives_rolling_back_the_import() {
+        let conn = open_test_db();
+        seed(&conn);
+        conn.execute_batch(
+            "INSERT INTO import_batches (account_id, source, row_count) VALUES (1, 'ofx', 1);
+             UPDATE transactions SET import_batch_id = 1 WHERE id = 2;",
+        )
+        .unwrap();
+
+        merge_transactions(&conn, 2, &[1]).unwrap();
+        crate::commands::imports::delete_import_batch(&conn, 1).unwrap();
+        let kept = load_transaction(&conn, 2).unwrap();
+        assert_eq!(kept.tag_ids, vec![1]);
+        assert_eq!(kept.notes.as_deref(), Some("JOLLIBEE #12"));
+    }
+
+    #[test]
+    fn installment_rows_are_not_merged() {
+        let conn = open_test_db();
+        seed(&conn);
+        conn.execute_batch(
+            "INSERT INTO installment_plans (account_id, purchase_transaction_id, principal, term_months, start_date)
+               VALUES (1, 1, 25000, 1, '2025-02-20');
+             INSERT INTO transactions (account_id, type, amount, date, installment_plan_id, installment_kind)
+               VALUES (1, 'expense', 25000, '2025-02-20', 1, 'principal');",
+        )
+        .unwrap();
+
+        assert!(merge_transactions(&conn, 3, &[4]).is_err());
+        assert!(merge_transactions(&conn, 2, &[1]).is_err());
+        assert!(load_transaction(&conn, 1).is_ok());
+        assert!(load_transaction(&conn, 4).is_ok());
+        let tags: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transaction_tags WHERE transaction_id = 2", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(tags, 0);
+    }
+
+    #[test]
+    fn dismissed_pairs_are_not_suggested_again() {
+        let conn = open_test_db();
+        seed(&conn);
+        conn.execute("UPDATE transactions SET duplicate_of = 1 WHERE id = 2", []).unwrap();
+
+        dismiss_duplicate_pairs(&conn, &[2, 1]).unwrap();
+        assert!(find_duplicate_clusters(&conn, None, DEFAULT_WINDOW_DAYS, DEFAULT_MIN_SCORE)
+            .unwrap()
+            .is_empty());
+        assert_eq!(load_transaction(&conn, 2).unwrap().duplicate_of, None);
+        assert_eq!(flag_probable_duplicate(&conn, 2), Ok(false));
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::budgets::{next_period, period};
+use crate::commands::categories::{category_tree, path_to};
+use crate::commands::currencies::base_currency;
+use crate::commands::installments::NOT_INSTALLMENT_PRINCIPAL;
+use crate::commands::investments::NOT_TRADE_SETTLEMENT;
+use crate::commands::reports::{category_spending, BaseConverter};
+use crate::models::{Envelope, EnvelopeMonth, EnvelopeMove, Money};
+
+const MOVE_SELECT: &str = "SELECT id, period, from_category_id, to_category_id, amount, currency, notes, created_at
+         FROM envelope_moves";
+
+fn row_to_move(row: &rusqlite::Row) -> rusqlite::Result<EnvelopeMove> {
+    Ok(EnvelopeMove {
+        id: row.get(0)?,
+        period: row.get(1)?,
+        from_category_id: row.get(2)?,
+        to_category_id: row.get(3)?,
+        amount: Money::new(row.get(4)?, row.get::<_, String>(5)?),
+        notes: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+fn envelope_mode(conn: &Connection) -> Result<bool, String> {
+    let value: Option<String> = conn
+        .query_row("SELECT value FROM app_settings WHERE key = 'envelope_budgeting'", [], |r| r.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    Ok(value.as_deref() == Some("1"))
+}
+
+/// Envelopes only take money, and only have balances, while the mode is on.
+fn require_envelope_mode(conn: &Connection) -> Result<(), String> {
+    if !envelope_mode(conn)? {
+        return Err("Turn on envelope budgeting first".into());
+    }
+    Ok(())
+}
+
+/// Envelopes are expense categories; income categories feed the pool.
+fn check_envelope(conn: &Connection, category_id: i64) -> Result<(), String> {
+    let direction: Option<String> = conn
+        .query_row("SELECT direction FROM categories WHERE id = ?1", [category_id], |r| r.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match direction.as_deref() {
+        None => Err("Category not found".into()),
+        Some("income") => Err("Income categories feed the pool and have no envelope".into()),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Moves `amount` from one envelope (or the pool, when `from` is `None`) to
+/// another (or back to the pool) for `period`.
+pub(crate) fn move_money(
+    conn: &Connection,
+    period: &str,
+    from: Option<i64>,
+    to: Option<i64>,
+    amount: &Money,
+    notes: Option<&str>,
+) -> Result<EnvelopeMove, String> {
+    require_envelope_mode(conn)?;
+    let currency = base_currency(conn)?;
+    amount.require_currency(&currency)?;
+    if amount.minor <= 0 {
+        return Err("Move a positive amount".into());
+    }
+    if from == to {
+        return Err("Pick two different envelopes".into());
+    }
+    for category_id in [from, to].into_iter().flatten() {
+        check_envelope(conn, category_id)?;
+    }
+    conn.execute(
+        "INSERT INTO envelope_moves (period, from_category_id, to_category_id, amount, currency, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![period, from, to, amount.minor, currency, notes],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row(&format!("{MOVE_SELECT} WHERE id = ?1"), [conn.last_insert_rowid()], |row| row_to_move(row))
+        .map_err(|e| e.to_string())
+}
+
+/// Income received each month up to and including `until`, in the base
+/// currency. Transfers between accounts and sale proceeds are left out.
+fn income_by_month(conn: &Connection, fx: &mut BaseConverter, until: &str) -> Result<BTreeMap<String, i64>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT l.amount, a.currency, l.date FROM category_lines l
+             JOIN transactions t ON t.id = l.transaction_id
+             JOIN accounts a ON a.id = l.account_id
+             WHERE l.type = 'income' AND l.transfer_id IS NULL AND strftime('%Y-%m', l.date) <= ?1
+               AND {NOT_INSTALLMENT_PRINCIPAL} AND {NOT_TRADE_SETTLEMENT}"
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, String, String)> = stmt
+        .query_map([until], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let mut income = BTreeMap::new();
+    for (amount, currency, date) in rows {
+        *income.entry(date[0..7].to_string()).or_insert(0) += fx.convert(amount, &currency, &date);
+    }
+    Ok(income)
+}
+
+/// Net taken out of the pool, and net moved into each envelope, in a month.
+type MonthMoves = (i64, HashMap<i64, i64>);
+
+/// Moves up to `until` by month, in the base currency at the rate on the
+/// first of each move's month.
+fn moves_by_month(conn: &Connection, fx: &mut BaseConverter, until: &str) -> Result<BTreeMap<String, MonthMoves>, String> {
+    let mut stmt = conn
+        .prepare(&format!("{MOVE_SELECT} WHERE period <= ?1"))
+        .map_err(|e| e.to_string())?;
+    let moves: Vec<EnvelopeMove> = stmt
+        .query_map([until], |row| row_to_move(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    let mut months: BTreeMap<String, MonthMoves> = BTreeMap::new();
+    for m in moves {
+        let amount = fx.convert(m.amount.minor, &m.amount.currency, &format!("{}-01", m.period));
+        let (from, to) = (m.from_category_id, m.to_category_id);
+        let (pool_out, assigned) = months.entry(m.period).or_default();
+        match from {
+            Some(id) => *assigned.entry(id).or_insert(0) -= amount,
+            None => *pool_out += amount,
+        }
+        match to {
+            Some(id) => *assigned.entry(id).or_insert(0) += amount,
+            None => *pool_out -= amount,
+        }
+    }
+    Ok(months)
+}
+
+/// Envelopes for `year`-`month`. Balances run forward from the first month
+/// anything was assigned: each envelope starts with what it had left (an
+/// overspent envelope starts again from zero), gains what was moved in and
+/// loses what was spent from the category. Spending in a subcategory without
+/// money of its own comes out of the nearest parent that has some, so funding
+/// "Food & Dining" covers "Groceries". Overspending nobody covered comes out
+/// of the next month's pool.
+pub(crate) fn envelope_month(conn: &Connection, year: i64, month: i64) -> Result<EnvelopeMonth, String> {
+    require_envelope_mode(conn)?;
+    let target = period(year, month)?;
+    let mut fx = BaseConverter::load(conn)?;
+    let income = income_by_month(conn, &mut fx, &target)?;
+    let moves = moves_by_month(conn, &mut fx, &target)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, is_archived FROM categories WHERE direction <> 'income' ORDER BY name",
+        )
+        .map_err(|e| e.to_string())?;
+    let categories: Vec<(i64, String, bool)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    // Spending is charged to the category itself or its nearest funded parent.
+    let funded: HashSet<i64> = moves.values().flat_map(|(_, assigned)| assigned.keys().copied()).collect();
+    let tree = category_tree(conn)?;
+    let envelope_of = |id: i64| -> i64 {
+        path_to(&tree, id).into_iter().rev().find(|c| funded.contains(c)).unwrap_or(id)
+    };
+
+    let mut available: HashMap<i64, i64> = HashMap::new();
+    let mut uncovered = 0;
+    let mut overspent_last_month = 0;
+    let no_moves = HashMap::new();
+    let mut current = moves.keys().next().filter(|first| **first < target).cloned().unwrap_or(target.clone());
+    let envelopes = loop {
+        let mut spent: HashMap<i64, i64> = HashMap::new();
+        for spend in category_spending(conn, &mut fx, &current)? {
+            if let Some(id) = spend.category_id {
+                *spent.entry(envelope_of(id)).or_insert(0) += spend.amount.minor;
+            }
+        }
+        let assigned = moves.get(&current).map(|(_, assigned)| assigned).unwrap_or(&no_moves);
+        let mut month_envelopes = Vec::new();
+        let mut overspent = 0;
+        for (id, name, archived) in &categories {
+            let carried = available.get(id).copied().unwrap_or(0).max(0);
+            let moved_in = assigned.get(id).copied().unwrap_or(0);
+            let spent = spent.get(id).copied().unwrap_or(0);
+            let left = carried + moved_in - spent;
+            available.insert(*id, left);
+            overspent += (-left).max(0);
+            if !archived || carried != 0 || moved_in != 0 || spent != 0 {
+                month_envelopes.push(Envelope {
+                    category_id: *id,
+                    category_name: name.clone(),
+                    carried_over: fx.money(carried),
+                    assigned: fx.money(moved_in),
+                    spent: fx.money(spent),
+                    available: fx.money(left),
+                });
+            }
+        }
+        if current == target {
+            break month_envelopes;
+        }
+        uncovered += overspent;
+        overspent_last_month = overspent;
+        current = next_period(&current);
+    };
+    fx.require_complete()?;
+
+    let pool_out: i64 = moves.values().map(|(pool_out, _)| pool_out).sum();
+    let total = |field: fn(&Envelope) -> &Money| envelopes.iter().map(|e| field(e).minor).sum::<i64>();
+    Ok(EnvelopeMonth {
+        year,
+        month,
+        income: fx.money(income.get(&target).copied().unwrap_or(0)),
+        to_be_assigned: fx.money(income.values().sum::<i64>() - pool_out - uncovered),
+        overspent_last_month: fx.money(overspent_last_month),
+        assigned: fx.money(total(|e| &e.assigned)),
+        spent: fx.money(total(|e| &e.spent)),
+        available: fx.money(total(|e| &e.available)),
+        envelopes,
+    })
+}
+
+/// Moves exactly what `category_id` is overspent by from another envelope,
+/// or from the pool when `from` is `None`.
+pub(crate) fn cover(
+    conn: &Connection,
+    year: i64,
+    month: i64,
+    category_id: i64,
+    from: Option<i64>,
+) -> Result<EnvelopeMove, String> {
+    let summary = envelope_month(conn, year, month)?;
+    let envelope = summary
+        .envelopes
+        .iter()
+        .find(|e| e.category_id == category_id)
+        .ok_or_else(|| "Category not found".to_string())?;
+    if envelope.available.minor >= 0 {
+        return Err(format!("{} is not overspent", envelope.category_name));
+    }
+    let shortfall = Money::new(-envelope.available.minor, envelope.available.currency.clone());
+    move_money(conn, &period(year, month)?, from, Some(category_id), &shortfall, Some("Covered overspending"))
+}
+
+#[tauri::command]
+pub fn get_envelope_mode(state: State<DbState>) -> Result<bool, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    envelope_mode(conn)
+}
+
+/// Turns envelope budgeting on or off. Moves are kept either way, but while
+/// the mode is off nothing can be assigned and there is no month summary.
+#[tauri::command]
+pub fn set_envelope_mode(state: State<DbState>, enabled: bool) -> Result<bool, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute(
+        "INSERT INTO app_settings (key, value) VALUES ('envelope_budgeting', ?1)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        [if enabled { "1" } else { "0" }],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub fn list_envelope_moves(state: State<DbState>, year: i64, month: i64) -> Result<Vec<EnvelopeMove>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{MOVE_SELECT} WHERE period = ?1 ORDER BY id"))
+        .map_err(|e| e.to_string())?;
+    let moves = stmt
+        .query_map([period(year, month)?], |row| row_to_move(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(moves)
+}
+
+/// Assigns money from the pool (no `from_category_id`), returns it (no
+/// `to_category_id`) or moves it between envelopes.
+#[tauri::command]
+pub fn move_envelope_money(
+    state: State<DbState>,
+    year: i64,
+    month: i64,
+    from_category_id: Option<i64>,
+    to_category_id: Option<i64>,
+    amount: Money,
+    notes: Option<String>,
+) -> Result<EnvelopeMove, String> {
+    with_transaction(&state, |conn| {
+        move_money(conn, &period(year, month)?, from_category_id, to_category_id, &amount, notes.as_deref())
+    })
+}
+
+#[tauri::command]
+pub fn delete_envelope_move(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM envelope_moves WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cover_overspending(
+    state: State<DbState>,
+    year: i64,
+    month: i64,
+    category_id: i64,
+    from_category_id: Option<i64>,
+) -> Result<EnvelopeMove, String> {
+    with_transaction(&state, |conn| cover(conn, year, month, category_id, from_category_id))
+}
+
+#[tauri::command]
+pub fn get_envelope_month(state: State<DbState>, year: i64, month: i64) -> Result<EnvelopeMonth, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    envelope_month(conn, year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    fn envelope_db() -> Connection {
+        let conn = open_test_db();
+        conn.execute("INSERT INTO app_settings (key, value) VALUES ('envelope_budgeting', '1')", [])
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn income_is_assigned_and_overspending_is_covered_or_carried_to_the_pool() {
+        let conn = envelope_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food', 'expense');
+             INSERT INTO categories (name, direction) VALUES ('Rent', 'expense');
+             INSERT INTO categories (name, direction) VALUES ('Salary', 'income');
+             INSERT INTO accounts (name, type) VALUES ('Bank', 'debit');
+             INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 3, 'income', 5000000, '2025-01-15');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 1, 'expense', 1200000, '2025-01-20');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 1, 'expense', 100000, '2025-02-03');
+             INSERT INTO transfers (from_account_id, to_account_id, amount, to_amount, date) VALUES (1, 2, 500000, 500000, '2025-01-16');
+             INSERT INTO transactions (account_id, transfer_id, type, amount, date) VALUES (2, 1, 'income', 500000, '2025-01-16');",
+        )
+        .unwrap();
+        let php = |minor: i64| Money::new(minor, "PHP");
+        assert!(move_money(&conn, "2025-01", None, Some(3), &php(100), None).is_err());
+        move_money(&conn, "2025-01", None, Some(1), &php(1_000_000), None).unwrap();
+        move_money(&conn, "2025-01", None, Some(2), &php(3_000_000), None).unwrap();
+
+        let january = envelope_month(&conn, 2025, 1).unwrap();
+        // The transfer into the wallet is not income.
+        assert_eq!(january.income.minor, 5_000_000);
+        assert_eq!(january.to_be_assigned.minor, 1_000_000);
+        assert_eq!(january.envelopes[0].category_name, "Food");
+        assert_eq!(january.envelopes[0].available.minor, -200_000);
+
+        assert!(cover(&conn, 2025, 1, 2, None).is_err());
+        let covered = cover(&conn, 2025, 1, 1, Some(2)).unwrap();
+        assert_eq!(covered.amount.minor, 200_000);
+        let january = envelope_month(&conn, 2025, 1).unwrap();
+        assert_eq!(january.envelopes[0].available.minor, 0);
+        assert_eq!(january.envelopes[1].available.minor, 2_800_000);
+
+        // Rent rolls over; February's uncovered food spending comes out of March's pool.
+        let february = envelope_month(&conn, 2025, 2).unwrap();
+        assert_eq!(february.envelopes[1].carried_over.minor, 2_800_000);
+        assert_eq!(february.envelopes[0].available.minor, -100_000);
+        assert_eq!(february.to_be_assigned.minor, 1_000_000);
+        let march = envelope_month(&conn, 2025, 3).unwrap();
+        assert_eq!(march.envelopes[0].carried_over.minor, 0);
+        assert_eq!(march.overspent_last_month.minor, 100_000);
+        assert_eq!(march.to_be_assigned.minor, 900_000);
+        assert_eq!(march.available.minor, 2_800_000);
+    }
+
+    #[test]
+    fn moves_convert_when_the_base_currency_changes() {
+        let conn = envelope_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food', 'expense');
+             INSERT INTO exchange_rates (base, quote, rate, date) VALUES ('PHP', 'JPY', 2.5, '2025-01-01');",
+        )
+        .unwrap();
+        let moved = move_money(&conn, "2025-03", None, Some(1), &Money::new(500_000, "PHP"), None).unwrap();
+        assert_eq!(moved.amount, Money::new(500_000, "PHP"));
+        conn.execute("INSERT INTO app_settings (key, value) VALUES ('base_currency', 'JPY')", [])
+            .unwrap();
+
+        // ₱5,000.00 assigned is ¥12,500, not ¥500,000.
+        let march = envelope_month(&conn, 2025, 3).unwrap();
+        assert_eq!(march.envelopes[0].assigned, Money::new(12_500, "JPY"));
+        assert_eq!(march.to_be_assigned, Money::new(-12_500, "JPY"));
+    }
+
+    #[test]
+    fn a_funded_parent_covers_spending_in_its_subcategories() {
+        let conn = envelope_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food & Dining', 'expense');
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Groceries', 'expense', 1);
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Restaurants', 'expense', 1);
+             INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 2, 'expense', 300000, '2025-03-02');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 3, 'expense', 100000, '2025-03-05');",
+        )
+        .unwrap();
+        let php = |minor: i64| Money::new(minor, "PHP");
+        move_money(&conn, "2025-03", None, Some(1), &php(500_000), None).unwrap();
+
+        let march = envelope_month(&conn, 2025, 3).unwrap();
+        let food = &march.envelopes[0];
+        assert_eq!(food.category_name, "Food & Dining");
+        assert_eq!((food.spent.minor, food.available.minor), (400_000, 100_000));
+        assert_eq!(march.spent.minor, 400_000);
+
+        // Once Restaurants has money of its own, its spending comes out of it.
+        move_money(&conn, "2025-03", Some(1), Some(3), &php(150_000), None).unwrap();
+        let march = envelope_month(&conn, 2025, 3).unwrap();
+        let restaurants = march.envelopes.iter().find(|e| e.category_id == 3).unwrap();
+        assert_eq!((restaurants.spent.minor, restaurants.available.minor), (100_000, 50_000));
+        assert_eq!(march.envelopes[0].available.minor, 50_000);
+        assert_eq!(march.spent.minor, 400_000);
+    }
+
+    #[test]
+    fn nothing_moves_while_the_mode_is_off() {
+        let conn = open_test_db();
+        conn.execute("INSERT INTO categories (name, direction) VALUES ('Food', 'expense')", [])
+            .unwrap();
+        let error = move_money(&conn, "2025-03", None, Some(1), &Money::new(100, "PHP"), None).unwrap_err();
+        assert_eq!(error, "Turn on envelope budgeting first");
+        assert!(envelope_month(&conn, 2025, 3).is_err());
+        assert!(cover(&conn, 2025, 3, 1, None).is_err());
+        let moves: i64 = conn.query_row("SELECT COUNT(*) FROM envelope_moves", [], |r| r.get(0)).unwrap();
+        assert_eq!(moves, 0);
+    }
+}
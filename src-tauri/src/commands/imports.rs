@@ -0,0 +1,630 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::{AppHandle, State};
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::{account_currency, link_ofx_account_id};
+use crate::commands::duplicates::{flag_probable_duplicate, probable_duplicate_of_row};
+use crate::commands::installments::require_no_plan;
+use crate::commands::payees::resolve_payee_id;
+use crate::commands::rules::apply_rules_to_transaction;
+use crate::logic::csv_import::parse_csv;
+use crate::logic::ofx_import::{parse_ofx, statement_rows};
+use crate::logic::qif_import::parse_qif;
+use crate::models::{
+    CsvMapping, ImportBatch, ImportPreview, ImportProfile, ImportResult, ImportRow,
+    OfxStatementPreview, DEFAULT_CURRENCY,
+};
+
+const PROFILE_SELECT: &str = "SELECT id, name, delimiter, has_header, skip_rows, date_column,
+         date_format, description_column, amount_column, debit_column, credit_column,
+         negate_amounts, decimal_separator, created_at
+         FROM import_profiles";
+
+fn row_to_profile(row: &rusqlite::Row) -> rusqlite::Result<ImportProfile> {
+    Ok(ImportProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        mapping: CsvMapping {
+            delimiter: row.get(2)?,
+            has_header: row.get::<_, i64>(3)? != 0,
+            skip_rows: row.get(4)?,
+            date_column: row.get(5)?,
+            date_format: row.get(6)?,
+            description_column: row.get(7)?,
+            amount_column: row.get(8)?,
+            debit_column: row.get(9)?,
+            credit_column: row.get(10)?,
+            negate_amounts: row.get::<_, i64>(11)? != 0,
+            decimal_separator: row.get(12)?,
+        },
+        created_at: row.get(13)?,
+    })
+}
+
+const BATCH_SELECT: &str = "SELECT b.id, b.account_id, b.source, b.file_name, b.row_count,
+         b.created_at, a.name
+         FROM import_batches b
+         LEFT JOIN accounts a ON a.id = b.account_id";
+
+fn row_to_batch(row: &rusqlite::Row) -> rusqlite::Result<ImportBatch> {
+    Ok(ImportBatch {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        source: row.get(2)?,
+        file_name: row.get(3)?,
+        row_count: row.get(4)?,
+        created_at: row.get(5)?,
+        account_name: row.get(6)?,
+    })
+}
+
+fn load_profile(conn: &Connection, id: i64) -> Result<ImportProfile, String> {
+    conn.query_row(&format!("{PROFILE_SELECT} WHERE id = ?1"), [id], |row| row_to_profile(row))
+        .map_err(|e| e.to_string())
+}
+
+/// Reads an import file as UTF-8, falling back to Latin-1 for the legacy
+/// encodings some banks still export.
+pub(crate) fn read_import_file(path: &str) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8(bytes)
+        .unwrap_or_else(|e| e.into_bytes().iter().map(|&b| b as char).collect()))
+}
+
+pub(crate) fn file_name(path: &str) -> Option<String> {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+}
+
+/// Writes every parsed row without an error into `account_id` under a new
+/// import batch. Rows whose external id is already in the account are
+/// skipped. Payees are matched (never created) from the description and
+/// categorization rules run on each row. Callers wrap this in a transaction so
+/// a failure leaves nothing behind.
+pub(crate) fn insert_import_rows(
+    conn: &Connection,
+    account_id: i64,
+    source: &str,
+    file_name: Option<&str>,
+    rows: &[ImportRow],
+) -> Result<ImportResult, String> {
+    let currency = account_currency(conn, account_id)?;
+    conn.execute(
+        "INSERT INTO import_batches (account_id, source, file_name) VALUES (?1, ?2, ?3)",
+        rusqlite::params![account_id, source, file_name],
+    )
+    .map_err(|e| e.to_string())?;
+    let batch_id = conn.last_insert_rowid();
+
+    let mut imported = 0i64;
+    let mut skipped = 0i64;
+    let mut duplicates = 0i64;
+    let mut flagged = 0i64;
+    for row in rows {
+        let (Some(date), Some(tx_type), Some(amount), None) =
+            (&row.date, &row.tx_type, &row.amount, &row.error)
+        else {
+            skipped += 1;
+            continue;
+        };
+        amount.require_currency(&currency)?;
+        if let Some(ref external_id) = row.external_id {
+            let seen: bool = conn
+                .query_row(
+                    "SELECT EXISTS (SELECT 1 FROM transactions WHERE account_id = ?1 AND external_id = ?2)",
+                    rusqlite::params![account_id, external_id],
+                    |r| r.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if seen {
+                duplicates += 1;
+                continue;
+            }
+        }
+        let payee_id = match row.description {
+            Some(ref description) => resolve_payee_id(conn, description)?,
+            None => None,
+        };
+        conn.execute(
+            "INSERT INTO transactions (account_id, payee_id, type, amount, date, notes, import_batch_id, external_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![account_id, payee_id, tx_type, amount.minor, date, row.description, batch_id, row.external_id],
+        )
+        .map_err(|e| e.to_string())?;
+        let id = conn.last_insert_rowid();
+        apply_rules_to_transaction(conn, id)?;
+        if flag_probable_duplicate(conn, id)? {
+            flagged += 1;
+        }
+        imported += 1;
+    }
+    if imported == 0 && duplicates == 0 {
+        return Err("The file has no rows that can be imported".into());
+    }
+
+    let batch_id = if imported == 0 {
+        delete_import_batch(conn, batch_id)?;
+        None
+    } else {
+        conn.execute(
+            "UPDATE import_batches SET row_count = ?1 WHERE id = ?2",
+            [imported, batch_id],
+        )
+        .map_err(|e| e.to_string())?;
+        Some(batch_id)
+    };
+    Ok(ImportResult { batch_id, account_id, imported, skipped, duplicates, flagged })
+}
+
+/// Points each previewed row at the existing transaction it probably repeats.
+fn mark_probable_duplicates(conn: &Connection, account_id: i64, rows: &mut [ImportRow]) -> Result<(), String> {
+    for row in rows {
+        row.duplicate_of = probable_duplicate_of_row(conn, account_id, row)?;
+    }
+    Ok(())
+}
+
+/// Removes a batch together with every transaction it created.
+pub(crate) fn delete_import_batch(conn: &Connection, id: i64) -> Result<(), String> {
+    let reconciled: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM transactions WHERE import_batch_id = ?1 AND status = 'reconciled'",
+            [id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if reconciled > 0 {
+        return Err(format!(
+            "{} transaction(s) from this import are reconciled; unlock them before rolling back",
+            reconciled
+        ));
+    }
+    let mut stmt = conn
+        .prepare("SELECT id FROM transactions WHERE import_batch_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let transaction_ids: Vec<i64> = stmt
+        .query_map([id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    for transaction_id in transaction_ids {
+        require_no_plan(conn, transaction_id)?;
+    }
+    conn.execute("DELETE FROM transactions WHERE import_batch_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM import_batches WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_import_profiles(state: State<DbState>) -> Result<Vec<ImportProfile>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{PROFILE_SELECT} ORDER BY name COLLATE NOCASE"))
+        .map_err(|e| e.to_string())?;
+
+    let profiles = stmt
+        .query_map([], |row| row_to_profile(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn create_import_profile(
+    state: State<DbState>,
+    name: String,
+    mapping: CsvMapping,
+) -> Result<ImportProfile, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute(
+        "INSERT INTO import_profiles (name, delimiter, has_header, skip_rows, date_column,
+         date_format, description_column, amount_column, debit_column, credit_column,
+         negate_amounts, decimal_separator)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        rusqlite::params![
+            name.trim(),
+            mapping.delimiter,
+            mapping.has_header as i64,
+            mapping.skip_rows,
+            mapping.date_column,
+            mapping.date_format,
+            mapping.description_column,
+            mapping.amount_column,
+            mapping.debit_column,
+            mapping.credit_column,
+            mapping.negate_amounts as i64,
+            mapping.decimal_separator,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    load_profile(conn, conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn update_import_profile(
+    state: State<DbState>,
+    id: i64,
+    name: String,
+    mapping: CsvMapping,
+) -> Result<ImportProfile, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute(
+        "UPDATE import_profiles SET name=?1, delimiter=?2, has_header=?3, skip_rows=?4,
+         date_column=?5, date_format=?6, description_column=?7, amount_column=?8,
+         debit_column=?9, credit_column=?10, negate_amounts=?11, decimal_separator=?12
+         WHERE id=?13",
+        rusqlite::params![
+            name.trim(),
+            mapping.delimiter,
+            mapping.has_header as i64,
+            mapping.skip_rows,
+            mapping.date_column,
+            mapping.date_format,
+            mapping.description_column,
+            mapping.amount_column,
+            mapping.debit_column,
+            mapping.credit_column,
+            mapping.negate_amounts as i64,
+            mapping.decimal_separator,
+            id,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    load_profile(conn, id)
+}
+
+#[tauri::command]
+pub fn delete_import_profile(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM import_profiles WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Opens a file picker for a bank export and returns the chosen path.
+#[tauri::command]
+pub async fn pick_import_file(app: AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let path = app
+        .dialog()
+        .file()
+        .add_filter("Bank export", &["csv", "txt", "ofx", "qfx", "qif"])
+        .blocking_pick_file();
+
+    Ok(path.map(|p| p.to_string()))
+}
+
+/// Parses the file with `mapping` without writing anything, so the user can
+/// check the columns before importing.
+#[tauri::command]
+pub fn preview_csv_import(
+    state: State<DbState>,
+    account_id: i64,
+    file_path: String,
+    mapping: CsvMapping,
+) -> Result<ImportPreview, String> {
+    let text = read_import_file(&file_path)?;
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut preview = parse_csv(&text, &mapping, &account_currency(conn, account_id)?)?;
+    mark_probable_duplicates(conn, account_id, &mut preview.rows)?;
+    Ok(preview)
+}
+
+/// Imports every row that parses cleanly into `account_id` as one batch.
+#[tauri::command]
+pub fn import_transactions_csv(
+    state: State<DbState>,
+    account_id: i64,
+    file_path: String,
+    mapping: CsvMapping,
+) -> Result<ImportResult, String> {
+    let text = read_import_file(&file_path)?;
+    with_transaction(&state, |conn| {
+        let preview = parse_csv(&text, &mapping, &account_currency(conn, account_id)?)?;
+        insert_import_rows(conn, account_id, "csv", file_name(&file_path).as_deref(), &preview.rows)
+    })
+}
+
+/// Account already linked to an OFX `ACCTID`, if any.
+fn account_for_ofx_id(conn: &Connection, ofx_account_id: Option<&str>) -> Result<Option<i64>, String> {
+    let Some(ofx_account_id) = ofx_account_id else {
+        return Ok(None);
+    };
+    conn.query_row(
+        "SELECT id FROM accounts WHERE ofx_account_id = ?1",
+        [ofx_account_id],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Imports every statement of an OFX file. Statements go to the account
+/// linked to their `ACCTID`; an unlinked statement goes to `account_id`,
+/// which is linked to it for next time.
+pub(crate) fn import_ofx_text(
+    conn: &Connection,
+    text: &str,
+    account_id: Option<i64>,
+    file_name: Option<&str>,
+) -> Result<Vec<ImportResult>, String> {
+    let statements = parse_ofx(text)?;
+    let mut results = Vec::new();
+    let mut fallback_used = false;
+    for statement in &statements {
+        let target = match account_for_ofx_id(conn, statement.account_id.as_deref())? {
+            Some(id) => id,
+            None => {
+                let id = account_id.ok_or_else(|| {
+                    format!(
+                        "No account is linked to OFX account {}; choose one to import into",
+                        statement.account_id.as_deref().unwrap_or("(unknown)")
+                    )
+                })?;
+                if fallback_used {
+                    return Err("The file has several unlinked statements; link each OFX account first".into());
+                }
+                fallback_used = true;
+                if let Some(ref ofx_id) = statement.account_id {
+                    link_ofx_account_id(conn, id, Some(ofx_id))?;
+                }
+                id
+            }
+        };
+        let currency = account_currency(conn, target)?;
+        if let Some(ref statement_currency) = statement.currency {
+            if !statement_currency.eq_ignore_ascii_case(&currency) {
+                return Err(format!(
+                    "Statement is in {} but the account uses {}",
+                    statement_currency, currency
+                ));
+            }
+        }
+        let rows = statement_rows(statement, &currency);
+        results.push(insert_import_rows(conn, target, "ofx", file_name, &rows)?);
+    }
+    Ok(results)
+}
+
+/// Parses an OFX/QFX file and reports which account each statement maps to.
+#[tauri::command]
+pub fn preview_ofx_import(
+    state: State<DbState>,
+    file_path: String,
+) -> Result<Vec<OfxStatementPreview>, String> {
+    let text = read_import_file(&file_path)?;
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+
+    parse_ofx(&text)?
+        .iter()
+        .map(|statement| {
+            let account_id = account_for_ofx_id(conn, statement.account_id.as_deref())?;
+            let currency = match account_id {
+                Some(id) => account_currency(conn, id)?,
+                None => statement.currency.clone().unwrap_or_else(|| DEFAULT_CURRENCY.to_string()),
+            };
+            let mut rows = statement_rows(statement, &currency);
+            if let Some(id) = account_id {
+                mark_probable_duplicates(conn, id, &mut rows)?;
+            }
+            Ok(OfxStatementPreview {
+                ofx_account_id: statement.account_id.clone(),
+                currency: statement.currency.clone(),
+                account_id,
+                rows,
+            })
+        })
+        .collect()
+}
+
+/// Imports an OFX/QFX file, one batch per statement. Transactions whose FITID
+/// was already imported are skipped, so overlapping downloads are safe.
+#[tauri::command]
+pub fn import_ofx(
+    state: State<DbState>,
+    file_path: String,
+    account_id: Option<i64>,
+) -> Result<Vec<ImportResult>, String> {
+    let text = read_import_file(&file_path)?;
+    with_transaction(&state, |conn| {
+        import_ofx_text(conn, &text, account_id, file_name(&file_path).as_deref())
+    })
+}
+
+/// `day_first` reads QIF dates as D/M/Y instead of the usual M/D/Y.
+#[tauri::command]
+pub fn preview_qif_import(
+    state: State<DbState>,
+    account_id: i64,
+    file_path: String,
+    day_first: Option<bool>,
+) -> Result<Vec<ImportRow>, String> {
+    let text = read_import_file(&file_path)?;
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut rows = parse_qif(&text, day_first.unwrap_or(false), &account_currency(conn, account_id)?)?;
+    mark_probable_duplicates(conn, account_id, &mut rows)?;
+    Ok(rows)
+}
+
+#[tauri::command]
+pub fn import_qif(
+    state: State<DbState>,
+    account_id: i64,
+    file_path: String,
+    day_first: Option<bool>,
+) -> Result<ImportResult, String> {
+    let text = read_import_file(&file_path)?;
+    with_transaction(&state, |conn| {
+        let rows = parse_qif(&text, day_first.unwrap_or(false), &account_currency(conn, account_id)?)?;
+        insert_import_rows(conn, account_id, "qif", file_name(&file_path).as_deref(), &rows)
+    })
+}
+
+#[tauri::command]
+pub fn list_import_batches(
+    state: State<DbState>,
+    account_id: Option<i64>,
+) -> Result<Vec<ImportBatch>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{BATCH_SELECT} WHERE ?1 IS NULL OR b.account_id = ?1 ORDER BY b.created_at DESC, b.id DESC"
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let batches = stmt
+        .query_map([account_id], |row| row_to_batch(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(batches)
+}
+
+/// Undoes an import by deleting the batch and all of its transactions.
+#[tauri::command]
+pub fn rollback_import_batch(state: State<DbState>, id: i64) -> Result<(), String> {
+    with_transaction(&state, |conn| delete_import_batch(conn, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{open_test_db, run_in_transaction};
+    use crate::models::Money;
+
+    fn row(line: i64, amount: Option<i64>, error: Option<&str>) -> ImportRow {
+        ImportRow {
+            line,
+            date: Some("2025-01-05".into()),
+            description: Some("JOLLIBEE #12".into()),
+            tx_type: Some("expense".into()),
+            amount: amount.map(|m| Money::new(m, "PHP")),
+            external_id: None,
+            duplicate_of: None,
+            error: error.map(String::from),
+        }
+    }
+
+    #[test]
+    fn import_skips_bad_rows_and_rolls_back_as_a_batch() {
+        let mut conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Bank', 'debit');
+             INSERT INTO payees (name) VALUES ('Jollibee');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'income', 100000, '2025-01-01');",
+        )
+        .unwrap();
+
+        let rows = vec![row(2, Some(25000), None), row(3, None, Some("No amount")), row(4, Some(9900), None)];
+        let result = run_in_transaction(&mut conn, |tx| {
+            insert_import_rows(tx, 1, "csv", Some("jan.csv"), &rows)
+        })
+        .unwrap();
+        assert_eq!((result.imported, result.skipped), (2, 1));
+
+        let matched: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions WHERE payee_id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(matched, 2);
+
+        run_in_transaction(&mut conn, |tx| delete_import_batch(tx, result.batch_id.unwrap())).unwrap();
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[test]
+    fn rollback_keeps_purchases_on_an_installment_plan() {
+        let mut conn = open_test_db();
+        conn.execute_batch("INSERT INTO accounts (name, type) VALUES ('Card', 'credit');").unwrap();
+        let result = run_in_transaction(&mut conn, |tx| {
+            insert_import_rows(tx, 1, "csv", Some("jan.csv"), &[row(2, Some(25000), None)])
+        })
+        .unwrap();
+        conn.execute(
+            "INSERT INTO installment_plans (account_id, purchase_transaction_id, principal, term_months, start_date)
+             VALUES (1, 1, 25000, 3, '2025-02-05')",
+            [],
+        )
+        .unwrap();
+
+        let error = run_in_transaction(&mut conn, |tx| delete_import_batch(tx, result.batch_id.unwrap())).unwrap_err();
+        assert!(error.contains("installment plan"), "{error}");
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[test]
+    fn import_flags_rows_that_repeat_manual_entries() {
+        let mut conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Bank', 'debit');
+             INSERT INTO transactions (account_id, type, amount, date, notes)
+               VALUES (1, 'expense', 25000, '2025-01-04', 'Jollibee lunch');",
+        )
+        .unwrap();
+
+        let rows = vec![row(2, Some(25000), None), row(3, Some(9900), None)];
+        let flagged = probable_duplicate_of_row(&conn, 1, &rows[0]).unwrap();
+        assert_eq!(flagged, Some(1));
+
+        let result = run_in_transaction(&mut conn, |tx| {
+            insert_import_rows(tx, 1, "csv", None, &rows)
+        })
+        .unwrap();
+        assert_eq!((result.imported, result.flagged), (2, 1));
+        let duplicate_of: Option<i64> = conn
+            .query_row("SELECT duplicate_of FROM transactions WHERE amount = 25000 AND id != 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(duplicate_of, Some(1));
+    }
+
+    #[test]
+    fn reimporting_an_overlapping_ofx_statement_skips_known_fitids() {
+        let mut conn = open_test_db();
+        conn.execute("INSERT INTO accounts (name, type) VALUES ('Bank', 'debit')", [])
+            .unwrap();
+        let statement = |fitids: &[&str]| {
+            let trns: String = fitids
+                .iter()
+                .map(|id| format!("<STMTTRN><DTPOSTED>20250105<TRNAMT>-100.00<FITID>{id}<NAME>SHOP</STMTTRN>"))
+                .collect();
+            format!("<OFX><STMTRS><CURDEF>PHP<BANKACCTFROM><ACCTID>998877</BANKACCTFROM>{trns}</STMTRS></OFX>")
+        };
+
+        let first = run_in_transaction(&mut conn, |tx| {
+            import_ofx_text(tx, &statement(&["A", "B"]), Some(1), None)
+        })
+        .unwrap();
+        assert_eq!(first[0].imported, 2);
+
+        // The account is now linked, so no target needs to be given.
+        let second = run_in_transaction(&mut conn, |tx| {
+            import_ofx_text(tx, &statement(&["B", "C"]), None, None)
+        })
+        .unwrap();
+        assert_eq!((second[0].imported, second[0].duplicates), (1, 1));
+
+        let total: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(total, 3);
+    }
+}
@@ -0,0 +1,512 @@
+use rusqlite::Connection;
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::{row_to_account, ACCOUNT_SELECT};
+use crate::commands::reconciliation::require_unlocked;
+use crate::logic::credit_cards::{day_in_month, next_closing_date};
+use crate::logic::csv_import::parse_date;
+use crate::logic::installments::{
+    installment_principal, monthly_add_on_interest, remaining_principal, MAX_TERM_MONTHS,
+};
+use crate::models::{Account, InstallmentPlan, InstallmentPlanInput, Money};
+
+/// Condition on `transactions t` that leaves out installment conversions and
+/// principal billings. The purchase already counts as spending, so reports
+/// skip the rows that only move it between statements.
+pub(crate) const NOT_INSTALLMENT_PRINCIPAL: &str =
+    "COALESCE(t.installment_kind, '') NOT IN ('conversion', 'principal')";
+
+const PLAN_SELECT: &str = "SELECT p.id, p.account_id, p.purchase_transaction_id, p.description,
+         p.principal, p.term_months, p.monthly_add_on_rate, p.processing_fee, p.category_id,
+         p.start_date, p.status, p.paid_off_date, p.created_at, a.currency,
+         (SELECT COUNT(*) FROM transactions t
+          WHERE t.installment_plan_id = p.id AND t.installment_kind = 'principal')
+         FROM installment_plans p
+         JOIN accounts a ON a.id = p.account_id";
+
+fn row_to_plan(row: &rusqlite::Row) -> rusqlite::Result<InstallmentPlan> {
+    let currency: String = row.get(13)?;
+    let principal: i64 = row.get(4)?;
+    let term_months: i64 = row.get(5)?;
+    let rate: f64 = row.get(6)?;
+    let status: String = row.get(10)?;
+    let posted: i64 = row.get(14)?;
+    let interest = monthly_add_on_interest(principal, rate);
+    let remaining = if status == "active" {
+        remaining_principal(principal, term_months, posted)
+    } else {
+        0
+    };
+    Ok(InstallmentPlan {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        purchase_transaction_id: row.get(2)?,
+        description: row.get(3)?,
+        principal: Money::new(principal, currency.clone()),
+        term_months,
+        monthly_add_on_rate: rate,
+        processing_fee: Money::new(row.get(7)?, currency.clone()),
+        category_id: row.get(8)?,
+        start_date: row.get(9)?,
+        paid_off_date: row.get(11)?,
+        created_at: row.get(12)?,
+        monthly_payment: Money::new(installment_principal(principal, term_months, 1) + interest, currency.clone()),
+        total_interest: Money::new(interest * term_months, currency.clone()),
+        installments_posted: posted,
+        remaining_principal: Money::new(remaining, currency),
+        next_installment_date: None,
+        status,
+    })
+}
+
+/// Billing date of installment `number` (1-based): the start date's day of
+/// the month, clamped to shorter months.
+fn installment_date(conn: &Connection, start_date: &str, number: i64) -> Result<String, String> {
+    let day: i64 = start_date[8..10].parse().map_err(|_| format!("Invalid date \"{}\"", start_date))?;
+    day_in_month(conn, start_date, number - 1, day)
+}
+
+fn fill_next_date(conn: &Connection, plan: &mut InstallmentPlan) -> Result<(), String> {
+    if plan.status == "active" {
+        plan.next_installment_date = Some(installment_date(conn, &plan.start_date, plan.installments_posted + 1)?);
+    }
+    Ok(())
+}
+
+pub(crate) fn load_plan(conn: &Connection, id: i64) -> Result<InstallmentPlan, String> {
+    let mut plan = conn
+        .query_row(&format!("{PLAN_SELECT} WHERE p.id = ?1"), [id], |row| row_to_plan(row))
+        .map_err(|e| e.to_string())?;
+    fill_next_date(conn, &mut plan)?;
+    Ok(plan)
+}
+
+fn today(conn: &Connection) -> Result<String, String> {
+    conn.query_row("SELECT DATE('now')", [], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_charge(
+    conn: &Connection,
+    plan: &InstallmentPlan,
+    kind: &str,
+    tx_type: &str,
+    amount: i64,
+    category_id: Option<i64>,
+    number: Option<i64>,
+    date: &str,
+    notes: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO transactions (account_id, category_id, type, amount, date, notes,
+           installment_plan_id, installment_kind, installment_number)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![plan.account_id, category_id, tx_type, amount, date, notes, plan.id, kind, number],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn plan_label(plan: &InstallmentPlan) -> String {
+    plan.description.clone().unwrap_or_else(|| "Installment".to_string())
+}
+
+/// Bills installment `number` of a plan on `date`: the principal share, the
+/// add-on interest and, with the first installment, the processing fee.
+fn post_installment(conn: &Connection, plan: &InstallmentPlan, number: i64, date: &str) -> Result<(), String> {
+    let label = format!("{} {}/{}", plan_label(plan), number, plan.term_months);
+    let principal = installment_principal(plan.principal.minor, plan.term_months, number);
+    insert_charge(conn, plan, "principal", "expense", principal, None, Some(number), date, &label)?;
+
+    let interest = monthly_add_on_interest(plan.principal.minor, plan.monthly_add_on_rate);
+    if interest > 0 {
+        let notes = format!("{} interest", label);
+        insert_charge(conn, plan, "interest", "expense", interest, plan.category_id, Some(number), date, &notes)?;
+    }
+    if number == 1 && plan.processing_fee.minor > 0 {
+        let notes = format!("{} processing fee", plan_label(plan));
+        insert_charge(conn, plan, "fee", "expense", plan.processing_fee.minor, plan.category_id, Some(number), date, &notes)?;
+    }
+    Ok(())
+}
+
+/// Bills every installment of an active plan due on or before `today` and
+/// completes plans whose last installment has been billed. Returns the
+/// number of installments posted.
+pub(crate) fn post_due_installments(conn: &Connection, today: &str) -> Result<i32, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM installment_plans WHERE status = 'active' ORDER BY id")
+        .map_err(|e| e.to_string())?;
+    let ids: Vec<i64> = stmt
+        .query_map([], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut count = 0i32;
+    for id in ids {
+        let plan = load_plan(conn, id)?;
+        for number in plan.installments_posted + 1..=plan.term_months {
+            let date = installment_date(conn, &plan.start_date, number)?;
+            if date.as_str() > today {
+                break;
+            }
+            post_installment(conn, &plan, number, &date)?;
+            count += 1;
+            if number == plan.term_months {
+                conn.execute("UPDATE installment_plans SET status = 'completed' WHERE id = ?1", [id])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Converts a card purchase into a plan: validates the purchase, records the
+/// plan, credits the principal back to the card on the purchase date and
+/// bills any installments already due.
+pub(crate) fn create_plan(conn: &Connection, input: &InstallmentPlanInput, today: &str) -> Result<InstallmentPlan, String> {
+    let (account_id, tx_type, amount, date, transfer_id, payee_id, notes): (
+        i64,
+        String,
+        i64,
+        String,
+        Option<i64>,
+        Option<i64>,
+        Option<String>,
+    ) = conn
+        .query_row(
+            "SELECT account_id, type, amount, date, transfer_id, payee_id, notes FROM transactions WHERE id = ?1",
+            [input.purchase_transaction_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?, r.get(6)?)),
+        )
+        .map_err(|_| "Purchase not found".to_string())?;
+    let account: Account = conn
+        .query_row(&format!("{ACCOUNT_SELECT} WHERE id = ?1"), [account_id], |row| row_to_account(row))
+        .map_err(|e| e.to_string())?;
+
+    if account.account_type != "credit" {
+        return Err(format!("{} is not a credit card", account.name));
+    }
+    if tx_type != "expense" || transfer_id.is_some() {
+        return Err("Only card purchases can be converted to installments".into());
+    }
+    let existing: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM installment_plans WHERE purchase_transaction_id = ?1",
+            [input.purchase_transaction_id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if existing > 0 {
+        return Err("This purchase is already on an installment plan".into());
+    }
+    if !(1..=MAX_TERM_MONTHS).contains(&input.term_months) {
+        return Err(format!("Terms run from 1 to {} months", MAX_TERM_MONTHS));
+    }
+    if !input.monthly_add_on_rate.is_finite() || input.monthly_add_on_rate < 0.0 {
+        return Err("The add-on rate cannot be negative".into());
+    }
+
+    let principal = match &input.principal {
+        Some(principal) => {
+            principal.require_currency(&account.currency)?;
+            principal.minor
+        }
+        None => amount,
+    };
+    if principal <= 0 || principal > amount {
+        return Err("The principal must be more than zero and no more than the purchase".into());
+    }
+    let processing_fee = match &input.processing_fee {
+        Some(fee) => {
+            fee.require_currency(&account.currency)?;
+            if fee.minor < 0 {
+                return Err("The processing fee cannot be negative".into());
+            }
+            fee.minor
+        }
+        None => 0,
+    };
+
+    let start_date = match input.start_date.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(start) => {
+            let start = parse_date(start, "YYYY-MM-DD")?;
+            if start < date {
+                return Err("The first installment cannot be billed before the purchase".into());
+            }
+            start
+        }
+        None => match account.billing_cycle_day {
+            Some(cycle_day) => next_closing_date(conn, cycle_day, &date)?,
+            None => day_in_month(conn, &date, 1, date[8..10].parse().unwrap_or(1))?,
+        },
+    };
+    let description = input
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .or(notes);
+
+    conn.execute(
+        "INSERT INTO installment_plans (account_id, purchase_transaction_id, description, principal, term_months,
+           monthly_add_on_rate, processing_fee, category_id, start_date)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            account.id,
+            input.purchase_transaction_id,
+            description,
+            principal,
+            input.term_months,
+            input.monthly_add_on_rate,
+            processing_fee,
+            input.category_id,
+            start_date
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let plan = load_plan(conn, conn.last_insert_rowid())?;
+
+    let notes = format!("{} conversion", plan_label(&plan));
+    insert_charge(conn, &plan, "conversion", "income", principal, None, None, &date, &notes)?;
+    if payee_id.is_some() {
+        conn.execute(
+            "UPDATE transactions SET payee_id = ?1 WHERE id = ?2",
+            rusqlite::params![payee_id, conn.last_insert_rowid()],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    post_due_installments(conn, today)?;
+    load_plan(conn, plan.id)
+}
+
+/// Settles a plan early: bills whatever is due up to `date`, then the rest of
+/// the principal in one charge, plus an optional pre-termination fee.
+pub(crate) fn pay_off_plan(
+    conn: &Connection,
+    id: i64,
+    date: &str,
+    pretermination_fee: Option<&Money>,
+) -> Result<InstallmentPlan, String> {
+    let plan = load_plan(conn, id)?;
+    if plan.status != "active" {
+        return Err("This installment plan is already settled".into());
+    }
+    post_due_installments(conn, date)?;
+    let plan = load_plan(conn, id)?;
+    if plan.status != "active" {
+        return Ok(plan);
+    }
+
+    let remaining = plan.remaining_principal.minor;
+    let notes = format!("{} early payoff", plan_label(&plan));
+    insert_charge(conn, &plan, "principal", "expense", remaining, None, None, date, &notes)?;
+    if let Some(fee) = pretermination_fee.filter(|fee| fee.minor != 0) {
+        fee.require_currency(&plan.principal.currency)?;
+        if fee.minor < 0 {
+            return Err("The pre-termination fee cannot be negative".into());
+        }
+        let notes = format!("{} pre-termination fee", plan_label(&plan));
+        insert_charge(conn, &plan, "fee", "expense", fee.minor, plan.category_id, None, date, &notes)?;
+    }
+    conn.execute(
+        "UPDATE installment_plans SET status = 'paid_off', paid_off_date = ?1 WHERE id = ?2",
+        rusqlite::params![date, id],
+    )
+    .map_err(|e| e.to_string())?;
+    load_plan(conn, id)
+}
+
+fn plan_transaction_ids(conn: &Connection, id: i64) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM transactions WHERE installment_plan_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+/// Refuses editing or deleting a purchase converted into a plan, or a row a
+/// plan posted. Those change with the plan; delete the plan first.
+pub(crate) fn require_no_plan(conn: &Connection, transaction_id: i64) -> Result<(), String> {
+    let (posted, converted): (bool, bool) = conn
+        .query_row(
+            "SELECT installment_plan_id IS NOT NULL,
+                    EXISTS (SELECT 1 FROM installment_plans WHERE purchase_transaction_id = ?1)
+             FROM transactions WHERE id = ?1",
+            [transaction_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|_| "Transaction not found".to_string())?;
+    if converted {
+        return Err("This purchase is on an installment plan; delete the plan first".into());
+    }
+    if posted {
+        return Err("This was posted by an installment plan; delete the plan to remove it".into());
+    }
+    Ok(())
+}
+
+/// Remaining principal on a card's active plans: converted off the balance
+/// but not yet billed.
+pub(crate) fn unbilled_principal(conn: &Connection, account_id: i64) -> Result<i64, String> {
+    let mut stmt = conn
+        .prepare(&format!("{PLAN_SELECT} WHERE p.account_id = ?1 AND p.status = 'active'"))
+        .map_err(|e| e.to_string())?;
+    let total = stmt
+        .query_map([account_id], |row| row_to_plan(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .map(|plan| plan.remaining_principal.minor)
+        .sum();
+    Ok(total)
+}
+
+#[tauri::command]
+pub fn list_installment_plans(
+    state: State<DbState>,
+    account_id: Option<i64>,
+) -> Result<Vec<InstallmentPlan>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{PLAN_SELECT} WHERE (?1 IS NULL OR p.account_id = ?1)
+             ORDER BY p.status = 'active' DESC, p.start_date DESC, p.id DESC"
+        ))
+        .map_err(|e| e.to_string())?;
+    let mut plans: Vec<InstallmentPlan> = stmt
+        .query_map([account_id], |row| row_to_plan(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    for plan in plans.iter_mut() {
+        fill_next_date(conn, plan)?;
+    }
+    Ok(plans)
+}
+
+#[tauri::command]
+pub fn create_installment_plan(
+    state: State<DbState>,
+    input: InstallmentPlanInput,
+) -> Result<InstallmentPlan, String> {
+    with_transaction(&state, |conn| create_plan(conn, &input, &today(conn)?))
+}
+
+/// Pays off the rest of a plan on `date` (default today).
+#[tauri::command]
+pub fn pay_off_installment_plan(
+    state: State<DbState>,
+    id: i64,
+    date: Option<String>,
+    pretermination_fee: Option<Money>,
+) -> Result<InstallmentPlan, String> {
+    with_transaction(&state, |conn| {
+        let date = match date.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(date) => parse_date(date, "YYYY-MM-DD")?,
+            None => today(conn)?,
+        };
+        pay_off_plan(conn, id, &date, pretermination_fee.as_ref())
+    })
+}
+
+/// Deletes a plan with its conversion and every charge it billed, leaving
+/// the original purchase on the card.
+#[tauri::command]
+pub fn delete_installment_plan(state: State<DbState>, id: i64) -> Result<(), String> {
+    with_transaction(&state, |conn| {
+        let ids = plan_transaction_ids(conn, id)?;
+        require_unlocked(conn, &ids)?;
+        conn.execute("DELETE FROM transactions WHERE installment_plan_id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM installment_plans WHERE id = ?1", [id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+/// Bills installments that have come due; run at startup next to recurring
+/// transactions.
+#[tauri::command]
+pub fn process_installments(state: State<DbState>) -> Result<i32, String> {
+    with_transaction(&state, |conn| post_due_installments(conn, &today(conn)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn plans_bill_monthly_and_pay_off_early() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Bank Fees', 'expense');
+             INSERT INTO accounts (name, type, credit_limit, billing_cycle_day) VALUES ('Card', 'credit', 10000000, 31);
+             INSERT INTO transactions (account_id, type, amount, date, notes) VALUES (1, 'expense', 1200000, '2025-01-10', 'Laptop');",
+        )
+        .unwrap();
+        let input = InstallmentPlanInput {
+            purchase_transaction_id: 1,
+            principal: None,
+            term_months: 6,
+            monthly_add_on_rate: 0.5,
+            processing_fee: Some(Money::new(20000, "PHP")),
+            category_id: Some(1),
+            start_date: None,
+            description: None,
+        };
+
+        let plan = create_plan(&conn, &input, "2025-03-05").unwrap();
+        // First installment on the closing after the purchase; the second on February's last day.
+        assert_eq!(plan.start_date, "2025-01-31");
+        assert_eq!(plan.installments_posted, 2);
+        assert_eq!(plan.next_installment_date.as_deref(), Some("2025-03-31"));
+        assert_eq!(plan.monthly_payment.minor, 200_000 + 6_000);
+        assert_eq!(plan.remaining_principal.minor, 800_000);
+        assert_eq!(unbilled_principal(&conn, 1).unwrap(), 800_000);
+        assert!(create_plan(&conn, &input, "2025-03-05").is_err());
+
+        let balance: i64 = conn
+            .query_row(
+                "SELECT SUM(CASE type WHEN 'expense' THEN amount ELSE -amount END) FROM transactions WHERE account_id = 1",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        // Two principal shares, two interest charges and the processing fee.
+        assert_eq!(balance, 400_000 + 12_000 + 20_000);
+
+        let plan = pay_off_plan(&conn, plan.id, "2025-03-10", Some(&Money::new(5000, "PHP"))).unwrap();
+        assert_eq!(plan.status, "paid_off");
+        assert_eq!(plan.remaining_principal.minor, 0);
+        assert_eq!(plan.next_installment_date, None);
+        let billed: i64 = conn
+            .query_row(
+                "SELECT SUM(amount) FROM transactions WHERE installment_kind = 'principal'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(billed, 1_200_000);
+        assert_eq!(post_due_installments(&conn, "2025-12-31").unwrap(), 0);
+
+        // The purchase and the plan's rows only change through the plan.
+        assert!(require_no_plan(&conn, 1).is_err());
+        let conversion: i64 = conn
+            .query_row("SELECT id FROM transactions WHERE installment_kind = 'conversion'", [], |r| r.get(0))
+            .unwrap();
+        assert!(require_no_plan(&conn, conversion).is_err());
+        conn.execute("DELETE FROM transactions WHERE installment_plan_id = ?1", [plan.id]).unwrap();
+        conn.execute("DELETE FROM installment_plans WHERE id = ?1", [plan.id]).unwrap();
+        assert!(require_no_plan(&conn, 1).is_ok());
+    }
+}
@@ -0,0 +1,418 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::{row_to_account, ACCOUNT_SELECT};
+use crate::logic::balance::balance_samples;
+use crate::logic::credit_cards::card_statements;
+use crate::logic::csv_import::parse_date;
+use crate::logic::interest::{
+    accrued_interest, days_360, withholding_tax, COMPOUNDING_PERIODS, DAY_COUNTS, RATE_TYPES,
+};
+use crate::models::{Account, InterestAccrual, InterestSettings, InterestSettingsInput, Money};
+
+const SETTINGS_SELECT: &str = "SELECT account_id, rate, rate_type, day_count, compounding,
+         withholding_tax_rate, category_id, tax_category_id, created_at, updated_at
+         FROM interest_settings";
+
+fn row_to_settings(row: &rusqlite::Row) -> rusqlite::Result<InterestSettings> {
+    Ok(InterestSettings {
+        account_id: row.get(0)?,
+        rate: row.get(1)?,
+        rate_type: row.get(2)?,
+        day_count: row.get(3)?,
+        compounding: row.get(4)?,
+        withholding_tax_rate: row.get(5)?,
+        category_id: row.get(6)?,
+        tax_category_id: row.get(7)?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn load_account(conn: &Connection, account_id: i64) -> Result<Account, String> {
+    conn.query_row(&format!("{ACCOUNT_SELECT} WHERE id = ?1"), [account_id], |row| row_to_account(row))
+        .map_err(|e| e.to_string())
+}
+
+fn find_settings(conn: &Connection, account_id: i64) -> Result<Option<InterestSettings>, String> {
+    conn.query_row(&format!("{SETTINGS_SELECT} WHERE account_id = ?1"), [account_id], |row| row_to_settings(row))
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn set_settings(
+    conn: &Connection,
+    account_id: i64,
+    input: &InterestSettingsInput,
+) -> Result<InterestSettings, String> {
+    let account = load_account(conn, account_id)?;
+    if account.account_type == "loan" {
+        return Err("Loan interest is charged with each payment".into());
+    }
+    if account.account_type == "credit" && account.billing_cycle_day.is_none() {
+        return Err(format!("Set a billing cycle day for {} first", account.name));
+    }
+    if !input.rate.is_finite() || input.rate < 0.0 {
+        return Err("The rate cannot be negative".into());
+    }
+    if !RATE_TYPES.contains(&input.rate_type.as_str()) {
+        return Err(format!("Unknown rate type \"{}\"", input.rate_type));
+    }
+    if !DAY_COUNTS.contains(&input.day_count.as_str()) {
+        return Err(format!("Unknown day count \"{}\"", input.day_count));
+    }
+    if !COMPOUNDING_PERIODS.contains(&input.compounding.as_str()) {
+        return Err(format!("Unknown compounding period \"{}\"", input.compounding));
+    }
+    if !(0.0..=100.0).contains(&input.withholding_tax_rate) {
+        return Err("Withholding tax runs from 0 to 100 percent".into());
+    }
+
+    conn.execute(
+        "INSERT INTO interest_settings (account_id, rate, rate_type, day_count, compounding,
+           withholding_tax_rate, category_id, tax_category_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(account_id) DO UPDATE SET
+           rate = excluded.rate, rate_type = excluded.rate_type, day_count = excluded.day_count,
+           compounding = excluded.compounding, withholding_tax_rate = excluded.withholding_tax_rate,
+           category_id = excluded.category_id, tax_category_id = excluded.tax_category_id,
+           updated_at = datetime('now')",
+        rusqlite::params![
+            account_id,
+            input.rate,
+            input.rate_type,
+            input.day_count,
+            input.compounding,
+            input.withholding_tax_rate,
+            input.category_id,
+            input.tax_category_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    find_settings(conn, account_id)?.ok_or_else(|| "Interest settings were not saved".to_string())
+}
+
+/// Calendar months, quarters or years ending between `from` and `to`.
+/// Daily compounding is credited monthly.
+fn calendar_periods(conn: &Connection, compounding: &str, from: &str, to: &str) -> Result<Vec<(String, String)>, String> {
+    let months = match compounding {
+        "quarterly" => 3,
+        "annually" => 12,
+        _ => 1,
+    };
+    let year: i64 = from[0..4].parse().map_err(|_| format!("Invalid date \"{}\"", from))?;
+    let month: i64 = from[5..7].parse().map_err(|_| format!("Invalid date \"{}\"", from))?;
+    let mut start = format!("{:04}-{:02}-01", year, (month - 1) / months * months + 1);
+    let mut periods = Vec::new();
+    loop {
+        let (end, next): (String, String) = conn
+            .query_row(
+                "SELECT DATE(?1, ?2, '-1 day'), DATE(?1, ?2)",
+                rusqlite::params![start, format!("+{} months", months)],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        if end.as_str() > to {
+            break;
+        }
+        if end.as_str() >= from {
+            periods.push((start, end));
+        }
+        start = next;
+    }
+    Ok(periods)
+}
+
+/// Card cycles closing between `from` and `to` that carry a finance charge:
+/// those following a statement that was not paid in full before the cycle
+/// closed. A statement paid in full keeps its grace period.
+fn finance_charge_cycles(conn: &Connection, account: &Account, from: &str, to: &str) -> Result<Vec<(String, String)>, String> {
+    let span = |date: &str| -> i64 {
+        let year: i64 = date[0..4].parse().unwrap_or(0);
+        let month: i64 = date[5..7].parse().unwrap_or(0);
+        year * 12 + month
+    };
+    let count = span(to) - span(from) + 3;
+    let statements = card_statements(conn, account, to, count)?;
+    let cycles = statements
+        .windows(2)
+        .filter(|pair| pair[0].closing_date.as_str() >= from && pair[0].closing_date.as_str() <= to)
+        .filter(|pair| pair[1].amount_remaining.minor > 0)
+        .map(|pair| (pair[0].period_start.clone(), pair[0].closing_date.clone()))
+        .rev()
+        .collect();
+    Ok(cycles)
+}
+
+/// Average of the end-of-day balances from `start` to `end`, and the number
+/// of days. Days before the account was opened count as zero.
+fn average_daily_balance(conn: &Connection, account: &Account, start: &str, end: &str) -> Result<(i64, i64), String> {
+    let days: i64 = conn
+        .query_row(
+            "SELECT CAST(julianday(?2) - julianday(?1) AS INTEGER) + 1",
+            [start, end],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let samples = balance_samples(conn, Some(account.id), start, end, "daily")?;
+    let total: i64 = samples.iter().map(|sample| sample.balance.minor).sum();
+    Ok(((total as f64 / days as f64).round() as i64, days))
+}
+
+/// Transaction and withholding tax already posted for a period overlapping
+/// `start`..`end`.
+fn posted_for(conn: &Connection, account_id: i64, start: &str, end: &str) -> Result<Option<(i64, i64, i64)>, String> {
+    conn.query_row(
+        "SELECT p.transaction_id, t.amount, COALESCE(tax.amount, 0)
+         FROM interest_postings p
+         JOIN transactions t ON t.id = p.transaction_id
+         LEFT JOIN transactions tax ON tax.id = p.tax_transaction_id
+         WHERE p.account_id = ?1 AND p.period_start <= ?3 AND p.period_end >= ?2
+         LIMIT 1",
+        rusqlite::params![account_id, start, end],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+fn insert_charge(
+    conn: &Connection,
+    account_id: i64,
+    category_id: Option<i64>,
+    kind: &str,
+    amount: i64,
+    date: &str,
+    notes: &str,
+) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO transactions (account_id, category_id, type, amount, date, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![account_id, category_id, kind, amount, date, notes],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Works out interest for every period ending between `from` and `to` on
+/// each account with interest settings (or just `account_id`), and posts it
+/// unless `preview` is set. Asset accounts earn on their average daily
+/// balance each compounding period, less withholding tax; cards are charged
+/// on the average daily balance of each statement cycle. Periods already
+/// posted are reported and left alone; periods with nothing due are left
+/// out.
+pub(crate) fn accrue(
+    conn: &Connection,
+    from: &str,
+    to: &str,
+    account_id: Option<i64>,
+    preview: bool,
+) -> Result<Vec<InterestAccrual>, String> {
+    if from > to {
+        return Err("The start date must be on or before the end date".into());
+    }
+    let mut stmt = conn
+        .prepare(&format!(
+            "{SETTINGS_SELECT} WHERE account_id IN (SELECT id FROM accounts WHERE is_active = 1)
+               AND (?1 IS NULL OR account_id = ?1)
+             ORDER BY account_id"
+        ))
+        .map_err(|e| e.to_string())?;
+    let settings: Vec<InterestSettings> = stmt
+        .query_map([account_id], |row| row_to_settings(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut accruals = Vec::new();
+    for setting in settings {
+        let account = load_account(conn, setting.account_id)?;
+        let is_card = account.account_type == "credit";
+        let periods = if is_card {
+            finance_charge_cycles(conn, &account, from, to)?
+        } else {
+            calendar_periods(conn, &setting.compounding, from, to)?
+        };
+        let money = |minor: i64| Money::new(minor, account.currency.clone());
+
+        for (start, end) in periods {
+            let (average, actual_days) = average_daily_balance(conn, &account, &start, &end)?;
+            let days = if setting.day_count == "30/360" {
+                let after: String = conn
+                    .query_row("SELECT DATE(?1, '+1 day')", [&end], |r| r.get(0))
+                    .map_err(|e| e.to_string())?;
+                days_360(&start, &after)?
+            } else {
+                actual_days
+            };
+
+            let posted = posted_for(conn, account.id, &start, &end)?;
+            let (gross, tax) = match posted {
+                Some((_, amount, tax)) => (amount, tax),
+                None => {
+                    let gross = accrued_interest(
+                        average,
+                        setting.rate,
+                        &setting.rate_type,
+                        &setting.compounding,
+                        &setting.day_count,
+                        days,
+                    );
+                    (gross, if is_card { 0 } else { withholding_tax(gross, setting.withholding_tax_rate) })
+                }
+            };
+            let mut status = if posted.is_some() { "already_posted" } else { "preview" };
+            let mut transaction_id = posted.map(|(id, _, _)| id);
+            if gross <= 0 {
+                continue;
+            }
+
+            if !preview && status == "preview" {
+                let (kind, notes) = if is_card { ("expense", "Finance charge") } else { ("income", "Interest") };
+                let id = insert_charge(conn, account.id, setting.category_id, kind, gross, &end, notes)?;
+                let tax_id = if tax > 0 {
+                    Some(insert_charge(conn, account.id, setting.tax_category_id, "expense", tax, &end, "Withholding tax")?)
+                } else {
+                    None
+                };
+                conn.execute(
+                    "INSERT INTO interest_postings (account_id, period_start, period_end, transaction_id, tax_transaction_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    rusqlite::params![account.id, start, end, id, tax_id],
+                )
+                .map_err(|e| e.to_string())?;
+                status = "posted";
+                transaction_id = Some(id);
+            }
+
+            accruals.push(InterestAccrual {
+                account_id: account.id,
+                account_name: account.name.clone(),
+                kind: if is_card { "finance_charge" } else { "interest" }.to_string(),
+                period_start: start,
+                period_end: end,
+                average_daily_balance: money(average),
+                days,
+                gross: money(gross),
+                withholding_tax: money(tax),
+                net: money(gross - tax),
+                status: status.to_string(),
+                transaction_id,
+            });
+        }
+    }
+    Ok(accruals)
+}
+
+/// Interest settings of an account, if set.
+#[tauri::command]
+pub fn get_interest_settings(state: State<DbState>, account_id: i64) -> Result<Option<InterestSettings>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    find_settings(conn, account_id)
+}
+
+#[tauri::command]
+pub fn set_interest_settings(
+    state: State<DbState>,
+    account_id: i64,
+    input: InterestSettingsInput,
+) -> Result<InterestSettings, String> {
+    with_transaction(&state, |conn| set_settings(conn, account_id, &input))
+}
+
+/// Stops accruing; interest already posted stays.
+#[tauri::command]
+pub fn delete_interest_settings(state: State<DbState>, account_id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM interest_settings WHERE account_id = ?1", [account_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Posts interest and finance charges for periods ending from `date_from`
+/// to `date_to`. With `preview` set nothing is written.
+#[tauri::command]
+pub fn accrue_interest(
+    state: State<DbState>,
+    date_from: String,
+    date_to: String,
+    account_id: Option<i64>,
+    preview: Option<bool>,
+) -> Result<Vec<InterestAccrual>, String> {
+    let from = parse_date(date_from.trim(), "YYYY-MM-DD")?;
+    let to = parse_date(date_to.trim(), "YYYY-MM-DD")?;
+    if preview.unwrap_or(false) {
+        let guard = state.0.lock().map_err(|e| e.to_string())?;
+        let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+        return accrue(conn, &from, &to, account_id, true);
+    }
+    with_transaction(&state, |conn| accrue(conn, &from, &to, account_id, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    fn settings(rate: f64, withholding_tax_rate: f64) -> InterestSettingsInput {
+        InterestSettingsInput {
+            rate,
+            rate_type: "apr".into(),
+            day_count: "actual/360".into(),
+            compounding: "monthly".into(),
+            withholding_tax_rate,
+            category_id: None,
+            tax_category_id: None,
+        }
+    }
+
+    #[test]
+    fn accrual_posts_once_per_period_and_charges_carried_card_balances() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, opening_balance, created_at)
+               VALUES ('Savings', 'savings', 10000000, '2024-12-01 08:00:00');
+             INSERT INTO accounts (name, type, credit_limit, billing_cycle_day, payment_due_day, created_at)
+               VALUES ('Card', 'credit', 10000000, 31, 15, '2024-12-01 08:00:00');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'expense', 3000000, '2024-12-20');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'expense', 1000000, '2025-02-10');",
+        )
+        .unwrap();
+        set_settings(&conn, 1, &settings(3.6, 20.0)).unwrap();
+        let card = InterestSettingsInput { day_count: "actual/365".into(), ..settings(36.5, 0.0) };
+        set_settings(&conn, 2, &card).unwrap();
+
+        let preview = accrue(&conn, "2025-04-01", "2025-04-30", Some(1), true).unwrap();
+        assert_eq!(preview.len(), 1);
+        // ₱100,000.00 at 3.6% for April's 30 days, less 20% final tax.
+        assert_eq!(preview[0].gross.minor, 30_000);
+        assert_eq!(preview[0].withholding_tax.minor, 6_000);
+        assert_eq!(preview[0].status, "preview");
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM transactions", [], |r| r.get(0)).unwrap()
+        };
+        assert_eq!(count(&conn), 2);
+
+        let posted = accrue(&conn, "2025-04-01", "2025-04-30", Some(1), false).unwrap();
+        assert_eq!(posted[0].status, "posted");
+        assert_eq!(count(&conn), 4);
+        let again = accrue(&conn, "2025-04-15", "2025-04-30", None, false).unwrap();
+        let savings: Vec<&str> = again.iter().filter(|a| a.account_id == 1).map(|a| a.status.as_str()).collect();
+        assert_eq!(savings, ["already_posted"]);
+        // Only the card's April finance charge is new.
+        assert_eq!(count(&conn), 5);
+
+        // December's statement was never paid, so January and February are
+        // charged; the charge lands on the closing date.
+        let charges = accrue(&conn, "2025-01-01", "2025-02-28", Some(2), false).unwrap();
+        assert_eq!(charges.len(), 2);
+        assert_eq!(charges[0].kind, "finance_charge");
+        assert_eq!(charges[0].period_end, "2025-01-31");
+        assert_eq!(charges[0].average_daily_balance.minor, 3_000_000);
+        // 36.5% a year is 0.1% a day, compounded monthly.
+        assert_eq!(charges[0].gross.minor, 93_027);
+        assert_eq!(charges[1].period_start, "2025-02-01");
+    }
+}
@@ -0,0 +1,595 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::{row_to_account, ACCOUNT_SELECT};
+use crate::commands::imports::read_import_file;
+use crate::commands::reconciliation::require_unlocked;
+use crate::logic::balance::compute_balance;
+use crate::logic::csv_import::parse_date;
+use crate::logic::investments::{
+    build_position, parse_prices_csv, units_value, TradeEntry, COST_BASIS_METHODS,
+};
+use crate::models::{
+    normalize_currency, Account, Holding, HoldingLot, InvestmentPortfolio, InvestmentTrade, Money,
+    Security, SecurityPrice, TradeInput,
+};
+
+/// Condition on `transactions t` that leaves out the cash side of buys and
+/// sells, which only swap cash for securities. Dividends stay in as income.
+pub(crate) const NOT_TRADE_SETTLEMENT: &str = "(t.trade_id IS NULL OR EXISTS (
+       SELECT 1 FROM investment_trades it WHERE it.id = t.trade_id AND it.kind = 'dividend'))";
+
+const SECURITY_SELECT: &str = "SELECT id, symbol, name, currency, created_at FROM securities";
+
+fn row_to_security(row: &rusqlite::Row) -> rusqlite::Result<Security> {
+    Ok(Security {
+        id: row.get(0)?,
+        symbol: row.get(1)?,
+        name: row.get(2)?,
+        currency: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+const PRICE_SELECT: &str = "SELECT id, security_id, date, price, source, created_at FROM security_prices";
+
+fn row_to_price(row: &rusqlite::Row) -> rusqlite::Result<SecurityPrice> {
+    Ok(SecurityPrice {
+        id: row.get(0)?,
+        security_id: row.get(1)?,
+        date: row.get(2)?,
+        price: row.get(3)?,
+        source: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+const TRADE_SELECT: &str = "SELECT tr.id, tr.account_id, tr.security_id, tr.kind, tr.date, tr.quantity,
+         tr.price, tr.fees, tr.amount, tr.notes, tr.created_at, s.symbol, a.currency,
+         (SELECT t.id FROM transactions t WHERE t.trade_id = tr.id)
+         FROM investment_trades tr
+         JOIN securities s ON s.id = tr.security_id
+         JOIN accounts a ON a.id = tr.account_id";
+
+fn row_to_trade(row: &rusqlite::Row) -> rusqlite::Result<InvestmentTrade> {
+    let currency: String = row.get(12)?;
+    Ok(InvestmentTrade {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        security_id: row.get(2)?,
+        kind: row.get(3)?,
+        date: row.get(4)?,
+        quantity: row.get(5)?,
+        price: row.get(6)?,
+        fees: Money::new(row.get(7)?, currency.clone()),
+        amount: Money::new(row.get(8)?, currency),
+        notes: row.get(9)?,
+        created_at: row.get(10)?,
+        symbol: row.get(11)?,
+        transaction_id: row.get(13)?,
+    })
+}
+
+fn load_security(conn: &Connection, id: i64) -> Result<Security, String> {
+    conn.query_row(&format!("{SECURITY_SELECT} WHERE id = ?1"), [id], |row| row_to_security(row))
+        .map_err(|_| "Security not found".to_string())
+}
+
+fn investment_account(conn: &Connection, account_id: i64) -> Result<Account, String> {
+    let account = conn
+        .query_row(&format!("{ACCOUNT_SELECT} WHERE id = ?1"), [account_id], |row| row_to_account(row))
+        .map_err(|e| e.to_string())?;
+    if account.account_type != "investment" {
+        return Err(format!("{} is not an investment account", account.name));
+    }
+    Ok(account)
+}
+
+/// Stores the price of `security_id` on `date`, replacing any price already
+/// entered for that day.
+fn upsert_price(conn: &Connection, security_id: i64, date: &str, price: f64, source: &str) -> Result<i64, String> {
+    if !(price.is_finite() && price > 0.0) {
+        return Err("Prices must be positive".into());
+    }
+    conn.execute(
+        "INSERT INTO security_prices (security_id, date, price, source) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (security_id, date) DO UPDATE SET price = excluded.price, source = excluded.source",
+        rusqlite::params![security_id, date, price, source],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT id FROM security_prices WHERE security_id = ?1 AND date = ?2",
+        rusqlite::params![security_id, date],
+        |r| r.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Latest price on or before `date` with the day it was set, falling back to
+/// the price of the latest buy or sell when none was entered.
+fn latest_price(conn: &Connection, security_id: i64, date: &str) -> Result<Option<(f64, String)>, String> {
+    conn.query_row(
+        "SELECT price, date FROM (
+           SELECT price, date, 1 AS preferred FROM security_prices WHERE security_id = ?1 AND date <= ?2
+           UNION ALL
+           SELECT price, date, 0 FROM investment_trades
+           WHERE security_id = ?1 AND date <= ?2 AND kind IN ('buy', 'sell')
+         )
+         ORDER BY preferred DESC, date DESC
+         LIMIT 1",
+        rusqlite::params![security_id, date],
+        |r| Ok((r.get(0)?, r.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// One security's trades in an account up to and including `until`, oldest
+/// first.
+fn trade_entries(conn: &Connection, account_id: i64, security_id: i64, until: &str) -> Result<Vec<TradeEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, date, quantity, amount FROM investment_trades
+             WHERE account_id = ?1 AND security_id = ?2 AND date <= ?3
+             ORDER BY date, id",
+        )
+        .map_err(|e| e.to_string())?;
+    let entries = stmt
+        .query_map(rusqlite::params![account_id, security_id, until], |r| {
+            Ok(TradeEntry { kind: r.get(0)?, date: r.get(1)?, quantity: r.get(2)?, amount: r.get(3)? })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(entries)
+}
+
+/// Fails when a security's trades in an account sell more than was held.
+fn check_position(conn: &Connection, account_id: i64, security_id: i64) -> Result<(), String> {
+    build_position(&trade_entries(conn, account_id, security_id, "9999-12-31")?, "fifo").map(|_| ())
+}
+
+/// Records a trade and posts its cash: a buy is an expense of the cost plus
+/// fees, a sell income of the proceeds less fees, and a dividend income of
+/// the amount received.
+pub(crate) fn record_trade(conn: &Connection, input: &TradeInput) -> Result<InvestmentTrade, String> {
+    let account = investment_account(conn, input.account_id)?;
+    let security = load_security(conn, input.security_id)?;
+    if security.currency != account.currency {
+        return Err(format!("{} trades in {}, but {} holds {}", security.symbol, security.currency, account.name, account.currency));
+    }
+    let date = parse_date(input.date.trim(), "YYYY-MM-DD")?;
+    let fees = match &input.fees {
+        Some(fees) => {
+            fees.require_currency(&account.currency)?;
+            if fees.minor < 0 {
+                return Err("Fees cannot be negative".into());
+            }
+            fees.minor
+        }
+        None => 0,
+    };
+
+    let (quantity, price, amount) = match input.kind.as_str() {
+        "buy" | "sell" => {
+            if !(input.quantity.is_finite() && input.quantity > 0.0) {
+                return Err("Enter how many units were traded".into());
+            }
+            if !(input.price.is_finite() && input.price > 0.0) {
+                return Err("Enter the price per unit".into());
+            }
+            let value = units_value(input.quantity, input.price, &account.currency);
+            let amount = if input.kind == "buy" { value + fees } else { value - fees };
+            if amount < 0 {
+                return Err("Fees cannot exceed the proceeds".into());
+            }
+            (input.quantity, input.price, amount)
+        }
+        "dividend" => {
+            let amount = input.amount.as_ref().ok_or("Enter the dividend received")?;
+            amount.require_currency(&account.currency)?;
+            if amount.minor - fees <= 0 {
+                return Err("Dividends must be more than the fees".into());
+            }
+            (0.0, 0.0, amount.minor - fees)
+        }
+        other => return Err(format!("Unknown trade kind \"{}\"", other)),
+    };
+
+    let notes = input.notes.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    conn.execute(
+        "INSERT INTO investment_trades (account_id, security_id, kind, date, quantity, price, fees, amount, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![account.id, security.id, input.kind, date, quantity, price, fees, amount, notes],
+    )
+    .map_err(|e| e.to_string())?;
+    let trade_id = conn.last_insert_rowid();
+
+    let (tx_type, category_id, description) = match input.kind.as_str() {
+        "buy" => ("expense", None, format!("Buy {} {} @ {}", quantity, security.symbol, price)),
+        "sell" => ("income", None, format!("Sell {} {} @ {}", quantity, security.symbol, price)),
+        _ => ("income", input.category_id, format!("{} dividend", security.symbol)),
+    };
+    conn.execute(
+        "INSERT INTO transactions (account_id, category_id, type, amount, date, notes, trade_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![account.id, category_id, tx_type, amount, date, notes.unwrap_or(&description), trade_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    check_position(conn, account.id, security.id)?;
+    conn.query_row(&format!("{TRADE_SELECT} WHERE tr.id = ?1"), [trade_id], |row| row_to_trade(row))
+        .map_err(|e| e.to_string())
+}
+
+/// Refuses editing a trade's cash transaction directly: its amount, account
+/// and type must stay in step with the trade and the cost basis.
+pub(crate) fn require_no_trade(conn: &Connection, transaction_id: i64) -> Result<(), String> {
+    let trade_id: Option<i64> = conn
+        .query_row("SELECT trade_id FROM transactions WHERE id = ?1", [transaction_id], |r| r.get(0))
+        .map_err(|_| "Transaction not found".to_string())?;
+    if trade_id.is_some() {
+        return Err("This is the cash side of an investment trade; delete the trade and record it again".into());
+    }
+    Ok(())
+}
+
+/// Deletes a trade with its cash transaction and any receipts attached to it.
+pub(crate) fn remove_trade(conn: &Connection, id: i64) -> Result<(), String> {
+    let (account_id, security_id): (i64, i64) = conn
+        .query_row(
+            "SELECT account_id, security_id FROM investment_trades WHERE id = ?1",
+            [id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .map_err(|_| "Trade not found".to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT id FROM transactions WHERE trade_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let transaction_ids: Vec<i64> = stmt
+        .query_map([id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    require_unlocked(conn, &transaction_ids)?;
+    conn.execute(
+        "DELETE FROM attachments WHERE transaction_id IN (SELECT id FROM transactions WHERE trade_id = ?1)",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM transactions WHERE trade_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM investment_trades WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    // Removing a buy must not leave a later sale short of units.
+    check_position(conn, account_id, security_id)
+}
+
+/// Every security traded in an account up to `as_of`, valued at the end of
+/// that day with cost tracked by `method`.
+fn holdings(conn: &Connection, account_id: i64, currency: &str, as_of: &str, method: &str) -> Result<Vec<Holding>, String> {
+    let money = |minor: i64| Money::new(minor, currency);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "{SECURITY_SELECT} WHERE id IN (
+               SELECT security_id FROM investment_trades WHERE account_id = ?1 AND date <= ?2)
+             ORDER BY symbol"
+        ))
+        .map_err(|e| e.to_string())?;
+    let securities: Vec<Security> = stmt
+        .query_map(rusqlite::params![account_id, as_of], |row| row_to_security(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut holdings = Vec::new();
+    for security in securities {
+        let position = build_position(&trade_entries(conn, account_id, security.id, as_of)?, method)?;
+        let quote = latest_price(conn, security.id, as_of)?;
+        let market_value = match &quote {
+            Some((price, _)) => units_value(position.quantity, *price, currency),
+            None => 0,
+        };
+        holdings.push(Holding {
+            security_id: security.id,
+            symbol: security.symbol,
+            name: security.name,
+            quantity: position.quantity,
+            cost_basis: money(position.cost_basis),
+            price: quote.as_ref().map(|(price, _)| *price),
+            price_date: quote.map(|(_, date)| date),
+            market_value: money(market_value),
+            unrealized_gain: money(if position.quantity > 0.0 { market_value - position.cost_basis } else { 0 }),
+            realized_gain: money(position.realized_gain),
+            dividends: money(position.dividends),
+            lots: position
+                .lots
+                .into_iter()
+                .map(|lot| HoldingLot { date: lot.date, quantity: lot.quantity, cost: money(lot.cost) })
+                .collect(),
+        });
+    }
+    Ok(holdings)
+}
+
+/// Cash and holdings of an investment account as they stood at the end of
+/// `as_of`, with cost tracked by `method` ('fifo' or 'average').
+pub(crate) fn portfolio(conn: &Connection, account: &Account, as_of: &str, method: &str) -> Result<InvestmentPortfolio, String> {
+    if !COST_BASIS_METHODS.contains(&method) {
+        return Err(format!("Unknown cost basis method \"{}\"", method));
+    }
+    let money = |minor: i64| Money::new(minor, account.currency.clone());
+    let holdings = holdings(conn, account.id, &account.currency, as_of, method)?;
+    let cash = compute_balance(conn, account, Some(as_of));
+    let market_value: i64 = holdings.iter().map(|h| h.market_value.minor).sum();
+    Ok(InvestmentPortfolio {
+        account_id: account.id,
+        as_of: as_of.to_string(),
+        cost_basis_method: method.to_string(),
+        total_value: money(cash.minor + market_value),
+        market_value: money(market_value),
+        unrealized_gain: money(holdings.iter().map(|h| h.unrealized_gain.minor).sum()),
+        realized_gain: money(holdings.iter().map(|h| h.realized_gain.minor).sum()),
+        dividends: money(holdings.iter().map(|h| h.dividends.minor).sum()),
+        cash,
+        holdings,
+    })
+}
+
+/// Market value of the securities held in an account at the end of `date`,
+/// in the account's currency.
+pub(crate) fn holdings_value(conn: &Connection, account_id: i64, currency: &str, date: &str) -> Result<i64, String> {
+    Ok(holdings(conn, account_id, currency, date, "fifo")?
+        .iter()
+        .map(|h| h.market_value.minor)
+        .sum())
+}
+
+#[tauri::command]
+pub fn list_securities(state: State<DbState>) -> Result<Vec<Security>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{SECURITY_SELECT} ORDER BY symbol"))
+        .map_err(|e| e.to_string())?;
+    let securities = stmt
+        .query_map([], |row| row_to_security(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(securities)
+}
+
+#[tauri::command]
+pub fn create_security(
+    state: State<DbState>,
+    symbol: String,
+    name: Option<String>,
+    currency: String,
+) -> Result<Security, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.is_empty() {
+        return Err("Symbol is required".into());
+    }
+    let name = name.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    conn.execute(
+        "INSERT INTO securities (symbol, name, currency) VALUES (?1, ?2, ?3)",
+        rusqlite::params![symbol, name, normalize_currency(&currency)?],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            format!("{} already exists", symbol)
+        } else {
+            e.to_string()
+        }
+    })?;
+    load_security(conn, conn.last_insert_rowid())
+}
+
+/// Deletes a security that was never traded, with its prices.
+#[tauri::command]
+pub fn delete_security(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let trades: i64 = conn
+        .query_row("SELECT COUNT(*) FROM investment_trades WHERE security_id = ?1", [id], |r| r.get(0))
+        .map_err(|e| e.to_string())?;
+    if trades > 0 {
+        return Err("This security has trades; delete them first".into());
+    }
+    conn.execute("DELETE FROM securities WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Prices of one security, newest first.
+#[tauri::command]
+pub fn list_security_prices(state: State<DbState>, security_id: i64) -> Result<Vec<SecurityPrice>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{PRICE_SELECT} WHERE security_id = ?1 ORDER BY date DESC"))
+        .map_err(|e| e.to_string())?;
+    let prices = stmt
+        .query_map([security_id], |row| row_to_price(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(prices)
+}
+
+/// Records that one unit of a security was worth `price` from `date` on.
+#[tauri::command]
+pub fn set_security_price(
+    state: State<DbState>,
+    security_id: i64,
+    date: String,
+    price: f64,
+) -> Result<SecurityPrice, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    load_security(conn, security_id)?;
+    let id = upsert_price(conn, security_id, &parse_date(&date, "YYYY-MM-DD")?, price, "manual")?;
+    conn.query_row(&format!("{PRICE_SELECT} WHERE id = ?1"), [id], |row| row_to_price(row))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_security_price(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM security_prices WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Imports `date,symbol,price` rows from a CSV file. Every symbol must
+/// already exist; prices already stored for the same day are replaced.
+/// Returns the number of rows read.
+#[tauri::command]
+pub fn import_security_prices_csv(state: State<DbState>, file_path: String) -> Result<i64, String> {
+    let rows = parse_prices_csv(&read_import_file(&file_path)?)?;
+    if rows.is_empty() {
+        return Err("The file has no prices".into());
+    }
+    with_transaction(&state, |conn| {
+        for row in &rows {
+            let security_id: i64 = conn
+                .query_row("SELECT id FROM securities WHERE symbol = ?1", [&row.symbol], |r| r.get(0))
+                .optional()
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Unknown symbol {}; add the security first", row.symbol))?;
+            upsert_price(conn, security_id, &row.date, row.price, "csv")?;
+        }
+        Ok(rows.len() as i64)
+    })
+}
+
+/// Trades in an account, newest first.
+#[tauri::command]
+pub fn list_investment_trades(state: State<DbState>, account_id: i64) -> Result<Vec<InvestmentTrade>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{TRADE_SELECT} WHERE tr.account_id = ?1 ORDER BY tr.date DESC, tr.id DESC"))
+        .map_err(|e| e.to_string())?;
+    let trades = stmt
+        .query_map([account_id], |row| row_to_trade(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(trades)
+}
+
+#[tauri::command]
+pub fn record_investment_trade(state: State<DbState>, input: TradeInput) -> Result<InvestmentTrade, String> {
+    with_transaction(&state, |conn| record_trade(conn, &input))
+}
+
+#[tauri::command]
+pub fn delete_investment_trade(state: State<DbState>, id: i64) -> Result<(), String> {
+    with_transaction(&state, |conn| remove_trade(conn, id))
+}
+
+/// Holdings, market value and gains of an investment account on `as_of`
+/// (default today), with cost tracked FIFO unless `cost_basis_method` is
+/// "average".
+#[tauri::command]
+pub fn get_investment_portfolio(
+    state: State<DbState>,
+    account_id: i64,
+    as_of: Option<String>,
+    cost_basis_method: Option<String>,
+) -> Result<InvestmentPortfolio, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let account = investment_account(conn, account_id)?;
+    let as_of = match as_of {
+        Some(date) => parse_date(&date, "YYYY-MM-DD")?,
+        None => conn
+            .query_row("SELECT DATE('now')", [], |r| r.get(0))
+            .map_err(|e| e.to_string())?,
+    };
+    portfolio(conn, &account, &as_of, cost_basis_method.as_deref().unwrap_or("fifo"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    fn trade(kind: &str, date: &str, quantity: f64, price: f64) -> TradeInput {
+        TradeInput {
+            account_id: 1,
+            security_id: 1,
+            kind: kind.into(),
+            date: date.into(),
+            quantity,
+            price,
+            fees: None,
+            amount: None,
+            category_id: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn trades_move_cash_and_portfolio_values_at_market() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, opening_balance) VALUES ('Broker', 'investment', 10000000);
+             INSERT INTO securities (symbol, currency) VALUES ('JFC', 'PHP');",
+        )
+        .unwrap();
+
+        let buy = TradeInput { fees: Some(Money::new(2000, "PHP")), ..trade("buy", "2025-01-10", 100.0, 200.0) };
+        let buy = record_trade(&conn, &buy).unwrap();
+        assert_eq!(buy.amount.minor, 2_002_000);
+        assert!(buy.transaction_id.is_some());
+        assert!(require_no_trade(&conn, buy.transaction_id.unwrap()).is_err());
+        record_trade(&conn, &trade("buy", "2025-02-10", 100.0, 250.0)).unwrap();
+        record_trade(&conn, &trade("sell", "2025-03-10", 150.0, 260.0)).unwrap();
+        {
+            // The command's transaction rolls back a rejected sale.
+            let tx = conn.unchecked_transaction().unwrap();
+            assert!(record_trade(&tx, &trade("sell", "2025-03-11", 60.0, 260.0)).is_err());
+        }
+        upsert_price(&conn, 1, "2025-03-31", 280.0, "manual").unwrap();
+
+        let account = investment_account(&conn, 1).unwrap();
+        let fifo = portfolio(&conn, &account, "2025-04-01", "fifo").unwrap();
+        assert_eq!(fifo.cash.minor, 10_000_000 - 2_002_000 - 2_500_000 + 3_900_000);
+        let jfc = &fifo.holdings[0];
+        assert_eq!(jfc.quantity, 50.0);
+        assert_eq!(jfc.price_date.as_deref(), Some("2025-03-31"));
+        assert_eq!(jfc.market_value.minor, 1_400_000);
+        assert_eq!(jfc.cost_basis.minor, 1_250_000);
+        assert_eq!(jfc.unrealized_gain.minor, 150_000);
+        assert_eq!(jfc.realized_gain.minor, 3_900_000 - 2_002_000 - 1_250_000);
+        assert_eq!(fifo.total_value.minor, fifo.cash.minor + 1_400_000);
+
+        // Before the price was entered, the last trade price values the holding.
+        let earlier = portfolio(&conn, &account, "2025-03-15", "average").unwrap();
+        assert_eq!(earlier.holdings[0].price, Some(260.0));
+        assert_eq!(holdings_value(&conn, 1, "PHP", "2025-03-15").unwrap(), 1_300_000);
+
+        // Deleting the first buy would leave the sale short of units.
+        {
+            let tx = conn.unchecked_transaction().unwrap();
+            assert!(remove_trade(&tx, buy.id).is_err());
+        }
+
+        // Buys and sells stay out of spending; dividends count as income.
+        let settlements: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM transactions t WHERE {NOT_TRADE_SETTLEMENT}"),
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(settlements, 0);
+    }
+}
@@ -0,0 +1,367 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::{row_to_account, ACCOUNT_SELECT};
+use crate::commands::transfers::insert_transfer;
+use crate::logic::balance::compute_balance;
+use crate::logic::credit_cards::day_in_month;
+use crate::logic::csv_import::parse_date;
+use crate::logic::loans::{
+    amortize, level_payment, period_interest, periodic_rate, periods_per_year, MAX_SCHEDULE_ROWS,
+    PAYMENT_FREQUENCIES,
+};
+use crate::models::{Account, AmortizationRow, Loan, LoanPayment, LoanSchedule, LoanTermsInput, Money};
+
+const LOAN_SELECT: &str = "SELECT l.account_id, l.principal, l.annual_rate, l.term_payments,
+         l.payment_frequency, l.first_payment_date, l.interest_category_id, l.created_at,
+         l.updated_at, a.currency
+         FROM loans l
+         JOIN accounts a ON a.id = l.account_id";
+
+fn row_to_loan(row: &rusqlite::Row) -> rusqlite::Result<Loan> {
+    let currency: String = row.get(9)?;
+    let principal: i64 = row.get(1)?;
+    let annual_rate: f64 = row.get(2)?;
+    let term_payments: i64 = row.get(3)?;
+    let payment_frequency: String = row.get(4)?;
+    let rate = periodic_rate(annual_rate, periods_per_year(&payment_frequency).unwrap_or(12));
+    Ok(Loan {
+        account_id: row.get(0)?,
+        principal: Money::new(principal, currency.clone()),
+        annual_rate,
+        term_payments,
+        payment_frequency,
+        first_payment_date: row.get(5)?,
+        interest_category_id: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+        regular_payment: Money::new(level_payment(principal, rate, term_payments), currency),
+    })
+}
+
+fn loan_account(conn: &Connection, account_id: i64) -> Result<Account, String> {
+    let account = conn
+        .query_row(&format!("{ACCOUNT_SELECT} WHERE id = ?1"), [account_id], |row| row_to_account(row))
+        .map_err(|e| e.to_string())?;
+    if account.account_type != "loan" {
+        return Err(format!("{} is not a loan", account.name));
+    }
+    Ok(account)
+}
+
+pub(crate) fn load_loan(conn: &Connection, account_id: i64) -> Result<Loan, String> {
+    conn.query_row(&format!("{LOAN_SELECT} WHERE l.account_id = ?1"), [account_id], |row| row_to_loan(row))
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Set the loan terms first".to_string())
+}
+
+/// Due date of payment `index` (0-based) counting from the first payment.
+/// Monthly and quarterly payments keep the first payment's day of the month,
+/// clamped to shorter months.
+fn payment_date(conn: &Connection, loan: &Loan, index: i64) -> Result<String, String> {
+    let first = loan.first_payment_date.as_str();
+    let day: i64 = first[8..10].parse().map_err(|_| format!("Invalid date \"{}\"", first))?;
+    match loan.payment_frequency.as_str() {
+        "weekly" | "biweekly" => {
+            let step = if loan.payment_frequency == "weekly" { 7 } else { 14 };
+            conn.query_row(
+                "SELECT DATE(?1, ?2)",
+                rusqlite::params![first, format!("+{} days", step * index)],
+                |r| r.get(0),
+            )
+            .map_err(|e| e.to_string())
+        }
+        "quarterly" => day_in_month(conn, first, 3 * index, day),
+        _ => day_in_month(conn, first, index, day),
+    }
+}
+
+fn loan_rate(loan: &Loan) -> f64 {
+    periodic_rate(loan.annual_rate, periods_per_year(&loan.payment_frequency).unwrap_or(12))
+}
+
+pub(crate) fn set_terms(conn: &Connection, account_id: i64, input: &LoanTermsInput) -> Result<Loan, String> {
+    let account = loan_account(conn, account_id)?;
+    input.principal.require_currency(&account.currency)?;
+    if input.principal.minor <= 0 {
+        return Err("The principal must be more than zero".into());
+    }
+    if !input.annual_rate.is_finite() || input.annual_rate < 0.0 {
+        return Err("The interest rate cannot be negative".into());
+    }
+    if !(1..=MAX_SCHEDULE_ROWS as i64).contains(&input.term_payments) {
+        return Err(format!("The term must be between 1 and {} payments", MAX_SCHEDULE_ROWS));
+    }
+    if !PAYMENT_FREQUENCIES.contains(&input.payment_frequency.as_str()) {
+        return Err(format!("Unknown payment frequency \"{}\"", input.payment_frequency));
+    }
+    let first_payment_date = parse_date(input.first_payment_date.trim(), "YYYY-MM-DD")?;
+
+    conn.execute(
+        "INSERT INTO loans (account_id, principal, annual_rate, term_payments, payment_frequency,
+           first_payment_date, interest_category_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(account_id) DO UPDATE SET
+           principal = excluded.principal, annual_rate = excluded.annual_rate,
+           term_payments = excluded.term_payments, payment_frequency = excluded.payment_frequency,
+           first_payment_date = excluded.first_payment_date,
+           interest_category_id = excluded.interest_category_id, updated_at = datetime('now')",
+        rusqlite::params![
+            account_id,
+            input.principal.minor,
+            input.annual_rate,
+            input.term_payments,
+            input.payment_frequency,
+            first_payment_date,
+            input.interest_category_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    load_loan(conn, account_id)
+}
+
+/// Records a payment from `from_account_id` into a loan. The full amount
+/// moves as a transfer; the interest part is charged back to the loan as a
+/// categorized expense, so only the rest reduces the balance. Interest
+/// defaults to one period's interest on the balance owed that day; pass zero
+/// for an extra payment that goes entirely to principal.
+pub(crate) fn record_payment(
+    conn: &Connection,
+    account_id: i64,
+    from_account_id: i64,
+    amount: &Money,
+    date: &str,
+    interest: Option<&Money>,
+    notes: Option<&str>,
+) -> Result<LoanPayment, String> {
+    let account = loan_account(conn, account_id)?;
+    let loan = load_loan(conn, account_id)?;
+    amount.require_currency(&account.currency)?;
+    if amount.minor <= 0 {
+        return Err("Payments must be positive".into());
+    }
+    let interest = match interest {
+        Some(interest) => {
+            interest.require_currency(&account.currency)?;
+            if interest.minor < 0 || interest.minor > amount.minor {
+                return Err("Interest must be between zero and the payment".into());
+            }
+            interest.minor
+        }
+        None => {
+            let owed = compute_balance(conn, &account, Some(date)).minor;
+            period_interest(owed, loan_rate(&loan)).min(amount.minor)
+        }
+    };
+
+    let transfer = insert_transfer(conn, from_account_id, account_id, amount, None, None, date, notes, "regular")?;
+    if interest > 0 {
+        conn.execute(
+            "INSERT INTO transactions (account_id, category_id, type, amount, date, notes)
+             VALUES (?1, ?2, 'expense', ?3, ?4, 'Loan interest')",
+            rusqlite::params![account_id, loan.interest_category_id, interest, date],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE transfers SET interest_transaction_id = ?1 WHERE id = ?2",
+            rusqlite::params![conn.last_insert_rowid(), transfer.id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(LoanPayment {
+        transfer_id: transfer.id,
+        date: transfer.date,
+        principal: Money::new(amount.minor - interest, account.currency.clone()),
+        interest: Money::new(interest, account.currency.clone()),
+        amount: transfer.to_amount,
+    })
+}
+
+/// Payments into a loan, oldest first.
+fn loan_payments(conn: &Connection, account: &Account) -> Result<Vec<LoanPayment>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT tr.id, tr.date, tr.to_amount, COALESCE(i.amount, 0)
+             FROM transfers tr
+             LEFT JOIN transactions i ON i.id = tr.interest_transaction_id
+             WHERE tr.to_account_id = ?1
+             ORDER BY tr.date, tr.id",
+        )
+        .map_err(|e| e.to_string())?;
+    let payments = stmt
+        .query_map([account.id], |r| {
+            let amount: i64 = r.get(2)?;
+            let interest: i64 = r.get(3)?;
+            Ok(LoanPayment {
+                transfer_id: r.get(0)?,
+                date: r.get(1)?,
+                amount: Money::new(amount, account.currency.clone()),
+                principal: Money::new(amount - interest, account.currency.clone()),
+                interest: Money::new(interest, account.currency.clone()),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(payments)
+}
+
+/// Payments made so far, then the regular payment projected on the
+/// scheduled dates after the latest payment until the balance reaches zero.
+/// Extra payments lower the balance, so the projection ends sooner.
+pub(crate) fn loan_schedule(conn: &Connection, account_id: i64) -> Result<LoanSchedule, String> {
+    let account = loan_account(conn, account_id)?;
+    let loan = load_loan(conn, account_id)?;
+    let currency = account.currency.clone();
+    let money = |minor: i64| Money::new(minor, currency.clone());
+
+    let payments = loan_payments(conn, &account)?;
+    let mut rows = Vec::new();
+    for payment in &payments {
+        rows.push(AmortizationRow {
+            balance: compute_balance(conn, &account, Some(&payment.date)),
+            date: payment.date.clone(),
+            payment: payment.amount.clone(),
+            principal: payment.principal.clone(),
+            interest: payment.interest.clone(),
+            paid: true,
+        });
+    }
+
+    let mut index = 0;
+    if let Some(last) = payments.last() {
+        while payment_date(conn, &loan, index)? <= last.date {
+            index += 1;
+        }
+    }
+
+    let balance = compute_balance(conn, &account, None);
+    let projection = amortize(balance.minor, loan_rate(&loan), loan.regular_payment.minor)?;
+    let remaining_payments = projection.len() as i64;
+    for (offset, step) in projection.into_iter().enumerate() {
+        rows.push(AmortizationRow {
+            date: payment_date(conn, &loan, index + offset as i64)?,
+            payment: money(step.payment),
+            principal: money(step.principal),
+            interest: money(step.interest),
+            balance: money(step.balance),
+            paid: false,
+        });
+    }
+
+    let payoff_date = if balance.minor > 0 {
+        rows.last().map(|row| row.date.clone())
+    } else {
+        payments.last().map(|payment| payment.date.clone())
+    };
+    let total_interest = money(rows.iter().map(|row| row.interest.minor).sum());
+    Ok(LoanSchedule {
+        loan,
+        balance,
+        rows,
+        remaining_payments,
+        payoff_date,
+        total_interest,
+    })
+}
+
+/// Terms of a loan account, if set.
+#[tauri::command]
+pub fn get_loan(state: State<DbState>, account_id: i64) -> Result<Option<Loan>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.query_row(&format!("{LOAN_SELECT} WHERE l.account_id = ?1"), [account_id], |row| row_to_loan(row))
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_loan_terms(state: State<DbState>, account_id: i64, input: LoanTermsInput) -> Result<Loan, String> {
+    with_transaction(&state, |conn| set_terms(conn, account_id, &input))
+}
+
+#[tauri::command]
+pub fn get_loan_schedule(state: State<DbState>, account_id: i64) -> Result<LoanSchedule, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    loan_schedule(conn, account_id)
+}
+
+/// Pays into a loan from another account. `interest` overrides the
+/// computed interest part; zero makes it an extra principal payment.
+#[tauri::command]
+pub fn record_loan_payment(
+    state: State<DbState>,
+    account_id: i64,
+    from_account_id: i64,
+    amount: Money,
+    date: String,
+    interest: Option<Money>,
+    notes: Option<String>,
+) -> Result<LoanPayment, String> {
+    with_transaction(&state, |conn| {
+        let date = parse_date(date.trim(), "YYYY-MM-DD")?;
+        record_payment(conn, account_id, from_account_id, &amount, &date, interest.as_ref(), notes.as_deref())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn payments_split_interest_and_extra_payments_shorten_the_schedule() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Interest', 'expense');
+             INSERT INTO accounts (name, type, opening_balance) VALUES ('Bank', 'debit', 50000000);
+             INSERT INTO accounts (name, type, opening_balance) VALUES ('Car Loan', 'loan', 10000000);",
+        )
+        .unwrap();
+        let terms = LoanTermsInput {
+            principal: Money::new(10_000_000, "PHP"),
+            annual_rate: 12.0,
+            term_payments: 12,
+            payment_frequency: "monthly".into(),
+            first_payment_date: "2025-01-31".into(),
+            interest_category_id: Some(1),
+        };
+        assert!(set_terms(&conn, 1, &terms).is_err());
+        let loan = set_terms(&conn, 2, &terms).unwrap();
+        assert_eq!(loan.regular_payment.minor, 888_488);
+
+        let schedule = loan_schedule(&conn, 2).unwrap();
+        assert_eq!(schedule.remaining_payments, 12);
+        assert_eq!(schedule.rows[1].date, "2025-02-28");
+        assert_eq!(schedule.payoff_date.as_deref(), Some("2025-12-31"));
+
+        let payment = record_payment(&conn, 2, 1, &Money::new(888_488, "PHP"), "2025-01-31", None, None).unwrap();
+        assert_eq!(payment.interest.minor, 100_000);
+        assert_eq!(payment.principal.minor, 788_488);
+        let schedule = loan_schedule(&conn, 2).unwrap();
+        assert_eq!(schedule.balance.minor, 10_000_000 - 788_488);
+        assert!(schedule.rows[0].paid);
+        assert_eq!(schedule.rows[1].date, "2025-02-28");
+        assert_eq!(schedule.remaining_payments, 11);
+
+        // An extra principal payment mid-month keeps the next due date but ends the loan sooner.
+        let extra = Money::new(3_000_000, "PHP");
+        record_payment(&conn, 2, 1, &extra, "2025-02-10", Some(&Money::new(0, "PHP")), None).unwrap();
+        let schedule = loan_schedule(&conn, 2).unwrap();
+        assert_eq!(schedule.rows[2].date, "2025-02-28");
+        assert_eq!(schedule.remaining_payments, 8);
+        assert_eq!(schedule.payoff_date.as_deref(), Some("2025-09-30"));
+
+        let interest_expense: i64 = conn
+            .query_row(
+                "SELECT SUM(amount) FROM transactions WHERE account_id = 2 AND category_id = 1",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(interest_expense, 100_000);
+    }
+}
@@ -1,7 +1,23 @@
 pub mod accounts;
+pub mod attachments;
 pub mod auth;
+pub mod budgets;
 pub mod categories;
+pub mod credit_cards;
+pub mod currencies;
 pub mod data;
+pub mod duplicates;
+pub mod envelopes;
+pub mod imports;
+pub mod installments;
+pub mod interest;
+pub mod investments;
+pub mod loans;
+pub mod payees;
+pub mod reconciliation;
 pub mod reports;
+pub mod rules;
+pub mod savings;
+pub mod tags;
 pub mod transactions;
 pub mod transfers;
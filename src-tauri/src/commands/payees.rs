@@ -0,0 +1,364 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::models::{Payee, PayeeAlias};
+
+const PAYEE_SELECT: &str = "SELECT p.id, p.name, p.created_at,
+         (SELECT COUNT(*) FROM transactions t WHERE t.payee_id = p.id)
+         FROM payees p";
+
+fn row_to_payee(row: &rusqlite::Row) -> rusqlite::Result<Payee> {
+    Ok(Payee {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+        transaction_count: row.get(3)?,
+    })
+}
+
+fn row_to_alias(row: &rusqlite::Row) -> rusqlite::Result<PayeeAlias> {
+    Ok(PayeeAlias {
+        id: row.get(0)?,
+        payee_id: row.get(1)?,
+        pattern: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+/// Reduces a raw merchant string to a comparable key: lowercase words with
+/// punctuation, `#123`-style branch numbers and trailing store codes dropped.
+/// "JOLLIBEE #123" and "Jollibee" both become "jollibee".
+pub(crate) fn normalize_key(raw: &str) -> String {
+    let lower = raw.to_lowercase();
+    let mut words: Vec<&str> = Vec::new();
+    let mut after_hash = false;
+    for word in lower.split(|c: char| !c.is_alphanumeric() && c != '#') {
+        let numbered = word.starts_with('#');
+        let word = word.trim_matches('#');
+        if word.is_empty() {
+            after_hash = numbered;
+            continue;
+        }
+        if !((numbered || after_hash) && word.chars().all(|c| c.is_ascii_digit())) {
+            words.push(word);
+        }
+        after_hash = false;
+    }
+    while words.len() > 1 && words.last().is_some_and(|w| w.chars().all(|c| c.is_ascii_digit())) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// True when `pattern`'s words appear, in order and contiguously, in `key`.
+fn key_matches(key: &str, pattern: &str) -> bool {
+    format!(" {key} ").contains(&format!(" {pattern} "))
+}
+
+fn display_name(raw: &str) -> Result<String, String> {
+    let name = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("Payee name is required".into());
+    }
+    Ok(name)
+}
+
+fn load_payee(conn: &Connection, id: i64) -> Result<Payee, String> {
+    conn.query_row(&format!("{PAYEE_SELECT} WHERE p.id = ?1"), [id], |row| row_to_payee(row))
+        .map_err(|e| e.to_string())
+}
+
+/// Finds another payee whose name normalizes to the same key.
+fn find_by_key(conn: &Connection, key: &str, except_id: Option<i64>) -> Result<Option<i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name FROM payees")
+        .map_err(|e| e.to_string())?;
+    let payees: Vec<(i64, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(payees
+        .into_iter()
+        .find(|(id, name)| Some(*id) != except_id && normalize_key(name) == key)
+        .map(|(id, _)| id))
+}
+
+/// Maps a raw name (typed by the user or read from a bank file) to a payee:
+/// an exact normalized name match wins, otherwise the longest matching alias.
+pub(crate) fn resolve_payee_id(conn: &Connection, raw: &str) -> Result<Option<i64>, String> {
+    let key = normalize_key(raw);
+    if key.is_empty() {
+        return Ok(None);
+    }
+    if let Some(id) = find_by_key(conn, &key, None)? {
+        return Ok(Some(id));
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT payee_id, pattern FROM payee_aliases")
+        .map_err(|e| e.to_string())?;
+    let aliases: Vec<(i64, String)> = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(aliases
+        .into_iter()
+        .filter(|(_, pattern)| key_matches(&key, pattern))
+        .max_by_key(|(_, pattern)| pattern.len())
+        .map(|(payee_id, _)| payee_id))
+}
+
+/// Resolves `raw` to an existing payee or creates a new one named after it.
+pub(crate) fn find_or_create_payee(conn: &Connection, raw: &str) -> Result<Option<i64>, String> {
+    if let Some(id) = resolve_payee_id(conn, raw)? {
+        return Ok(Some(id));
+    }
+    if normalize_key(raw).is_empty() {
+        return Ok(None);
+    }
+    conn.execute("INSERT INTO payees (name) VALUES (?1)", [display_name(raw)?])
+        .map_err(|e| e.to_string())?;
+    Ok(Some(conn.last_insert_rowid()))
+}
+
+/// Points `pattern` at `payee_id`, taking it over from any other payee.
+fn upsert_alias(conn: &Connection, payee_id: i64, pattern: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO payee_aliases (payee_id, pattern) VALUES (?1, ?2)
+         ON CONFLICT(pattern) DO UPDATE SET payee_id = excluded.payee_id",
+        rusqlite::params![payee_id, pattern],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Folds every source payee into `target_id`: transactions and aliases move
+/// over and each source name becomes an alias so future imports still match.
+pub(crate) fn merge_payees_into(conn: &Connection, target_id: i64, source_ids: &[i64]) -> Result<(), String> {
+    let target_key = normalize_key(&load_payee(conn, target_id)?.name);
+    for &source_id in source_ids.iter().filter(|&&id| id != target_id) {
+        let source = load_payee(conn, source_id)?;
+        conn.execute(
+            "UPDATE transactions SET payee_id = ?1, updated_at = datetime('now') WHERE payee_id = ?2",
+            [target_id, source_id],
+        )
+        .map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE payee_aliases SET payee_id = ?1 WHERE payee_id = ?2",
+            [target_id, source_id],
+        )
+        .map_err(|e| e.to_string())?;
+        let source_key = normalize_key(&source.name);
+        if !source_key.is_empty() && source_key != target_key {
+            upsert_alias(conn, target_id, &source_key)?;
+        }
+        conn.execute("DELETE FROM payees WHERE id = ?1", [source_id])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_payees(state: State<DbState>) -> Result<Vec<Payee>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{PAYEE_SELECT} ORDER BY p.name COLLATE NOCASE"))
+        .map_err(|e| e.to_string())?;
+
+    let payees = stmt
+        .query_map([], |row| row_to_payee(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(payees)
+}
+
+#[tauri::command]
+pub fn create_payee(state: State<DbState>, name: String) -> Result<Payee, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let name = display_name(&name)?;
+    if find_by_key(conn, &normalize_key(&name), None)?.is_some() {
+        return Err(format!("A payee matching \"{}\" already exists", name));
+    }
+    conn.execute("INSERT INTO payees (name) VALUES (?1)", [&name])
+        .map_err(|e| e.to_string())?;
+    load_payee(conn, conn.last_insert_rowid())
+}
+
+/// Renames a payee, keeping the old name as an alias.
+#[tauri::command]
+pub fn rename_payee(state: State<DbState>, id: i64, name: String) -> Result<Payee, String> {
+    let name = display_name(&name)?;
+    with_transaction(&state, |conn| {
+        let old_key = normalize_key(&load_payee(conn, id)?.name);
+        let new_key = normalize_key(&name);
+        if find_by_key(conn, &new_key, Some(id))?.is_some() {
+            return Err(format!(
+                "A payee matching \"{}\" already exists; merge them instead",
+                name
+            ));
+        }
+        conn.execute("UPDATE payees SET name = ?1 WHERE id = ?2", rusqlite::params![name, id])
+            .map_err(|e| e.to_string())?;
+        if !old_key.is_empty() && old_key != new_key {
+            upsert_alias(conn, id, &old_key)?;
+        }
+        load_payee(conn, id)
+    })
+}
+
+#[tauri::command]
+pub fn merge_payees(
+    state: State<DbState>,
+    target_id: i64,
+    source_ids: Vec<i64>,
+) -> Result<Payee, String> {
+    with_transaction(&state, |conn| {
+        merge_payees_into(conn, target_id, &source_ids)?;
+        load_payee(conn, target_id)
+    })
+}
+
+/// Deletes the payee and its aliases; its transactions keep their other fields.
+#[tauri::command]
+pub fn delete_payee(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM payees WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_payee_aliases(state: State<DbState>, payee_id: i64) -> Result<Vec<PayeeAlias>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, payee_id, pattern, created_at FROM payee_aliases
+             WHERE payee_id = ?1 ORDER BY pattern",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let aliases = stmt
+        .query_map([payee_id], |row| row_to_alias(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(aliases)
+}
+
+/// Adds an alias pattern. The pattern is normalized the same way raw names are,
+/// so "JOLLIBEE" matches "Jollibee Ayala #12".
+#[tauri::command]
+pub fn create_payee_alias(
+    state: State<DbState>,
+    payee_id: i64,
+    pattern: String,
+) -> Result<PayeeAlias, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let pattern = normalize_key(&pattern);
+    if pattern.is_empty() {
+        return Err("Alias pattern is required".into());
+    }
+    let owner: Option<String> = conn
+        .query_row(
+            "SELECT p.name FROM payee_aliases pa JOIN payees p ON p.id = pa.payee_id
+             WHERE pa.pattern = ?1",
+            [&pattern],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some(owner) = owner {
+        return Err(format!("\"{}\" is already an alias of {}", pattern, owner));
+    }
+    conn.execute(
+        "INSERT INTO payee_aliases (payee_id, pattern) VALUES (?1, ?2)",
+        rusqlite::params![payee_id, pattern],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let id = conn.last_insert_rowid();
+    conn.query_row(
+        "SELECT id, payee_id, pattern, created_at FROM payee_aliases WHERE id = ?1",
+        [id],
+        |row| row_to_alias(row),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_payee_alias(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM payee_aliases WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Looks up the payee a raw name would map to, without creating one.
+#[tauri::command]
+pub fn resolve_payee(state: State<DbState>, name: String) -> Result<Option<Payee>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    resolve_payee_id(conn, &name)?
+        .map(|id| load_payee(conn, id))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn normalize_key_drops_branch_numbers_and_case() {
+        assert_eq!(normalize_key("JOLLIBEE #123"), "jollibee");
+        assert_eq!(normalize_key("  Jollibee "), "jollibee");
+        assert_eq!(normalize_key("jollibee ayala"), "jollibee ayala");
+        assert_eq!(normalize_key("SM Supermarket 0231"), "sm supermarket");
+        assert_eq!(normalize_key("7-Eleven"), "7 eleven");
+    }
+
+    #[test]
+    fn resolve_prefers_exact_name_then_longest_alias() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO payees (name) VALUES ('Jollibee'), ('Jollibee Delivery');
+             INSERT INTO payee_aliases (payee_id, pattern) VALUES (1, 'jollibee'), (2, 'jollibee delivery');",
+        )
+        .unwrap();
+        assert_eq!(resolve_payee_id(&conn, "JOLLIBEE #123").unwrap(), Some(1));
+        assert_eq!(resolve_payee_id(&conn, "jollibee ayala").unwrap(), Some(1));
+        assert_eq!(resolve_payee_id(&conn, "GRAB JOLLIBEE DELIVERY").unwrap(), Some(2));
+        assert_eq!(resolve_payee_id(&conn, "Mang Inasal").unwrap(), None);
+    }
+
+    #[test]
+    fn merge_moves_transactions_and_keeps_source_name_as_alias() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO payees (name) VALUES ('Jollibee'), ('JOLLIBEE AYALA');
+             INSERT INTO transactions (account_id, type, amount, date, payee_id)
+             VALUES (1, 'expense', 25000, '2025-01-15', 2);",
+        )
+        .unwrap();
+        merge_payees_into(&conn, 1, &[2]).unwrap();
+
+        let payee_id: i64 = conn
+            .query_row("SELECT payee_id FROM transactions", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(payee_id, 1);
+        assert_eq!(resolve_payee_id(&conn, "Jollibee Ayala #4").unwrap(), Some(1));
+        assert!(load_payee(&conn, 2).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::account_currency;
+use crate::commands::transactions::{row_to_transaction, TRANSACTION_SELECT};
+use crate::logic::balance::apply_activity;
+use crate::logic::csv_import::parse_date;
+use crate::models::{Money, Reconciliation, ReconciliationSession, Transaction};
+
+const RECONCILIATION_SELECT: &str = "SELECT r.id, r.account_id, r.statement_date, r.statement_balance, r.status,
+         r.cleared_balance, r.created_at, r.completed_at, a.currency
+         FROM reconciliations r
+         JOIN accounts a ON a.id = r.account_id";
+
+fn row_to_reconciliation(row: &rusqlite::Row) -> rusqlite::Result<Reconciliation> {
+    let currency: String = row.get(8)?;
+    Ok(Reconciliation {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        statement_date: row.get(2)?,
+        statement_balance: Money::new(row.get(3)?, currency.clone()),
+        status: row.get(4)?,
+        cleared_balance: row
+            .get::<_, Option<i64>>(5)?
+            .map(|minor| Money::new(minor, currency.clone())),
+        created_at: row.get(6)?,
+        completed_at: row.get(7)?,
+    })
+}
+
+fn load_reconciliation(conn: &Connection, id: i64) -> Result<Reconciliation, String> {
+    conn.query_row(
+        &format!("{RECONCILIATION_SELECT} WHERE r.id = ?1"),
+        [id],
+        |row| row_to_reconciliation(row),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Fails when any of `transaction_ids` is reconciled. Every command that
+/// changes or deletes transactions checks this first.
+pub(crate) fn require_unlocked(conn: &Connection, transaction_ids: &[i64]) -> Result<(), String> {
+    for &id in transaction_ids {
+        let status: Option<String> = conn
+            .query_row("SELECT status FROM transactions WHERE id = ?1", [id], |r| r.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        if status.as_deref() == Some("reconciled") {
+            return Err("This transaction is reconciled; unlock it before changing it".into());
+        }
+    }
+    Ok(())
+}
+
+/// Account balance at the end of `statement_date` counting only cleared and
+/// reconciled transactions, with the usual credit vs. asset signs.
+fn cleared_balance(conn: &Connection, account_id: i64, statement_date: &str) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT a.type, a.opening_balance,
+           COALESCE(SUM(CASE WHEN t.type = 'income' THEN t.amount END), 0),
+           COALESCE(SUM(CASE WHEN t.type = 'expense' THEN t.amount END), 0)
+         FROM accounts a
+         LEFT JOIN transactions t ON t.account_id = a.id
+           AND t.status != 'uncleared' AND t.date <= ?2
+         WHERE a.id = ?1
+         GROUP BY a.id",
+        rusqlite::params![account_id, statement_date],
+        |r| {
+            let account_type: String = r.get(0)?;
+            Ok(apply_activity(&account_type, r.get(1)?, r.get(2)?, r.get(3)?))
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// The session with every transaction that can be ticked against it: those
+/// on or before the statement date not reconciled by an earlier statement.
+pub(crate) fn load_session(conn: &Connection, id: i64) -> Result<ReconciliationSession, String> {
+    let reconciliation = load_reconciliation(conn, id)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{TRANSACTION_SELECT}
+             WHERE t.account_id = ?1 AND t.date <= ?2
+               AND (t.status != 'reconciled' OR t.reconciliation_id = ?3)
+             ORDER BY t.date, t.id"
+        ))
+        .map_err(|e| e.to_string())?;
+    let transactions: Vec<Transaction> = stmt
+        .query_map(
+            rusqlite::params![reconciliation.account_id, reconciliation.statement_date, id],
+            |row| row_to_transaction(row),
+        )
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let cleared = cleared_balance(conn, reconciliation.account_id, &reconciliation.statement_date)?;
+    let currency = reconciliation.statement_balance.currency.clone();
+    Ok(ReconciliationSession {
+        difference: Money::new(reconciliation.statement_balance.minor - cleared, currency.clone()),
+        cleared_balance: Money::new(cleared, currency),
+        reconciliation,
+        transactions,
+    })
+}
+
+pub(crate) fn start_session(
+    conn: &Connection,
+    account_id: i64,
+    statement_date: &str,
+    statement_balance: &Money,
+) -> Result<ReconciliationSession, String> {
+    statement_balance.require_currency(&account_currency(conn, account_id)?)?;
+    let open: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM reconciliations WHERE account_id = ?1 AND status = 'open'",
+            [account_id],
+            |r| r.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if open.is_some() {
+        return Err("Finish or cancel the reconciliation already in progress for this account".into());
+    }
+    conn.execute(
+        "INSERT INTO reconciliations (account_id, statement_date, statement_balance) VALUES (?1, ?2, ?3)",
+        rusqlite::params![account_id, statement_date, statement_balance.minor],
+    )
+    .map_err(|e| e.to_string())?;
+    load_session(conn, conn.last_insert_rowid())
+}
+
+/// Marks transactions cleared (ticked against a statement) or uncleared.
+pub(crate) fn set_cleared(conn: &Connection, transaction_ids: &[i64], cleared: bool) -> Result<(), String> {
+    require_unlocked(conn, transaction_ids)?;
+    let status = if cleared { "cleared" } else { "uncleared" };
+    for &id in transaction_ids {
+        conn.execute(
+            "UPDATE transactions SET status = ?1, updated_at = datetime('now') WHERE id = ?2",
+            rusqlite::params![status, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Completes a session whose cleared balance matches the statement, locking
+/// every cleared transaction up to the statement date.
+pub(crate) fn finish_session(conn: &Connection, id: i64) -> Result<Reconciliation, String> {
+    let session = load_session(conn, id)?;
+    let r = &session.reconciliation;
+    if r.status != "open" {
+        return Err("This reconciliation is already finished".into());
+    }
+    if session.difference.minor != 0 {
+        return Err(format!(
+            "The cleared balance is {} but the statement says {} (off by {})",
+            session.cleared_balance.to_decimal_string(),
+            r.statement_balance.to_decimal_string(),
+            session.difference.to_decimal_string()
+        ));
+    }
+    conn.execute(
+        "UPDATE transactions SET status = 'reconciled', reconciliation_id = ?1, updated_at = datetime('now')
+         WHERE account_id = ?2 AND status = 'cleared' AND date <= ?3",
+        rusqlite::params![id, r.account_id, r.statement_date],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE reconciliations SET status = 'completed', cleared_balance = ?1, completed_at = datetime('now')
+         WHERE id = ?2",
+        rusqlite::params![session.cleared_balance.minor, id],
+    )
+    .map_err(|e| e.to_string())?;
+    load_reconciliation(conn, id)
+}
+
+/// Opens a session for the statement ending `statement_date` with closing
+/// balance `statement_balance` (amount owed, for credit accounts).
+#[tauri::command]
+pub fn start_reconciliation(
+    state: State<DbState>,
+    account_id: i64,
+    statement_date: String,
+    statement_balance: Money,
+) -> Result<ReconciliationSession, String> {
+    let statement_date = parse_date(&statement_date, "YYYY-MM-DD")?;
+    with_transaction(&state, |conn| {
+        start_session(conn, account_id, &statement_date, &statement_balance)
+    })
+}
+
+/// The session with its candidate transactions, cleared balance and the
+/// difference still to explain.
+#[tauri::command]
+pub fn get_reconciliation(state: State<DbState>, id: i64) -> Result<ReconciliationSession, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    load_session(conn, id)
+}
+
+#[tauri::command]
+pub fn set_transactions_cleared(
+    state: State<DbState>,
+    transaction_ids: Vec<i64>,
+    cleared: bool,
+) -> Result<(), String> {
+    with_transaction(&state, |conn| set_cleared(conn, &transaction_ids, cleared))
+}
+
+#[tauri::command]
+pub fn finish_reconciliation(state: State<DbState>, id: i64) -> Result<Reconciliation, String> {
+    with_transaction(&state, |conn| finish_session(conn, id))
+}
+
+/// Discards a session in progress. Ticked transactions stay cleared.
+#[tauri::command]
+pub fn cancel_reconciliation(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM reconciliations WHERE id = ?1 AND status = 'open'", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Past and in-progress reconciliations of an account, latest statement first.
+#[tauri::command]
+pub fn list_reconciliations(state: State<DbState>, account_id: i64) -> Result<Vec<Reconciliation>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{RECONCILIATION_SELECT} WHERE r.account_id = ?1 ORDER BY r.statement_date DESC, r.id DESC"
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let reconciliations = stmt
+        .query_map([account_id], |row| row_to_reconciliation(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(reconciliations)
+}
+
+/// Releases a reconciled transaction for editing. It goes back to cleared,
+/// so the next reconciliation will pick it up again.
+#[tauri::command]
+pub fn unlock_transaction(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute(
+        "UPDATE transactions SET status = 'cleared', reconciliation_id = NULL, updated_at = datetime('now')
+         WHERE id = ?1 AND status = 'reconciled'",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn reconciling_balances_cleared_rows_and_locks_them() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, opening_balance) VALUES ('Bank', 'debit', 100000);
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'income', 50000, '2025-01-10');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 12000, '2025-01-20');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 3000, '2025-01-30');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 8000, '2025-02-03');",
+        )
+        .unwrap();
+
+        let session = start_session(&conn, 1, "2025-01-31", &Money::new(138000, "PHP")).unwrap();
+        // The February row is after the statement and not offered.
+        assert_eq!(session.transactions.len(), 3);
+        assert_eq!(session.difference.minor, 38000);
+        assert!(start_session(&conn, 1, "2025-01-31", &Money::new(0, "PHP")).is_err());
+
+        set_cleared(&conn, &[1, 2], true).unwrap();
+        let id = session.reconciliation.id;
+        assert_eq!(load_session(&conn, id).unwrap().difference.minor, 0);
+
+        let done = finish_session(&conn, id).unwrap();
+        assert_eq!(done.status, "completed");
+        assert_eq!(done.cleared_balance.unwrap().minor, 138000);
+        assert!(require_unlocked(&conn, &[1]).is_err());
+        assert!(require_unlocked(&conn, &[3]).is_ok());
+        assert!(set_cleared(&conn, &[2], false).is_err());
+
+        // The next statement only offers what earlier ones left open.
+        let next = start_session(&conn, 1, "2025-02-28", &Money::new(127000, "PHP")).unwrap();
+        let ids: Vec<i64> = next.transactions.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![3, 4]);
+        assert!(finish_session(&conn, next.reconciliation.id).is_err());
+    }
+}
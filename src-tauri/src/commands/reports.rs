@@ -1,273 +1,670 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use rusqlite::Connection;
 use tauri::State;
 use crate::db::DbState;
-use crate::models::{AccountWithBalance, CategorySpend, DashboardData, MonthSummary, SpendingBreakdown, Transaction};
-use crate::logic::balance::compute_balance;
+use crate::commands::accounts::{accounts_as_of, row_to_account, with_balance, ACCOUNT_SELECT};
+use crate::commands::categories::{category_tree, path_to};
+use crate::commands::currencies::base_currency;
+use crate::commands::installments::NOT_INSTALLMENT_PRINCIPAL;
+use crate::commands::investments::{holdings_value, NOT_TRADE_SETTLEMENT};
+use crate::commands::transactions::{row_to_transaction, TRANSACTION_SELECT};
+use crate::logic::balance::{balance_samples, is_liability, sample_dates, BalanceSample};
+use crate::logic::csv_import::parse_date;
+use crate::logic::fx::{implied_rate, RateBook};
+use crate::models::{AccountWithBalance, BalancePoint, CategorySpend, DashboardData, FxGain, Money, MonthSummary, NetWorthPoint, PayeeSummary, SpendingBreakdown, TagSummary, Transaction};
 use crate::models::Account;
 
-fn row_to_account(row: &rusqlite::Row) -> rusqlite::Result<Account> {
-    Ok(Account {
-        id: row.get(0)?,
-        name: row.get(1)?,
-        account_type: row.get(2)?,
-        subtype: row.get(3)?,
-        currency: row.get(4)?,
-        opening_balance: row.get(5)?,
-        credit_limit: row.get(6)?,
-        billing_cycle_day: row.get(7)?,
-        payment_due_day: row.get(8)?,
-        is_active: row.get(9)?,
-        created_at: row.get(10)?,
-        updated_at: row.get(11)?,
-    })
+/// Converts amounts into the base currency at the rate effective on each
+/// amount's own date, remembering currencies that had no usable rate (those
+/// amounts count as zero).
+pub(crate) struct BaseConverter {
+    book: RateBook,
+    base: String,
+    missing: BTreeSet<String>,
 }
 
-fn row_to_transaction(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
-    Ok(Transaction {
-        id: row.get(0)?,
-        account_id: row.get(1)?,
-        category_id: row.get(2)?,
-        transfer_id: row.get(3)?,
-        tx_type: row.get(4)?,
-        amount: row.get(5)?,
-        date: row.get(6)?,
-        notes: row.get(7)?,
-        is_recurring: row.get(8)?,
-        recurrence_frequency: row.get(9)?,
-        next_due_date: row.get(10)?,
-        created_at: row.get(11)?,
-        updated_at: row.get(12)?,
-        category_name: row.get(13)?,
-        account_name: row.get(14)?,
-    })
-}
+impl BaseConverter {
+    pub(crate) fn load(conn: &Connection) -> Result<Self, String> {
+        Ok(Self {
+            book: RateBook::load(conn)?,
+            base: base_currency(conn)?,
+            missing: BTreeSet::new(),
+        })
+    }
 
-#[tauri::command]
-pub fn get_dashboard(state: State<DbState>) -> Result<DashboardData, String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    pub(crate) fn convert(&mut self, minor: i64, currency: &str, date: &str) -> i64 {
+        match self.book.convert(minor, currency, &self.base, date) {
+            Some(converted) => converted,
+            None => {
+                self.missing.insert(currency.to_string());
+                0
+            }
+        }
+    }
+
+    pub(crate) fn money(&self, minor: i64) -> Money {
+        Money::new(minor, self.base.clone())
+    }
 
-    // Load all active accounts with balances
+    /// Fails when some amounts could not be converted, so a report never
+    /// shows a silently incomplete total.
+    pub(crate) fn require_complete(&self) -> Result<(), String> {
+        if self.missing.is_empty() {
+            return Ok(());
+        }
+        let missing: Vec<&str> = self.missing.iter().map(String::as_str).collect();
+        Err(format!(
+            "Add an exchange rate from {} to {} to include those accounts",
+            missing.join(", "),
+            self.base
+        ))
+    }
+}
+
+/// Income and expense totals for one `YYYY-MM` period, in the base currency.
+fn period_totals(conn: &Connection, fx: &mut BaseConverter, period: &str) -> Result<(i64, i64), String> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, name, type, subtype, currency, opening_balance, credit_limit,
-             billing_cycle_day, payment_due_day, is_active, created_at, updated_at
-             FROM accounts WHERE is_active = 1 ORDER BY name",
-        )
+        .prepare(&format!(
+            "SELECT t.type, t.amount, a.currency, t.date FROM transactions t
+             JOIN accounts a ON a.id = t.account_id
+             WHERE strftime('%Y-%m', t.date) = ?1 AND {NOT_INSTALLMENT_PRINCIPAL} AND {NOT_TRADE_SETTLEMENT}"
+        ))
         .map_err(|e| e.to_string())?;
+    let rows: Vec<(String, i64, String, String)> = stmt
+        .query_map([period], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let (mut income, mut expenses) = (0i64, 0i64);
+    for (tx_type, amount, currency, date) in rows {
+        let converted = fx.convert(amount, &currency, &date);
+        match tx_type.as_str() {
+            "income" => income += converted,
+            "expense" => expenses += converted,
+            _ => {}
+        }
+    }
+    Ok((income, expenses))
+}
+
+/// Category, its name and parent, amount, currency and date of one line.
+type SpendRow = (Option<i64>, String, Option<i64>, i64, String, String);
 
-    let accounts_raw: Vec<Account> = stmt
-        .query_map([], |row| row_to_account(row))
+/// Expense per category for one `YYYY-MM` period in the base currency,
+/// largest first. Split lines count individually.
+pub(crate) fn category_spending(
+    conn: &Connection,
+    fx: &mut BaseConverter,
+    period: &str,
+) -> Result<Vec<CategorySpend>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT l.category_id, COALESCE(c.name, 'Uncategorized'), c.parent_id, l.amount, a.currency, l.date
+             FROM category_lines l
+             JOIN transactions t ON t.id = l.transaction_id
+             JOIN accounts a ON a.id = l.account_id
+             LEFT JOIN categories c ON l.category_id = c.id
+             WHERE l.type = 'expense' AND strftime('%Y-%m', l.date) = ?1 AND {NOT_INSTALLMENT_PRINCIPAL}
+               AND {NOT_TRADE_SETTLEMENT}"
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<SpendRow> = stmt
+        .query_map([period], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?)))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    let accounts: Vec<AccountWithBalance> = accounts_raw
-        .iter()
-        .map(|a| {
-            let balance = compute_balance(conn, a);
-            AccountWithBalance {
-                id: a.id,
-                name: a.name.clone(),
-                account_type: a.account_type.clone(),
-                subtype: a.subtype.clone(),
-                currency: a.currency.clone(),
-                opening_balance: a.opening_balance,
-                credit_limit: a.credit_limit,
-                billing_cycle_day: a.billing_cycle_day,
-                payment_due_day: a.payment_due_day,
-                is_active: a.is_active,
-                created_at: a.created_at.clone(),
-                updated_at: a.updated_at.clone(),
-                balance,
-            }
+    let mut totals: HashMap<Option<i64>, (String, Option<i64>, i64)> = HashMap::new();
+    for (category_id, name, parent_id, amount, currency, date) in rows {
+        let converted = fx.convert(amount, &currency, &date);
+        totals.entry(category_id).or_insert((name, parent_id, 0)).2 += converted;
+    }
+    let mut spending: Vec<CategorySpend> = totals
+        .into_iter()
+        .map(|(category_id, (category_name, parent_id, amount))| CategorySpend {
+            category_id,
+            category_name,
+            parent_id,
+            amount: fx.money(amount),
         })
         .collect();
+    sort_spending(&mut spending);
+    Ok(spending)
+}
 
-    // Net worth: sum of non-credit account balances minus credit balances
-    let net_worth = accounts.iter().fold(0.0_f64, |acc, a| {
-        if a.account_type == "credit" {
-            acc - a.balance
-        } else {
-            acc + a.balance
-        }
-    });
-
-    // Liquid balance: only cash + debit minus credit (excludes savings & investment)
-    let liquid_balance = accounts.iter().fold(0.0_f64, |acc, a| {
-        match a.account_type.as_str() {
-            "credit"         => acc - a.balance,
-            "cash" | "debit" => acc + a.balance,
-            _                => acc,
+fn sort_spending(spending: &mut [CategorySpend]) {
+    spending.sort_by(|a, b| b.amount.minor.cmp(&a.amount.minor).then(a.category_name.cmp(&b.category_name)));
+}
+
+/// Folds spending up the category tree so that nothing is deeper than
+/// `depth` (0 keeps only top-level categories). Subcategories count toward
+/// their ancestor at that depth. `None` leaves every category as it is.
+pub(crate) fn roll_up(
+    conn: &Connection,
+    spending: Vec<CategorySpend>,
+    depth: Option<i64>,
+) -> Result<Vec<CategorySpend>, String> {
+    let Some(depth) = depth else {
+        return Ok(spending);
+    };
+    if depth < 0 {
+        return Err("Depth cannot be negative".into());
+    }
+    let tree = category_tree(conn)?;
+    let mut rolled: Vec<CategorySpend> = Vec::new();
+    for spend in spending {
+        let target = spend.category_id.map(|id| {
+            let path = path_to(&tree, id);
+            path[(depth as usize).min(path.len() - 1)]
+        });
+        match rolled.iter_mut().find(|r| r.category_id == target) {
+            Some(existing) => existing.amount.minor += spend.amount.minor,
+            None => {
+                let (category_name, parent_id) = match target.and_then(|id| tree.get(&id)) {
+                    Some((name, parent_id)) => (name.clone(), *parent_id),
+                    None => (spend.category_name, spend.parent_id),
+                };
+                rolled.push(CategorySpend {
+                    category_id: target,
+                    category_name,
+                    parent_id,
+                    amount: spend.amount,
+                });
+            }
         }
-    });
+    }
+    sort_spending(&mut rolled);
+    Ok(rolled)
+}
 
-    // Monthly income and expenses (current month)
-    let monthly_income: f64 = conn
+/// Dashboard for today, or a snapshot as it stood at the end of `as_of`:
+/// the accounts open then with their balances, that month's totals and the
+/// transactions leading up to it. Spending is rolled up to `category_depth`
+/// when given.
+#[tauri::command]
+pub fn get_dashboard(
+    state: State<DbState>,
+    as_of: Option<String>,
+    category_depth: Option<i64>,
+) -> Result<DashboardData, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut fx = BaseConverter::load(conn)?;
+    let as_of = as_of.map(|d| parse_date(&d, "YYYY-MM-DD")).transpose()?;
+    let (today, period): (String, String) = conn
         .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions
-             WHERE type = 'income' AND strftime('%Y-%m', date) = strftime('%Y-%m', 'now')",
-            [],
-            |r| r.get(0),
+            "SELECT DATE(COALESCE(?1, 'now')), strftime('%Y-%m', COALESCE(?1, 'now'))",
+            [&as_of],
+            |r| Ok((r.get(0)?, r.get(1)?)),
         )
-        .unwrap_or(0.0);
+        .map_err(|e| e.to_string())?;
 
-    let monthly_expenses: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions
-             WHERE type = 'expense' AND strftime('%Y-%m', date) = strftime('%Y-%m', 'now')",
-            [],
-            |r| r.get(0),
-        )
-        .unwrap_or(0.0);
+    let mut accounts: Vec<AccountWithBalance> = match &as_of {
+        Some(date) => accounts_as_of(conn, date)?,
+        None => {
+            // Load all active accounts with balances
+            let mut stmt = conn
+                .prepare(&format!("{ACCOUNT_SELECT} WHERE is_active = 1 ORDER BY name"))
+                .map_err(|e| e.to_string())?;
+
+            let accounts_raw: Vec<Account> = stmt
+                .query_map([], |row| row_to_account(row))
+                .map_err(|e| e.to_string())?
+                .filter_map(|r| r.ok())
+                .collect();
+
+            accounts_raw
+                .into_iter()
+                .map(|a| with_balance(conn, a, None))
+                .collect()
+        }
+    };
+
+    // Investment accounts count at market value: their cash plus holdings.
+    for a in accounts.iter_mut().filter(|a| a.account_type == "investment") {
+        a.balance.minor += holdings_value(conn, a.id, &a.currency, &today)?;
+    }
 
-    // Recent 10 transactions
+    // Balances are as of `today`, so they convert at that day's rate.
+    let mut net_worth = 0_i64;
+    let mut liquid_balance = 0_i64;
+    for a in &accounts {
+        let balance = fx.convert(a.balance.minor, &a.balance.currency, &today);
+        // Net worth: sum of asset balances minus credit and loan balances
+        net_worth += if is_liability(&a.account_type) { -balance } else { balance };
+        // Liquid balance: only cash + debit minus credit (excludes savings, investment & loans)
+        liquid_balance += match a.account_type.as_str() {
+            "credit"         => -balance,
+            "cash" | "debit" => balance,
+            _                => 0,
+        };
+    }
+
+    // Monthly income and expenses (current month), each at its own date's rate
+    let (monthly_income, monthly_expenses) = period_totals(conn, &mut fx, &period)?;
+
+    // Recent 10 transactions (up to the snapshot date)
     let mut txn_stmt = conn
-        .prepare(
-            "SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type, t.amount,
-             t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date,
-             t.created_at, t.updated_at, c.name, a.name
-             FROM transactions t
-             LEFT JOIN categories c ON t.category_id = c.id
-             LEFT JOIN accounts a ON t.account_id = a.id
-             ORDER BY t.date DESC, t.id DESC LIMIT 10",
-        )
+        .prepare(&format!(
+            "{TRANSACTION_SELECT} WHERE ?1 IS NULL OR t.date <= ?1 ORDER BY t.date DESC, t.id DESC LIMIT 10"
+        ))
         .map_err(|e| e.to_string())?;
 
     let recent_transactions: Vec<Transaction> = txn_stmt
-        .query_map([], |row| row_to_transaction(row))
+        .query_map([&as_of], |row| row_to_transaction(row))
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    // Spending by category (current month, expenses only)
-    let mut cat_stmt = conn
-        .prepare(
-            "SELECT t.category_id, COALESCE(c.name, 'Uncategorized'), SUM(t.amount)
-             FROM transactions t
-             LEFT JOIN categories c ON t.category_id = c.id
-             WHERE t.type = 'expense' AND strftime('%Y-%m', t.date) = strftime('%Y-%m', 'now')
-             GROUP BY t.category_id ORDER BY SUM(t.amount) DESC",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let spending_by_category: Vec<CategorySpend> = cat_stmt
-        .query_map([], |row| {
-            Ok(CategorySpend {
-                category_id: row.get(0)?,
-                category_name: row.get(1)?,
-                amount: row.get(2)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
-        .collect();
+    // Spending by category (current month, expenses only; split lines counted individually)
+    let spending_by_category = roll_up(conn, category_spending(conn, &mut fx, &period)?, category_depth)?;
 
     Ok(DashboardData {
-        net_worth,
-        liquid_balance,
-        monthly_income,
-        monthly_expenses,
+        as_of: today,
+        net_worth: fx.money(net_worth),
+        liquid_balance: fx.money(liquid_balance),
+        monthly_income: fx.money(monthly_income),
+        monthly_expenses: fx.money(monthly_expenses),
+        base_currency: fx.base.clone(),
+        missing_rates: fx.missing.into_iter().collect(),
         accounts,
         recent_transactions,
         spending_by_category,
     })
 }
 
+/// Spending per category for a month, rolled up to `category_depth` when
+/// given.
 #[tauri::command]
 pub fn get_spending_breakdown(
     state: State<DbState>,
     year: i64,
     month: i64,
+    category_depth: Option<i64>,
 ) -> Result<SpendingBreakdown, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     let period = format!("{:04}-{:02}", year, month);
+    let mut fx = BaseConverter::load(conn)?;
 
-    let total_income: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE type = 'income' AND strftime('%Y-%m', date) = ?1",
-            [&period],
-            |r| r.get(0),
-        )
-        .unwrap_or(0.0);
+    let (total_income, total_expenses) = period_totals(conn, &mut fx, &period)?;
+    let categories = roll_up(conn, category_spending(conn, &mut fx, &period)?, category_depth)?;
+    fx.require_complete()?;
 
-    let total_expenses: f64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE type = 'expense' AND strftime('%Y-%m', date) = ?1",
-            [&period],
-            |r| r.get(0),
-        )
-        .unwrap_or(0.0);
+    Ok(SpendingBreakdown {
+        year,
+        month,
+        total_income: fx.money(total_income),
+        total_expenses: fx.money(total_expenses),
+        categories,
+    })
+}
 
+#[tauri::command]
+pub fn get_monthly_trends(state: State<DbState>, months: i64) -> Result<Vec<MonthSummary>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let limit = months.min(24).max(1);
+    let mut fx = BaseConverter::load(conn)?;
+
+    // The most recent `limit` months that have any activity
     let mut stmt = conn
         .prepare(
-            "SELECT t.category_id, COALESCE(c.name, 'Uncategorized'), SUM(t.amount)
-             FROM transactions t
-             LEFT JOIN categories c ON t.category_id = c.id
-             WHERE t.type = 'expense' AND strftime('%Y-%m', t.date) = ?1
-             GROUP BY t.category_id ORDER BY SUM(t.amount) DESC",
+            "SELECT DISTINCT strftime('%Y-%m', date) FROM transactions
+             ORDER BY 1 DESC LIMIT ?1",
         )
         .map_err(|e| e.to_string())?;
+    let labels: Vec<String> = stmt
+        .query_map([limit], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
 
-    let categories: Vec<CategorySpend> = stmt
-        .query_map([&period], |row| {
-            Ok(CategorySpend {
-                category_id: row.get(0)?,
-                category_name: row.get(1)?,
-                amount: row.get(2)?,
-            })
+    let mut totals: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for label in labels {
+        let sums = period_totals(conn, &mut fx, &label)?;
+        totals.insert(label, sums);
+    }
+    fx.require_complete()?;
+
+    Ok(totals
+        .into_iter()
+        .map(|(label, (income, expenses))| MonthSummary {
+            year: label[..4].parse().unwrap_or_default(),
+            month: label[5..].parse().unwrap_or_default(),
+            income: fx.money(income),
+            expenses: fx.money(expenses),
+            net: fx.money(income - expenses),
+            label,
+        })
+        .collect())
+}
+
+/// Income and expense totals per tag over an inclusive date range, in the
+/// base currency. A transaction with several tags counts toward each of them.
+#[tauri::command]
+pub fn get_tag_report(
+    state: State<DbState>,
+    date_from: String,
+    date_to: String,
+) -> Result<Vec<TagSummary>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut fx = BaseConverter::load(conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT tg.id, tg.name, t.type, t.amount, a.currency, t.date
+             FROM tags tg
+             JOIN transaction_tags tt ON tt.tag_id = tg.id
+             JOIN transactions t ON t.id = tt.transaction_id
+             JOIN accounts a ON a.id = t.account_id
+             WHERE t.date >= ?1 AND t.date <= ?2
+             ORDER BY tg.name COLLATE NOCASE, tg.id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, String, String, i64, String, String)> = stmt
+        .query_map([&date_from, &date_to], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?))
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    Ok(SpendingBreakdown {
-        year,
-        month,
-        total_income,
-        total_expenses,
-        categories,
-    })
+    // Rows arrive grouped by tag, so each tag's totals are folded in turn.
+    let mut summaries: Vec<(i64, String, i64, i64, i64)> = Vec::new();
+    for (tag_id, tag_name, tx_type, amount, currency, date) in rows {
+        let converted = fx.convert(amount, &currency, &date);
+        if summaries.last().map(|s| s.0) != Some(tag_id) {
+            summaries.push((tag_id, tag_name, 0, 0, 0));
+        }
+        let summary = summaries.last_mut().unwrap();
+        summary.2 += 1;
+        match tx_type.as_str() {
+            "income" => summary.3 += converted,
+            "expense" => summary.4 += converted,
+            _ => {}
+        }
+    }
+    fx.require_complete()?;
+
+    Ok(summaries
+        .into_iter()
+        .map(|(tag_id, tag_name, transaction_count, income, expenses)| TagSummary {
+            tag_id,
+            tag_name,
+            transaction_count,
+            income: fx.money(income),
+            expenses: fx.money(expenses),
+            net: fx.money(income - expenses),
+        })
+        .collect())
 }
 
+/// Payees ranked by expense total (in the base currency) over an inclusive
+/// date range. Transfer legs are excluded. Returns at most `limit` rows
+/// (default 10).
 #[tauri::command]
-pub fn get_monthly_trends(state: State<DbState>, months: i64) -> Result<Vec<MonthSummary>, String> {
+pub fn get_top_payees(
+    state: State<DbState>,
+    date_from: String,
+    date_to: String,
+    limit: Option<i64>,
+) -> Result<Vec<PayeeSummary>, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    let limit = months.min(24).max(1);
+    let mut fx = BaseConverter::load(conn)?;
 
     let mut stmt = conn
         .prepare(
-            "SELECT
-               CAST(strftime('%Y', date) AS INTEGER) as year,
-               CAST(strftime('%m', date) AS INTEGER) as month,
-               strftime('%Y-%m', date) as label,
-               COALESCE(SUM(CASE WHEN type = 'income' THEN amount ELSE 0 END), 0) as income,
-               COALESCE(SUM(CASE WHEN type = 'expense' THEN amount ELSE 0 END), 0) as expenses
-             FROM transactions
-             GROUP BY strftime('%Y-%m', date)
-             ORDER BY strftime('%Y-%m', date) DESC
-             LIMIT ?1",
+            "SELECT p.id, p.name, t.amount, a.currency, t.date
+             FROM payees p
+             JOIN transactions t ON t.payee_id = p.id
+             JOIN accounts a ON a.id = t.account_id
+             WHERE t.type = 'expense' AND t.transfer_id IS NULL
+               AND t.date >= ?1 AND t.date <= ?2",
         )
         .map_err(|e| e.to_string())?;
-
-    let mut summaries: Vec<MonthSummary> = stmt
-        .query_map([limit], |row| {
-            let income: f64 = row.get(3)?;
-            let expenses: f64 = row.get(4)?;
-            Ok(MonthSummary {
-                year: row.get(0)?,
-                month: row.get(1)?,
-                label: row.get(2)?,
-                income,
-                expenses,
-                net: income - expenses,
-            })
+    let rows: Vec<(i64, String, i64, String, String)> = stmt
+        .query_map([&date_from, &date_to], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    summaries.reverse();
+    let mut totals: HashMap<i64, (String, i64, i64)> = HashMap::new();
+    for (payee_id, payee_name, amount, currency, date) in rows {
+        let converted = fx.convert(amount, &currency, &date);
+        let entry = totals.entry(payee_id).or_insert((payee_name, 0, 0));
+        entry.1 += 1;
+        entry.2 += converted;
+    }
+    fx.require_complete()?;
+
+    let mut summaries: Vec<PayeeSummary> = totals
+        .into_iter()
+        .map(|(payee_id, (payee_name, transaction_count, total))| PayeeSummary {
+            payee_id,
+            payee_name,
+            transaction_count,
+            total: fx.money(total),
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.total.minor.cmp(&a.total.minor).then(a.payee_id.cmp(&b.payee_id)));
+    summaries.truncate(usize::try_from(limit.unwrap_or(10)).unwrap_or(0));
     Ok(summaries)
 }
+
+/// Realized gains and losses on cross-currency transfers over an inclusive
+/// date range, oldest first. Each transfer's gain is the base-currency value
+/// of what arrived minus that of what was sent, at the rates stored for the
+/// transfer date; fees are reported separately as expenses.
+#[tauri::command]
+pub fn get_fx_gains(
+    state: State<DbState>,
+    date_from: String,
+    date_to: String,
+) -> Result<Vec<FxGain>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut fx = BaseConverter::load(conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT tr.id, tr.date, tr.amount, a.currency, tr.to_amount, b.currency
+             FROM transfers tr
+             JOIN accounts a ON a.id = tr.from_account_id
+             JOIN accounts b ON b.id = tr.to_account_id
+             WHERE a.currency != b.currency AND tr.date >= ?1 AND tr.date <= ?2
+             ORDER BY tr.date, tr.id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, String, i64, String, i64, String)> = stmt
+        .query_map([&date_from, &date_to], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?, r.get(5)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut gains = Vec::with_capacity(rows.len());
+    for (transfer_id, date, amount, from_currency, to_amount, to_currency) in rows {
+        let sent = fx.convert(amount, &from_currency, &date);
+        let received = fx.convert(to_amount, &to_currency, &date);
+        let amount = Money::new(amount, from_currency);
+        let to_amount = Money::new(to_amount, to_currency);
+        gains.push(FxGain {
+            transfer_id,
+            market_rate: fx.book.rate(&amount.currency, &to_amount.currency, &date),
+            rate: implied_rate(&amount, &to_amount),
+            gain: fx.money(received - sent),
+            date,
+            amount,
+            to_amount,
+        });
+    }
+    fx.require_complete()?;
+    Ok(gains)
+}
+
+/// Balance samples with investment accounts valued at market, as on the
+/// dashboard: cash plus the holdings on each sample date.
+fn market_samples(
+    conn: &Connection,
+    account_id: Option<i64>,
+    date_from: &str,
+    date_to: &str,
+    granularity: &str,
+) -> Result<Vec<BalanceSample>, String> {
+    let mut samples = balance_samples(conn, account_id, date_from, date_to, granularity)?;
+    for s in samples.iter_mut().filter(|s| s.account_type == "investment") {
+        s.balance.minor += holdings_value(conn, s.account_id, &s.balance.currency, &s.date)?;
+    }
+    Ok(samples)
+}
+
+/// Balance of one account (or of every account) at each `daily`, `weekly`
+/// or `monthly` point from `date_from` to `date_to`, in each account's own
+/// currency. Accounts only appear while they were open.
+#[tauri::command]
+pub fn get_balance_history(
+    state: State<DbState>,
+    account_id: Option<i64>,
+    date_from: String,
+    date_to: String,
+    granularity: String,
+) -> Result<Vec<BalancePoint>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let samples = market_samples(conn, account_id, &date_from, &date_to, &granularity)?;
+    Ok(samples
+        .into_iter()
+        .map(|s| BalancePoint {
+            date: s.date,
+            account_id: s.account_id,
+            balance: s.balance,
+        })
+        .collect())
+}
+
+/// Net worth at each `daily`, `weekly` or `monthly` point from `date_from` to
+/// `date_to`, in the base currency at each point's exchange rates. Credit
+/// balances count as liabilities; archived accounts count while they were open.
+#[tauri::command]
+pub fn get_net_worth_history(
+    state: State<DbState>,
+    date_from: String,
+    date_to: String,
+    granularity: String,
+) -> Result<Vec<NetWorthPoint>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    net_worth_history(conn, &date_from, &date_to, &granularity)
+}
+
+pub(crate) fn net_worth_history(
+    conn: &Connection,
+    date_from: &str,
+    date_to: &str,
+    granularity: &str,
+) -> Result<Vec<NetWorthPoint>, String> {
+    let mut fx = BaseConverter::load(conn)?;
+
+    let mut totals: BTreeMap<String, (i64, i64)> = sample_dates(conn, date_from, date_to, granularity)?
+        .into_iter()
+        .map(|date| (date, (0, 0)))
+        .collect();
+    for sample in market_samples(conn, None, date_from, date_to, granularity)? {
+        let converted = fx.convert(sample.balance.minor, &sample.balance.currency, &sample.date);
+        let entry = totals.entry(sample.date).or_default();
+        if is_liability(&sample.account_type) {
+            entry.1 += converted;
+        } else {
+            entry.0 += converted;
+        }
+    }
+    fx.require_complete()?;
+
+    Ok(totals
+        .into_iter()
+        .map(|(date, (assets, liabilities))| NetWorthPoint {
+            date,
+            assets: fx.money(assets),
+            liabilities: fx.money(liabilities),
+            net_worth: fx.money(assets - liabilities),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::investments::record_trade;
+    use crate::db::open_test_db;
+    use crate::models::TradeInput;
+
+    #[test]
+    fn buying_securities_leaves_net_worth_unchanged() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, opening_balance, created_at)
+               VALUES ('Broker', 'investment', 10000000, '2025-01-01 00:00:00');
+             INSERT INTO securities (symbol, currency) VALUES ('JFC', 'PHP');",
+        )
+        .unwrap();
+        let buy = TradeInput {
+            account_id: 1,
+            security_id: 1,
+            kind: "buy".into(),
+            date: "2025-01-10".into(),
+            quantity: 100.0,
+            price: 100.0,
+            fees: None,
+            amount: None,
+            category_id: None,
+            notes: None,
+        };
+        record_trade(&conn, &buy).unwrap();
+
+        let history = net_worth_history(&conn, "2025-01-09", "2025-01-11", "daily").unwrap();
+        let worth: Vec<i64> = history.iter().map(|p| p.net_worth.minor).collect();
+        assert_eq!(worth, vec![10_000_000; 3]);
+        let balances = market_samples(&conn, Some(1), "2025-01-09", "2025-01-11", "daily").unwrap();
+        assert!(balances.iter().all(|s| s.balance.minor == 10_000_000));
+    }
+
+    #[test]
+    fn spending_rolls_up_to_the_requested_depth() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Food & Dining', 'expense');
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Groceries', 'expense', 1);
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Restaurants', 'expense', 1);
+             INSERT INTO categories (name, direction, parent_id) VALUES ('Coffee', 'expense', 3);
+             INSERT INTO categories (name, direction) VALUES ('Transportation', 'expense');
+             INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 2, 'expense', 300000, '2025-03-02');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 3, 'expense', 120000, '2025-03-05');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 4, 'expense', 30000, '2025-03-06');
+             INSERT INTO transactions (account_id, category_id, type, amount, date) VALUES (1, 5, 'expense', 50000, '2025-03-07');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 10000, '2025-03-08');",
+        )
+        .unwrap();
+        let mut fx = BaseConverter::load(&conn).unwrap();
+        let leaves = category_spending(&conn, &mut fx, "2025-03").unwrap();
+        assert_eq!(leaves.len(), 5);
+        assert_eq!(roll_up(&conn, leaves.clone(), None).unwrap().len(), 5);
+
+        let top = roll_up(&conn, leaves.clone(), Some(0)).unwrap();
+        let names: Vec<(&str, i64)> = top.iter().map(|s| (s.category_name.as_str(), s.amount.minor)).collect();
+        assert_eq!(names, vec![("Food & Dining", 450_000), ("Transportation", 50_000), ("Uncategorized", 10_000)]);
+
+        // Coffee sits two levels down and folds into Restaurants.
+        let second = roll_up(&conn, leaves, Some(1)).unwrap();
+        let dining = second.iter().find(|s| s.category_id == Some(3)).unwrap();
+        assert_eq!((dining.amount.minor, dining.parent_id), (150_000, Some(1)));
+        assert_eq!(second.len(), 4);
+    }
+}
@@ -0,0 +1,578 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use crate::db::{with_transaction, DbState};
+use crate::commands::payees::find_or_create_payee;
+use crate::commands::transactions::{row_to_transaction, TRANSACTION_SELECT};
+use crate::logic::rules::plan_changes;
+use crate::models::{Money, Rule, RuleChange, RuleInput, Transaction};
+
+const RULE_SELECT: &str = "SELECT r.id, r.name, r.priority, r.is_active, r.notes_contains,
+         r.payee_contains, r.amount_min, r.amount_max, r.amount_currency, r.account_id,
+         r.tx_type, r.set_category_id, r.set_payee_id, r.set_notes, r.created_at, r.updated_at,
+         (SELECT GROUP_CONCAT(rt.tag_id) FROM rule_tags rt WHERE rt.rule_id = r.id)
+         FROM rules r";
+
+/// Evaluation order: highest priority first, then oldest first.
+const RULE_ORDER: &str = "ORDER BY r.priority DESC, r.id";
+
+fn row_to_rule(row: &rusqlite::Row) -> rusqlite::Result<Rule> {
+    let currency: Option<String> = row.get(8)?;
+    let bound = |minor: Option<i64>| {
+        minor.zip(currency.clone()).map(|(m, c)| Money::new(m, c))
+    };
+    Ok(Rule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        priority: row.get(2)?,
+        is_active: row.get(3)?,
+        notes_contains: row.get(4)?,
+        payee_contains: row.get(5)?,
+        amount_min: bound(row.get(6)?),
+        amount_max: bound(row.get(7)?),
+        account_id: row.get(9)?,
+        tx_type: row.get(10)?,
+        set_category_id: row.get(11)?,
+        set_payee_id: row.get(12)?,
+        set_notes: row.get(13)?,
+        created_at: row.get(14)?,
+        updated_at: row.get(15)?,
+        tag_ids: row
+            .get::<_, Option<String>>(16)?
+            .map(|ids| ids.split(',').filter_map(|id| id.parse().ok()).collect())
+            .unwrap_or_default(),
+    })
+}
+
+fn load_rule(conn: &Connection, id: i64) -> Result<Rule, String> {
+    conn.query_row(&format!("{RULE_SELECT} WHERE r.id = ?1"), [id], |row| row_to_rule(row))
+        .map_err(|e| e.to_string())
+}
+
+fn load_rules(conn: &Connection, active_only: bool) -> Result<Vec<Rule>, String> {
+    let filter = if active_only { "WHERE r.is_active = 1" } else { "" };
+    let mut stmt = conn
+        .prepare(&format!("{RULE_SELECT} {filter} {RULE_ORDER}"))
+        .map_err(|e| e.to_string())?;
+    let rules = stmt
+        .query_map([], |row| row_to_rule(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rules)
+}
+
+fn non_blank(value: &Option<String>) -> Option<String> {
+    value.as_ref().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn validate(input: &RuleInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Rule name is required".into());
+    }
+    let has_condition = non_blank(&input.notes_contains).is_some()
+        || non_blank(&input.payee_contains).is_some()
+        || input.amount_min.is_some()
+        || input.amount_max.is_some()
+        || input.account_id.is_some()
+        || input.tx_type.is_some();
+    if !has_condition {
+        return Err("A rule needs at least one condition".into());
+    }
+    let has_action = input.set_category_id.is_some()
+        || input.set_payee_id.is_some()
+        || non_blank(&input.set_notes).is_some()
+        || !input.tag_ids.is_empty();
+    if !has_action {
+        return Err("A rule needs at least one action".into());
+    }
+    if let (Some(min), Some(max)) = (&input.amount_min, &input.amount_max) {
+        max.require_currency(&min.currency)?;
+        if min.minor > max.minor {
+            return Err("Minimum amount is greater than the maximum".into());
+        }
+    }
+    if let Some(ref t) = input.tx_type {
+        if t != "income" && t != "expense" {
+            return Err(format!("Unknown transaction type: {}", t));
+        }
+    }
+    Ok(())
+}
+
+/// Inserts (`id` = None) or updates a rule and replaces its tag actions.
+fn write_rule(conn: &Connection, id: Option<i64>, input: &RuleInput) -> Result<Rule, String> {
+    validate(input)?;
+    let currency = input
+        .amount_min
+        .as_ref()
+        .or(input.amount_max.as_ref())
+        .map(|m| m.currency.clone());
+    let params = rusqlite::params![
+        input.name.trim(),
+        input.priority,
+        input.is_active as i64,
+        non_blank(&input.notes_contains),
+        non_blank(&input.payee_contains),
+        input.amount_min.as_ref().map(|m| m.minor),
+        input.amount_max.as_ref().map(|m| m.minor),
+        currency,
+        input.account_id,
+        input.tx_type,
+        input.set_category_id,
+        input.set_payee_id,
+        non_blank(&input.set_notes),
+    ];
+
+    let id = match id {
+        Some(id) => {
+            conn.execute(
+                "UPDATE rules SET name=?1, priority=?2, is_active=?3, notes_contains=?4,
+                 payee_contains=?5, amount_min=?6, amount_max=?7, amount_currency=?8,
+                 account_id=?9, tx_type=?10, set_category_id=?11, set_payee_id=?12,
+                 set_notes=?13, updated_at=datetime('now') WHERE id=?14",
+                [params, rusqlite::params![id]].concat().as_slice(),
+            )
+            .map_err(|e| e.to_string())?;
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO rules (name, priority, is_active, notes_contains, payee_contains,
+                 amount_min, amount_max, amount_currency, account_id, tx_type,
+                 set_category_id, set_payee_id, set_notes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params,
+            )
+            .map_err(|e| e.to_string())?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    conn.execute("DELETE FROM rule_tags WHERE rule_id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    for tag_id in &input.tag_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO rule_tags (rule_id, tag_id) VALUES (?1, ?2)",
+            [id, *tag_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    load_rule(conn, id)
+}
+
+fn load_candidates(conn: &Connection, transaction_id: Option<i64>) -> Result<Vec<Transaction>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{TRANSACTION_SELECT}
+             WHERE t.transfer_id IS NULL AND t.status != 'reconciled' AND (?1 IS NULL OR t.id = ?1)
+             ORDER BY t.date DESC, t.id DESC"
+        ))
+        .map_err(|e| e.to_string())?;
+    let txns = stmt
+        .query_map([transaction_id], |row| row_to_transaction(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(txns)
+}
+
+fn plan_all(conn: &Connection, rules: &[Rule], overwrite: bool) -> Result<Vec<RuleChange>, String> {
+    Ok(load_candidates(conn, None)?
+        .iter()
+        .filter_map(|tx| plan_changes(rules, tx, overwrite))
+        .collect())
+}
+
+fn apply_change(conn: &Connection, change: &RuleChange) -> Result<(), String> {
+    let id = change.transaction.id;
+    if let Some(category_id) = change.category_id {
+        conn.execute(
+            "UPDATE transactions SET category_id = ?1 WHERE id = ?2",
+            [category_id, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if let Some(payee_id) = change.payee_id {
+        conn.execute(
+            "UPDATE transactions SET payee_id = ?1 WHERE id = ?2",
+            [payee_id, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if let Some(ref notes) = change.notes {
+        conn.execute(
+            "UPDATE transactions SET notes = ?1 WHERE id = ?2",
+            rusqlite::params![notes, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    for tag_id in &change.add_tag_ids {
+        conn.execute(
+            "INSERT OR IGNORE INTO transaction_tags (transaction_id, tag_id) VALUES (?1, ?2)",
+            [id, *tag_id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "UPDATE transactions SET updated_at = datetime('now') WHERE id = ?1",
+        [id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Runs the active rules against a newly written transaction, filling only
+/// the fields it left empty. Called on create and for every imported row.
+pub(crate) fn apply_rules_to_transaction(conn: &Connection, transaction_id: i64) -> Result<(), String> {
+    let rules = load_rules(conn, true)?;
+    if rules.is_empty() {
+        return Ok(());
+    }
+    for tx in load_candidates(conn, Some(transaction_id))? {
+        if let Some(change) = plan_changes(&rules, &tx, false) {
+            apply_change(conn, &change)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_rules(state: State<DbState>) -> Result<Vec<Rule>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    load_rules(conn, false)
+}
+
+#[tauri::command]
+pub fn create_rule(state: State<DbState>, rule: RuleInput) -> Result<Rule, String> {
+    with_transaction(&state, |conn| write_rule(conn, None, &rule))
+}
+
+#[tauri::command]
+pub fn update_rule(state: State<DbState>, id: i64, rule: RuleInput) -> Result<Rule, String> {
+    with_transaction(&state, |conn| write_rule(conn, Some(id), &rule))
+}
+
+#[tauri::command]
+pub fn delete_rule(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM rules WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Dry run: the existing transactions a single rule would change, evaluated
+/// on its own (inactive rules can be previewed too). Nothing is written.
+#[tauri::command]
+pub fn preview_rule(
+    state: State<DbState>,
+    id: i64,
+    overwrite: Option<bool>,
+) -> Result<Vec<RuleChange>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut rule = load_rule(conn, id)?;
+    rule.is_active = 1;
+    plan_all(conn, &[rule], overwrite.unwrap_or(false))
+}
+
+/// Applies every active rule to all existing transactions. With `overwrite`,
+/// rule actions replace values that are already set. Returns the number of
+/// transactions changed.
+#[tauri::command]
+pub fn apply_rules_retroactively(
+    state: State<DbState>,
+    overwrite: Option<bool>,
+) -> Result<usize, String> {
+    with_transaction(&state, |conn| {
+        let rules = load_rules(conn, true)?;
+        let changes = plan_all(conn, &rules, overwrite.unwrap_or(false))?;
+        for change in &changes {
+            apply_change(conn, change)?;
+        }
+        Ok(changes.len())
+    })
+}
+
+// ── JSON sharing ────────────────────────────────────────────────────────────
+
+/// Portable form of a rule: accounts, categories, payees and tags are
+/// referenced by name so the file works on another database.
+#[derive(Debug, Serialize, Deserialize)]
+struct SharedRule {
+    name: String,
+    priority: i64,
+    is_active: bool,
+    notes_contains: Option<String>,
+    payee_contains: Option<String>,
+    amount_min: Option<Money>,
+    amount_max: Option<Money>,
+    account: Option<String>,
+    tx_type: Option<String>,
+    set_category: Option<String>,
+    set_payee: Option<String>,
+    set_notes: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RulesFile {
+    version: u32,
+    rules: Vec<SharedRule>,
+}
+
+const RULES_FILE_VERSION: u32 = 1;
+
+fn name_of(conn: &Connection, table: &str, id: Option<i64>) -> Result<Option<String>, String> {
+    id.map(|id| {
+        conn.query_row(&format!("SELECT name FROM {table} WHERE id = ?1"), [id], |r| r.get(0))
+            .map_err(|e| e.to_string())
+    })
+    .transpose()
+}
+
+fn id_of(conn: &Connection, table: &str, name: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        &format!("SELECT id FROM {table} WHERE name = ?1 COLLATE NOCASE ORDER BY id LIMIT 1"),
+        [name],
+        |r| r.get(0),
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+pub(crate) fn export_rules(conn: &Connection) -> Result<String, String> {
+    let mut shared = Vec::new();
+    for rule in load_rules(conn, false)? {
+        let mut tags = Vec::new();
+        for tag_id in &rule.tag_ids {
+            tags.extend(name_of(conn, "tags", Some(*tag_id))?);
+        }
+        shared.push(SharedRule {
+            account: name_of(conn, "accounts", rule.account_id)?,
+            set_category: name_of(conn, "categories", rule.set_category_id)?,
+            set_payee: name_of(conn, "payees", rule.set_payee_id)?,
+            name: rule.name,
+            priority: rule.priority,
+            is_active: rule.is_active != 0,
+            notes_contains: rule.notes_contains,
+            payee_contains: rule.payee_contains,
+            amount_min: rule.amount_min,
+            amount_max: rule.amount_max,
+            tx_type: rule.tx_type,
+            set_notes: rule.set_notes,
+            tags,
+        });
+    }
+    let file = RulesFile { version: RULES_FILE_VERSION, rules: shared };
+    serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+}
+
+/// Adds the rules from an exported file. Accounts and categories must already
+/// exist; missing payees and tags are created. Returns the number imported.
+pub(crate) fn import_rules(conn: &Connection, json: &str) -> Result<usize, String> {
+    let file: RulesFile = serde_json::from_str(json).map_err(|e| format!("Invalid rules file: {e}"))?;
+    if file.version > RULES_FILE_VERSION {
+        return Err(format!("Rules file version {} is not supported", file.version));
+    }
+
+    for shared in &file.rules {
+        let account_id = match shared.account {
+            Some(ref name) => Some(
+                id_of(conn, "accounts", name)?
+                    .ok_or_else(|| format!("Rule \"{}\": account \"{}\" not found", shared.name, name))?,
+            ),
+            None => None,
+        };
+        let set_category_id = match shared.set_category {
+            Some(ref name) => Some(
+                id_of(conn, "categories", name)?
+                    .ok_or_else(|| format!("Rule \"{}\": category \"{}\" not found", shared.name, name))?,
+            ),
+            None => None,
+        };
+        let set_payee_id = match shared.set_payee {
+            Some(ref name) => find_or_create_payee(conn, name)?,
+            None => None,
+        };
+        let mut tag_ids = Vec::new();
+        for tag in &shared.tags {
+            let id = match id_of(conn, "tags", tag)? {
+                Some(id) => id,
+                None => {
+                    conn.execute("INSERT INTO tags (name) VALUES (?1)", [tag.trim()])
+                        .map_err(|e| e.to_string())?;
+                    conn.last_insert_rowid()
+                }
+            };
+            tag_ids.push(id);
+        }
+
+        let input = RuleInput {
+            name: shared.name.clone(),
+            priority: shared.priority,
+            is_active: shared.is_active,
+            notes_contains: shared.notes_contains.clone(),
+            payee_contains: shared.payee_contains.clone(),
+            amount_min: shared.amount_min.clone(),
+            amount_max: shared.amount_max.clone(),
+            account_id,
+            tx_type: shared.tx_type.clone(),
+            set_category_id,
+            set_payee_id,
+            set_notes: shared.set_notes.clone(),
+            tag_ids,
+        };
+        write_rule(conn, None, &input).map_err(|e| format!("Rule \"{}\": {}", shared.name, e))?;
+    }
+    Ok(file.rules.len())
+}
+
+#[tauri::command]
+pub async fn export_rules_json(
+    app: AppHandle,
+    state: State<'_, DbState>,
+) -> Result<(), String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let json = {
+        let guard = state.0.lock().map_err(|e| e.to_string())?;
+        let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+        export_rules(conn)?
+    };
+
+    let path = app
+        .dialog()
+        .file()
+        .set_file_name("accrue_rules.json")
+        .blocking_save_file();
+
+    if let Some(file_path) = path {
+        let path_str = file_path.to_string();
+        std::fs::write(&path_str, json).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Returns the number of rules imported (0 if the dialog was cancelled).
+#[tauri::command]
+pub async fn import_rules_json(
+    app: AppHandle,
+    state: State<'_, DbState>,
+) -> Result<usize, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let path = app
+        .dialog()
+        .file()
+        .add_filter("JSON", &["json"])
+        .blocking_pick_file();
+
+    let Some(file_path) = path else {
+        return Ok(0);
+    };
+    let json = std::fs::read_to_string(file_path.to_string()).map_err(|e| e.to_string())?;
+    with_transaction(&state, |conn| import_rules(conn, &json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    fn rule_input(name: &str, priority: i64) -> RuleInput {
+        RuleInput {
+            name: name.into(),
+            priority,
+            is_active: true,
+            notes_contains: None,
+            payee_contains: None,
+            amount_min: None,
+            amount_max: None,
+            account_id: None,
+            tx_type: None,
+            set_category_id: None,
+            set_payee_id: None,
+            set_notes: None,
+            tag_ids: Vec::new(),
+        }
+    }
+
+    fn setup() -> Connection {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO categories (name, direction) VALUES ('Food', 'expense'), ('Transport', 'expense');
+             INSERT INTO tags (name) VALUES ('work');
+             INSERT INTO transactions (account_id, type, amount, date, notes)
+             VALUES (1, 'expense', 25000, '2025-01-15', 'GRAB FOOD 1234'),
+                    (1, 'expense', 900000, '2025-01-16', 'Grab car to airport');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn higher_priority_rule_wins_and_amount_bounds_apply() {
+        let conn = setup();
+        let food = id_of(&conn, "categories", "Food").unwrap();
+        let transport = id_of(&conn, "categories", "Transport").unwrap();
+
+        let mut grab = rule_input("Grab", 0);
+        grab.notes_contains = Some("grab".into());
+        grab.set_category_id = transport;
+        write_rule(&conn, None, &grab).unwrap();
+
+        let mut grab_food = rule_input("Grab Food", 10);
+        grab_food.notes_contains = Some("grab food".into());
+        grab_food.amount_max = Some(Money::new(100000, "PHP"));
+        grab_food.set_category_id = food;
+        grab_food.tag_ids = vec![1];
+        write_rule(&conn, None, &grab_food).unwrap();
+
+        let rules = load_rules(&conn, true).unwrap();
+        let changes = plan_all(&conn, &rules, false).unwrap();
+        assert_eq!(changes.len(), 2);
+        for change in &changes {
+            apply_change(&conn, change).unwrap();
+        }
+
+        let categories: Vec<Option<i64>> = conn
+            .prepare("SELECT category_id FROM transactions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(categories, vec![food, transport]);
+        assert_eq!(load_candidates(&conn, Some(1)).unwrap()[0].tag_ids, vec![1]);
+
+        // Already categorized: nothing left to do without overwrite.
+        assert!(plan_all(&conn, &rules, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn exported_rules_import_by_name() {
+        let conn = setup();
+        let mut rule = rule_input("Commute", 5);
+        rule.notes_contains = Some("grab car".into());
+        rule.account_id = Some(1);
+        rule.set_category_id = id_of(&conn, "categories", "Transport").unwrap();
+        rule.tag_ids = vec![1];
+        write_rule(&conn, None, &rule).unwrap();
+
+        let json = export_rules(&conn).unwrap();
+        conn.execute("DELETE FROM rules", []).unwrap();
+        assert_eq!(import_rules(&conn, &json).unwrap(), 1);
+
+        let imported = &load_rules(&conn, false).unwrap()[0];
+        assert_eq!(imported.name, "Commute");
+        assert_eq!(imported.account_id, Some(1));
+        assert_eq!(imported.set_category_id, rule.set_category_id);
+        assert_eq!(imported.tag_ids, vec![1]);
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::HashMap;
+use rusqlite::{Connection, OptionalExtension};
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::{row_to_account, ACCOUNT_SELECT};
+use crate::logic::balance::compute_balance;
+use crate::logic::credit_cards::day_in_month;
+use crate::logic::csv_import::parse_date;
+use crate::logic::savings::{
+    contribution_months, project, CONTRIBUTION_FREQUENCIES, MAX_TERM_MONTHS, PAYOUT_MODES, SAVINGS_KINDS,
+};
+use crate::models::{
+    Account, ContributionCheck, Money, ProjectedSavingsYear, SavingsDividend, SavingsPlan, SavingsPlanInput,
+    SavingsProjection, SavingsRate,
+};
+
+const PLAN_SELECT: &str = "SELECT p.account_id, p.kind, p.start_date, p.term_months, p.contribution_amount,
+         p.contribution_frequency, p.annual_rate, p.payout_mode, p.payout_account_id,
+         p.dividend_category_id, p.created_at, p.updated_at, a.currency
+         FROM savings_plans p
+         JOIN accounts a ON a.id = p.account_id";
+
+fn row_to_plan(row: &rusqlite::Row) -> rusqlite::Result<SavingsPlan> {
+    let currency: String = row.get(12)?;
+    Ok(SavingsPlan {
+        account_id: row.get(0)?,
+        kind: row.get(1)?,
+        start_date: row.get(2)?,
+        term_months: row.get(3)?,
+        contribution_amount: Money::new(row.get(4)?, currency),
+        contribution_frequency: row.get(5)?,
+        annual_rate: row.get(6)?,
+        payout_mode: row.get(7)?,
+        payout_account_id: row.get(8)?,
+        dividend_category_id: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+        maturity_date: String::new(),
+        rates: Vec::new(),
+    })
+}
+
+fn load_account(conn: &Connection, account_id: i64) -> Result<Account, String> {
+    conn.query_row(&format!("{ACCOUNT_SELECT} WHERE id = ?1"), [account_id], |row| row_to_account(row))
+        .map_err(|e| e.to_string())
+}
+
+fn savings_account(conn: &Connection, account_id: i64) -> Result<Account, String> {
+    let account = load_account(conn, account_id)?;
+    if account.account_type != "savings" {
+        return Err(format!("{} is not a savings account", account.name));
+    }
+    Ok(account)
+}
+
+/// The `months`-th monthly anniversary of the plan's start, clamped to
+/// shorter months.
+fn plan_date(conn: &Connection, plan: &SavingsPlan, months: i64) -> Result<String, String> {
+    let start = plan.start_date.as_str();
+    let day: i64 = start[8..10].parse().map_err(|_| format!("Invalid date \"{}\"", start))?;
+    day_in_month(conn, start, months, day)
+}
+
+fn find_plan(conn: &Connection, account_id: i64) -> Result<Option<SavingsPlan>, String> {
+    let plan = conn
+        .query_row(&format!("{PLAN_SELECT} WHERE p.account_id = ?1"), [account_id], |row| row_to_plan(row))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let Some(mut plan) = plan else {
+        return Ok(None);
+    };
+    plan.maturity_date = plan_date(conn, &plan, plan.term_months)?;
+    let mut stmt = conn
+        .prepare("SELECT year, rate FROM savings_rates WHERE account_id = ?1 ORDER BY year")
+        .map_err(|e| e.to_string())?;
+    plan.rates = stmt
+        .query_map([account_id], |r| Ok(SavingsRate { year: r.get(0)?, rate: r.get(1)? }))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(Some(plan))
+}
+
+pub(crate) fn load_plan(conn: &Connection, account_id: i64) -> Result<SavingsPlan, String> {
+    find_plan(conn, account_id)?.ok_or_else(|| "Set up the savings plan first".to_string())
+}
+
+pub(crate) fn set_plan(conn: &Connection, account_id: i64, input: &SavingsPlanInput) -> Result<SavingsPlan, String> {
+    let account = savings_account(conn, account_id)?;
+    input.contribution_amount.require_currency(&account.currency)?;
+    if !SAVINGS_KINDS.contains(&input.kind.as_str()) {
+        return Err(format!("Unknown savings kind \"{}\"", input.kind));
+    }
+    if !(1..=MAX_TERM_MONTHS).contains(&input.term_months) {
+        return Err(format!("The term must be between 1 and {} months", MAX_TERM_MONTHS));
+    }
+    if input.contribution_amount.minor < 0 {
+        return Err("Contributions cannot be negative".into());
+    }
+    if !CONTRIBUTION_FREQUENCIES.contains(&input.contribution_frequency.as_str()) {
+        return Err(format!("Unknown contribution frequency \"{}\"", input.contribution_frequency));
+    }
+    if !input.annual_rate.is_finite() || input.annual_rate < 0.0 {
+        return Err("The rate cannot be negative".into());
+    }
+    if !PAYOUT_MODES.contains(&input.payout_mode.as_str()) {
+        return Err(format!("Unknown payout mode \"{}\"", input.payout_mode));
+    }
+    if let Some(payout_id) = input.payout_account_id {
+        if payout_id == account_id {
+            return Err("Leave the payout account empty to keep dividends in the plan".into());
+        }
+        let payout = load_account(conn, payout_id)?;
+        if payout.currency != account.currency {
+            return Err(format!("{} is not in {}", payout.name, account.currency));
+        }
+    }
+    let start_date = parse_date(input.start_date.trim(), "YYYY-MM-DD")?;
+
+    conn.execute(
+        "INSERT INTO savings_plans (account_id, kind, start_date, term_months, contribution_amount,
+           contribution_frequency, annual_rate, payout_mode, payout_account_id, dividend_category_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(account_id) DO UPDATE SET
+           kind = excluded.kind, start_date = excluded.start_date, term_months = excluded.term_months,
+           contribution_amount = excluded.contribution_amount,
+           contribution_frequency = excluded.contribution_frequency, annual_rate = excluded.annual_rate,
+           payout_mode = excluded.payout_mode, payout_account_id = excluded.payout_account_id,
+           dividend_category_id = excluded.dividend_category_id, updated_at = datetime('now')",
+        rusqlite::params![
+            account_id,
+            input.kind,
+            start_date,
+            input.term_months,
+            input.contribution_amount.minor,
+            input.contribution_frequency,
+            input.annual_rate,
+            input.payout_mode,
+            input.payout_account_id,
+            input.dividend_category_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    load_plan(conn, account_id)
+}
+
+/// Sets the rate declared for calendar `year`, replacing any earlier one.
+pub(crate) fn set_rate(conn: &Connection, account_id: i64, year: i64, rate: f64) -> Result<SavingsPlan, String> {
+    load_plan(conn, account_id)?;
+    if !rate.is_finite() || rate < 0.0 {
+        return Err("The rate cannot be negative".into());
+    }
+    conn.execute(
+        "INSERT INTO savings_rates (account_id, year, rate) VALUES (?1, ?2, ?3)
+         ON CONFLICT(account_id, year) DO UPDATE SET rate = excluded.rate",
+        rusqlite::params![account_id, year, rate],
+    )
+    .map_err(|e| e.to_string())?;
+    load_plan(conn, account_id)
+}
+
+/// Credits a dividend (or interest) as income. Compounding plans keep it in
+/// the plan's account; annual-payout plans post it to the payout account
+/// when one is set.
+pub(crate) fn record_dividend(
+    conn: &Connection,
+    account_id: i64,
+    amount: &Money,
+    date: &str,
+    notes: Option<&str>,
+) -> Result<SavingsDividend, String> {
+    let account = savings_account(conn, account_id)?;
+    let plan = load_plan(conn, account_id)?;
+    amount.require_currency(&account.currency)?;
+    if amount.minor <= 0 {
+        return Err("Dividends must be positive".into());
+    }
+    let target = match plan.payout_account_id {
+        Some(payout_id) if plan.payout_mode == "annual_payout" => payout_id,
+        _ => account_id,
+    };
+    let default_notes = if plan.kind == "mp2" { "Savings dividend" } else { "Savings interest" };
+    conn.execute(
+        "INSERT INTO transactions (account_id, category_id, type, amount, date, notes)
+         VALUES (?1, ?2, 'income', ?3, ?4, ?5)",
+        rusqlite::params![target, plan.dividend_category_id, amount.minor, date, notes.unwrap_or(default_notes)],
+    )
+    .map_err(|e| e.to_string())?;
+    let transaction_id = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO savings_dividends (transaction_id, account_id) VALUES (?1, ?2)",
+        rusqlite::params![transaction_id, account_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(SavingsDividend {
+        transaction_id,
+        account_id: target,
+        date: date.to_string(),
+        amount: amount.clone(),
+    })
+}
+
+fn credited_dividends(conn: &Connection, account: &Account) -> Result<Vec<SavingsDividend>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.account_id, t.date, t.amount
+             FROM savings_dividends d
+             JOIN transactions t ON t.id = d.transaction_id
+             WHERE d.account_id = ?1
+             ORDER BY t.date, t.id",
+        )
+        .map_err(|e| e.to_string())?;
+    let dividends = stmt
+        .query_map([account.id], |r| {
+            Ok(SavingsDividend {
+                transaction_id: r.get(0)?,
+                account_id: r.get(1)?,
+                date: r.get(2)?,
+                amount: Money::new(r.get(3)?, account.currency.clone()),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(dividends)
+}
+
+/// Opening balance plus every deposit into the plan's account up to `date`,
+/// leaving out credited dividends.
+fn contributed_by(conn: &Connection, account: &Account, date: &str) -> Result<i64, String> {
+    let deposits: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions t
+             WHERE account_id = ?1 AND type = 'income' AND date <= ?2
+               AND NOT EXISTS (SELECT 1 FROM savings_dividends d WHERE d.transaction_id = t.id)",
+            rusqlite::params![account.id, date],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(account.opening_balance.minor + deposits)
+}
+
+/// Projects the plan to maturity assuming every contribution is made, using
+/// declared rates where there are any, and checks the contributions due by
+/// `as_of` against what was actually deposited.
+pub(crate) fn savings_projection(conn: &Connection, account_id: i64, as_of: &str) -> Result<SavingsProjection, String> {
+    let account = savings_account(conn, account_id)?;
+    let plan = load_plan(conn, account_id)?;
+    let currency = account.currency.clone();
+    let money = |minor: i64| Money::new(minor, currency.clone());
+
+    let term = plan.term_months;
+    let due_months = contribution_months(&plan.contribution_frequency, term);
+    let mut contributions = vec![0; term as usize];
+    for &month in &due_months {
+        contributions[month as usize] = plan.contribution_amount.minor;
+    }
+    let declared: HashMap<i64, f64> = plan.rates.iter().map(|r| (r.year, r.rate)).collect();
+    let start_year: i64 = plan.start_date[0..4].parse().map_err(|_| "Invalid start date".to_string())?;
+    let start_month: i64 = plan.start_date[5..7].parse().map_err(|_| "Invalid start date".to_string())?;
+    let rates: Vec<f64> = (0..term)
+        .map(|month| {
+            let year = start_year + (start_month - 1 + month) / 12;
+            declared.get(&year).copied().unwrap_or(plan.annual_rate)
+        })
+        .collect();
+    let projection = project(&contributions, &rates, plan.payout_mode == "compounding");
+
+    let mut years = Vec::new();
+    for year in &projection.years {
+        years.push(ProjectedSavingsYear {
+            end_date: plan_date(conn, &plan, year.months)?,
+            contributions: money(year.contributions),
+            dividend: money(year.dividend),
+            balance: money(year.balance),
+        });
+    }
+
+    let mut checks = Vec::new();
+    let mut cumulative_expected = 0;
+    for &month in &due_months {
+        let date = plan_date(conn, &plan, month)?;
+        if date.as_str() > as_of {
+            break;
+        }
+        cumulative_expected += plan.contribution_amount.minor;
+        let cumulative_paid = contributed_by(conn, &account, &date)?;
+        checks.push(ContributionCheck {
+            date,
+            expected: plan.contribution_amount.clone(),
+            cumulative_expected: money(cumulative_expected),
+            cumulative_paid: money(cumulative_paid),
+            shortfall: money((cumulative_expected - cumulative_paid).max(0)),
+        });
+    }
+    let shortfall = if checks.is_empty() {
+        0
+    } else {
+        (cumulative_expected - contributed_by(conn, &account, as_of)?).max(0)
+    };
+
+    let credited = credited_dividends(conn, &account)?;
+    let credited_total = money(credited.iter().map(|d| d.amount.minor).sum());
+    Ok(SavingsProjection {
+        balance: compute_balance(conn, &account, Some(as_of)),
+        as_of: as_of.to_string(),
+        years,
+        total_contributions: money(projection.contributions),
+        total_dividends: money(projection.dividends),
+        paid_out: money(projection.paid_out),
+        maturity_value: money(projection.maturity_value),
+        credited,
+        credited_total,
+        contributions: checks,
+        shortfall: money(shortfall),
+        plan,
+    })
+}
+
+/// Plan of a savings account, if one is set up.
+#[tauri::command]
+pub fn get_savings_plan(state: State<DbState>, account_id: i64) -> Result<Option<SavingsPlan>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    find_plan(conn, account_id)
+}
+
+#[tauri::command]
+pub fn set_savings_plan(state: State<DbState>, account_id: i64, input: SavingsPlanInput) -> Result<SavingsPlan, String> {
+    with_transaction(&state, |conn| set_plan(conn, account_id, &input))
+}
+
+/// Removes the plan and its declared rates; credited dividends stay as
+/// ordinary income.
+#[tauri::command]
+pub fn delete_savings_plan(state: State<DbState>, account_id: i64) -> Result<(), String> {
+    with_transaction(&state, |conn| {
+        conn.execute("DELETE FROM savings_dividends WHERE account_id = ?1", [account_id])
+            .map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM savings_plans WHERE account_id = ?1", [account_id])
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn set_savings_rate(state: State<DbState>, account_id: i64, year: i64, rate: f64) -> Result<SavingsPlan, String> {
+    with_transaction(&state, |conn| set_rate(conn, account_id, year, rate))
+}
+
+#[tauri::command]
+pub fn delete_savings_rate(state: State<DbState>, account_id: i64, year: i64) -> Result<SavingsPlan, String> {
+    with_transaction(&state, |conn| {
+        conn.execute(
+            "DELETE FROM savings_rates WHERE account_id = ?1 AND year = ?2",
+            rusqlite::params![account_id, year],
+        )
+        .map_err(|e| e.to_string())?;
+        load_plan(conn, account_id)
+    })
+}
+
+#[tauri::command]
+pub fn get_savings_projection(
+    state: State<DbState>,
+    account_id: i64,
+    as_of: Option<String>,
+) -> Result<SavingsProjection, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let as_of = match as_of {
+        Some(date) => parse_date(&date, "YYYY-MM-DD")?,
+        None => conn
+            .query_row("SELECT DATE('now')", [], |r| r.get(0))
+            .map_err(|e| e.to_string())?,
+    };
+    savings_projection(conn, account_id, &as_of)
+}
+
+/// Records a credited dividend or interest payment; delete its transaction
+/// to undo it.
+#[tauri::command]
+pub fn record_savings_dividend(
+    state: State<DbState>,
+    account_id: i64,
+    amount: Money,
+    date: String,
+    notes: Option<String>,
+) -> Result<SavingsDividend, String> {
+    with_transaction(&state, |conn| {
+        let date = parse_date(date.trim(), "YYYY-MM-DD")?;
+        record_dividend(conn, account_id, &amount, &date, notes.as_deref())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn projection_uses_declared_rates_and_reports_shortfalls() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO categories (name, direction) VALUES ('Dividends', 'income');
+             INSERT INTO accounts (name, type, opening_balance) VALUES ('Bank', 'debit', 50000000);
+             INSERT INTO accounts (name, type) VALUES ('MP2', 'savings');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'income', 100000, '2024-01-15');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'income', 100000, '2024-02-15');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'income', 50000, '2024-03-15');",
+        )
+        .unwrap();
+        let input = SavingsPlanInput {
+            kind: "mp2".into(),
+            start_date: "2024-01-15".into(),
+            term_months: 60,
+            contribution_amount: Money::new(100_000, "PHP"),
+            contribution_frequency: "monthly".into(),
+            annual_rate: 7.0,
+            payout_mode: "compounding".into(),
+            payout_account_id: None,
+            dividend_category_id: Some(1),
+        };
+        assert!(set_plan(&conn, 1, &input).is_err());
+        let plan = set_plan(&conn, 2, &input).unwrap();
+        assert_eq!(plan.maturity_date, "2029-01-15");
+
+        let projection = savings_projection(&conn, 2, "2024-04-20").unwrap();
+        assert_eq!(projection.years.len(), 5);
+        assert_eq!(projection.years[0].end_date, "2025-01-15");
+        assert_eq!(projection.years[0].dividend.minor, 45_500);
+        assert_eq!(projection.total_contributions.minor, 6_000_000);
+        assert_eq!(projection.contributions.len(), 4);
+        assert_eq!(projection.contributions[2].shortfall.minor, 50_000);
+        assert_eq!(projection.shortfall.minor, 150_000);
+
+        // A lower declared rate for 2024 trims the first year's dividend.
+        set_rate(&conn, 2, 2024, 6.0).unwrap();
+        let projection = savings_projection(&conn, 2, "2024-04-20").unwrap();
+        assert!(projection.years[0].dividend.minor < 45_500);
+
+        // Dividends credited to the plan are income but not contributions.
+        record_dividend(&conn, 2, &Money::new(40_000, "PHP"), "2024-04-01", None).unwrap();
+        let projection = savings_projection(&conn, 2, "2024-04-20").unwrap();
+        assert_eq!(projection.credited_total.minor, 40_000);
+        assert_eq!(projection.shortfall.minor, 150_000);
+        assert_eq!(projection.balance.minor, 290_000);
+
+        // Annual payout sends the next dividend to the bank account.
+        let payout = SavingsPlanInput {
+            payout_mode: "annual_payout".into(),
+            payout_account_id: Some(1),
+            ..input
+        };
+        set_plan(&conn, 2, &payout).unwrap();
+        let paid = record_dividend(&conn, 2, &Money::new(10_000, "PHP"), "2025-01-15", None).unwrap();
+        assert_eq!(paid.account_id, 1);
+        let projection = savings_projection(&conn, 2, "2025-01-20").unwrap();
+        assert_eq!(projection.credited.len(), 2);
+        assert_eq!(projection.maturity_value.minor, 6_000_000);
+    }
+}
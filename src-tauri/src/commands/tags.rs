@@ -0,0 +1,114 @@
+use tauri::State;
+use crate::db::{with_transaction, DbState};
+use crate::models::Tag;
+
+fn row_to_tag(row: &rusqlite::Row) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        color: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Tag name is required".into());
+    }
+    Ok(name.to_string())
+}
+
+#[tauri::command]
+pub fn list_tags(state: State<DbState>) -> Result<Vec<Tag>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT id, name, color, created_at FROM tags ORDER BY name COLLATE NOCASE")
+        .map_err(|e| e.to_string())?;
+
+    let tags = stmt
+        .query_map([], |row| row_to_tag(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub fn create_tag(
+    state: State<DbState>,
+    name: String,
+    color: Option<String>,
+) -> Result<Tag, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute(
+        "INSERT INTO tags (name, color) VALUES (?1, ?2)",
+        rusqlite::params![normalize_name(&name)?, color],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let id = conn.last_insert_rowid();
+    conn.query_row(
+        "SELECT id, name, color, created_at FROM tags WHERE id = ?1",
+        [id],
+        |row| row_to_tag(row),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_tag(
+    state: State<DbState>,
+    id: i64,
+    name: String,
+    color: Option<String>,
+) -> Result<Tag, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute(
+        "UPDATE tags SET name=?1, color=?2 WHERE id=?3",
+        rusqlite::params![normalize_name(&name)?, color, id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT id, name, color, created_at FROM tags WHERE id = ?1",
+        [id],
+        |row| row_to_tag(row),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Deletes the tag; its links to transactions are removed by cascade.
+#[tauri::command]
+pub fn delete_tag(state: State<DbState>, id: i64) -> Result<(), String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Replaces the full tag set of a transaction.
+#[tauri::command]
+pub fn set_transaction_tags(
+    state: State<DbState>,
+    transaction_id: i64,
+    tag_ids: Vec<i64>,
+) -> Result<(), String> {
+    with_transaction(&state, |conn| {
+        conn.execute("DELETE FROM transaction_tags WHERE transaction_id = ?1", [transaction_id])
+            .map_err(|e| e.to_string())?;
+        for tag_id in &tag_ids {
+            conn.execute(
+                "INSERT OR IGNORE INTO transaction_tags (transaction_id, tag_id) VALUES (?1, ?2)",
+                [transaction_id, *tag_id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    })
+}
@@ -1,15 +1,39 @@
+use rusqlite::Connection;
 use tauri::State;
-use crate::db::DbState;
-use crate::models::Transaction;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::account_currency;
+use crate::commands::installments::require_no_plan;
+use crate::commands::investments::{remove_trade, require_no_trade};
+use crate::commands::reconciliation::require_unlocked;
+use crate::commands::rules::apply_rules_to_transaction;
+use crate::commands::transfers::remove_transfer;
+use crate::models::{Money, Transaction, TransactionSplit, TransactionSplitInput, DEFAULT_CURRENCY};
 
-fn row_to_transaction(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
+/// Column list shared by every query that feeds `row_to_transaction`.
+pub(crate) const TRANSACTION_SELECT: &str = "SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type, t.amount,
+         t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date,
+         t.created_at, t.updated_at, c.name, a.name, a.currency,
+         EXISTS (SELECT 1 FROM transaction_splits s WHERE s.transaction_id = t.id),
+         (SELECT GROUP_CONCAT(tt.tag_id) FROM transaction_tags tt WHERE tt.transaction_id = t.id),
+         t.payee_id, p.name, t.import_batch_id, t.duplicate_of,
+         (SELECT COUNT(*) FROM attachments att WHERE att.transaction_id = t.id),
+         t.status, t.reconciliation_id, t.installment_plan_id, t.installment_kind, t.trade_id
+         FROM transactions t
+         LEFT JOIN categories c ON t.category_id = c.id
+         LEFT JOIN accounts a ON t.account_id = a.id
+         LEFT JOIN payees p ON t.payee_id = p.id";
+
+pub(crate) fn row_to_transaction(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
+    let currency: Option<String> = row.get(15)?;
+    let currency = currency.unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
     Ok(Transaction {
         id: row.get(0)?,
         account_id: row.get(1)?,
         category_id: row.get(2)?,
         transfer_id: row.get(3)?,
+        payee_id: row.get(18)?,
         tx_type: row.get(4)?,
-        amount: row.get(5)?,
+        amount: Money::new(row.get(5)?, currency),
         date: row.get(6)?,
         notes: row.get(7)?,
         is_recurring: row.get(8)?,
@@ -19,9 +43,105 @@ fn row_to_transaction(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
         updated_at: row.get(12)?,
         category_name: row.get(13)?,
         account_name: row.get(14)?,
+        payee_name: row.get(19)?,
+        import_batch_id: row.get(20)?,
+        duplicate_of: row.get(21)?,
+        status: row.get(23)?,
+        reconciliation_id: row.get(24)?,
+        installment_plan_id: row.get(25)?,
+        installment_kind: row.get(26)?,
+        trade_id: row.get(27)?,
+        is_split: row.get(16)?,
+        tag_ids: row
+            .get::<_, Option<String>>(17)?
+            .map(|ids| ids.split(',').filter_map(|id| id.parse().ok()).collect())
+            .unwrap_or_default(),
+        attachment_count: row.get(22)?,
+    })
+}
+
+const SPLIT_SELECT: &str = "SELECT s.id, s.transaction_id, s.category_id, s.amount, s.memo, s.created_at,
+         c.name, a.currency
+         FROM transaction_splits s
+         JOIN transactions t ON t.id = s.transaction_id
+         JOIN accounts a ON a.id = t.account_id
+         LEFT JOIN categories c ON c.id = s.category_id";
+
+fn row_to_split(row: &rusqlite::Row) -> rusqlite::Result<TransactionSplit> {
+    Ok(TransactionSplit {
+        id: row.get(0)?,
+        transaction_id: row.get(1)?,
+        category_id: row.get(2)?,
+        amount: Money::new(row.get(3)?, row.get::<_, String>(7)?),
+        memo: row.get(4)?,
+        created_at: row.get(5)?,
+        category_name: row.get(6)?,
     })
 }
 
+fn load_splits(conn: &Connection, transaction_id: i64) -> Result<Vec<TransactionSplit>, String> {
+    let mut stmt = conn
+        .prepare(&format!("{SPLIT_SELECT} WHERE s.transaction_id = ?1 ORDER BY s.id"))
+        .map_err(|e| e.to_string())?;
+    let splits = stmt
+        .query_map([transaction_id], |row| row_to_split(row))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(splits)
+}
+
+/// Replaces a transaction's split lines after checking they add up to its
+/// amount. An empty list removes the split.
+fn replace_splits(
+    conn: &Connection,
+    transaction_id: i64,
+    splits: &[TransactionSplitInput],
+) -> Result<Vec<TransactionSplit>, String> {
+    let (amount, transfer_id, currency): (i64, Option<i64>, String) = conn
+        .query_row(
+            "SELECT t.amount, t.transfer_id, a.currency FROM transactions t
+             JOIN accounts a ON a.id = t.account_id WHERE t.id = ?1",
+            [transaction_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    if transfer_id.is_some() {
+        return Err("Transfer legs cannot be split".into());
+    }
+    require_unlocked(conn, &[transaction_id])?;
+
+    if !splits.is_empty() {
+        let mut total = 0i64;
+        for split in splits {
+            split.amount.require_currency(&currency)?;
+            if split.amount.minor <= 0 {
+                return Err("Split amounts must be positive".into());
+            }
+            total += split.amount.minor;
+        }
+        if total != amount {
+            return Err(format!(
+                "Splits total {} but the transaction amount is {}",
+                Money::new(total, currency.clone()).to_decimal_string(),
+                Money::new(amount, currency).to_decimal_string()
+            ));
+        }
+    }
+
+    conn.execute("DELETE FROM transaction_splits WHERE transaction_id = ?1", [transaction_id])
+        .map_err(|e| e.to_string())?;
+    for split in splits {
+        conn.execute(
+            "INSERT INTO transaction_splits (transaction_id, category_id, amount, memo)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![transaction_id, split.category_id, split.amount.minor, split.memo],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    load_splits(conn, transaction_id)
+}
+
 #[tauri::command]
 pub fn list_transactions(
     state: State<DbState>,
@@ -31,6 +151,9 @@ pub fn list_transactions(
     date_from: Option<String>,
     date_to: Option<String>,
     search: Option<String>,
+    payee_id: Option<i64>,
+    tags: Option<Vec<i64>>,
+    tag_match: Option<String>,
 ) -> Result<Vec<Transaction>, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
@@ -46,7 +169,11 @@ pub fn list_transactions(
     }
     if let Some(cid) = category_id {
         param_idx += 1;
-        conditions.push(format!("t.category_id = ?{}", param_idx));
+        conditions.push(format!(
+            "(t.category_id = ?{0} OR EXISTS (SELECT 1 FROM transaction_splits s
+              WHERE s.transaction_id = t.id AND s.category_id = ?{0}))",
+            param_idx
+        ));
         params.push(Box::new(cid));
     }
     if let Some(ref tt) = tx_type {
@@ -67,26 +194,54 @@ pub fn list_transactions(
     if let Some(ref s) = search {
         let pat = format!("%{}%", s);
         conditions.push(format!(
-            "(t.notes LIKE ?{} OR c.name LIKE ?{} OR a.name LIKE ?{})",
+            "(t.notes LIKE ?{} OR c.name LIKE ?{} OR a.name LIKE ?{} OR p.name LIKE ?{})",
             param_idx + 1,
             param_idx + 2,
-            param_idx + 3
+            param_idx + 3,
+            param_idx + 4
         ));
-        param_idx += 3;
+        param_idx += 4;
+        params.push(Box::new(pat.clone()));
         params.push(Box::new(pat.clone()));
         params.push(Box::new(pat.clone()));
         params.push(Box::new(pat));
     }
+    if let Some(pid) = payee_id {
+        param_idx += 1;
+        conditions.push(format!("t.payee_id = ?{}", param_idx));
+        params.push(Box::new(pid));
+    }
+    if let Some(ref tag_ids) = tags.filter(|t| !t.is_empty()) {
+        let placeholders: Vec<String> = tag_ids
+            .iter()
+            .map(|_| {
+                param_idx += 1;
+                format!("?{}", param_idx)
+            })
+            .collect();
+        let matched = format!(
+            "(SELECT COUNT(DISTINCT tt.tag_id) FROM transaction_tags tt
+              WHERE tt.transaction_id = t.id AND tt.tag_id IN ({}))",
+            placeholders.join(", ")
+        );
+        // "all" requires every listed tag; anything else matches any of them.
+        if tag_match.as_deref() == Some("all") {
+            let mut distinct = tag_ids.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            conditions.push(format!("{} = {}", matched, distinct.len()));
+        } else {
+            conditions.push(format!("{} > 0", matched));
+        }
+        for tid in tag_ids {
+            params.push(Box::new(*tid));
+        }
+    }
     let _ = param_idx;
 
     let where_clause = conditions.join(" AND ");
     let sql = format!(
-        "SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type, t.amount,
-         t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date,
-         t.created_at, t.updated_at, c.name, a.name
-         FROM transactions t
-         LEFT JOIN categories c ON t.category_id = c.id
-         LEFT JOIN accounts a ON t.account_id = a.id
+        "{TRANSACTION_SELECT}
          WHERE {}
          ORDER BY t.date DESC, t.id DESC
          LIMIT 500",
@@ -109,37 +264,35 @@ pub fn create_transaction(
     state: State<DbState>,
     account_id: i64,
     category_id: Option<i64>,
+    payee_id: Option<i64>,
     tx_type: String,
-    amount: f64,
+    amount: Money,
     date: String,
     notes: Option<String>,
     is_recurring: bool,
     recurrence_frequency: Option<String>,
     next_due_date: Option<String>,
 ) -> Result<Transaction, String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    let is_recurring_int: i64 = if is_recurring { 1 } else { 0 };
-    conn.execute(
-        "INSERT INTO transactions (account_id, category_id, type, amount, date, notes, is_recurring, recurrence_frequency, next_due_date)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-        rusqlite::params![account_id, category_id, tx_type, amount, date, notes, is_recurring_int, recurrence_frequency, next_due_date],
-    )
-    .map_err(|e| e.to_string())?;
+    with_transaction(&state, |conn| {
+        amount.require_currency(&account_currency(conn, account_id)?)?;
+        let is_recurring_int: i64 = if is_recurring { 1 } else { 0 };
+        conn.execute(
+            "INSERT INTO transactions (account_id, category_id, payee_id, type, amount, date, notes, is_recurring, recurrence_frequency, next_due_date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![account_id, category_id, payee_id, tx_type, amount.minor, date, notes, is_recurring_int, recurrence_frequency, next_due_date],
+        )
+        .map_err(|e| e.to_string())?;
 
-    let id = conn.last_insert_rowid();
-    conn.query_row(
-        "SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type, t.amount,
-         t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date,
-         t.created_at, t.updated_at, c.name, a.name
-         FROM transactions t
-         LEFT JOIN categories c ON t.category_id = c.id
-         LEFT JOIN accounts a ON t.account_id = a.id
-         WHERE t.id = ?1",
-        [id],
-        |row| row_to_transaction(row),
-    )
-    .map_err(|e| e.to_string())
+        let id = conn.last_insert_rowid();
+        // Rules only fill in what the user left empty.
+        apply_rules_to_transaction(conn, id)?;
+        conn.query_row(
+            &format!("{TRANSACTION_SELECT} WHERE t.id = ?1"),
+            [id],
+            |row| row_to_transaction(row),
+        )
+        .map_err(|e| e.to_string())
+    })
 }
 
 #[tauri::command]
@@ -148,8 +301,9 @@ pub fn update_transaction(
     id: i64,
     account_id: i64,
     category_id: Option<i64>,
+    payee_id: Option<i64>,
     tx_type: String,
-    amount: f64,
+    amount: Money,
     date: String,
     notes: Option<String>,
     is_recurring: bool,
@@ -158,23 +312,31 @@ pub fn update_transaction(
 ) -> Result<Transaction, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    require_unlocked(conn, &[id])?;
+    require_no_plan(conn, id)?;
+    require_no_trade(conn, id)?;
+    amount.require_currency(&account_currency(conn, account_id)?)?;
+    let split_total: Option<i64> = conn
+        .query_row(
+            "SELECT SUM(amount) FROM transaction_splits WHERE transaction_id = ?1",
+            [id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if split_total.is_some_and(|total| total != amount.minor) {
+        return Err("This transaction is split; update its splits to match the new amount first".into());
+    }
     let is_recurring_int: i64 = if is_recurring { 1 } else { 0 };
     conn.execute(
-        "UPDATE transactions SET account_id=?1, category_id=?2, type=?3, amount=?4,
-         date=?5, notes=?6, is_recurring=?7, recurrence_frequency=?8, next_due_date=?9,
-         updated_at=datetime('now') WHERE id=?10",
-        rusqlite::params![account_id, category_id, tx_type, amount, date, notes, is_recurring_int, recurrence_frequency, next_due_date, id],
+        "UPDATE transactions SET account_id=?1, category_id=?2, payee_id=?3, type=?4, amount=?5,
+         date=?6, notes=?7, is_recurring=?8, recurrence_frequency=?9, next_due_date=?10,
+         updated_at=datetime('now') WHERE id=?11",
+        rusqlite::params![account_id, category_id, payee_id, tx_type, amount.minor, date, notes, is_recurring_int, recurrence_frequency, next_due_date, id],
     )
     .map_err(|e| e.to_string())?;
 
     conn.query_row(
-        "SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type, t.amount,
-         t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date,
-         t.created_at, t.updated_at, c.name, a.name
-         FROM transactions t
-         LEFT JOIN categories c ON t.category_id = c.id
-         LEFT JOIN accounts a ON t.account_id = a.id
-         WHERE t.id = ?1",
+        &format!("{TRANSACTION_SELECT} WHERE t.id = ?1"),
         [id],
         |row| row_to_transaction(row),
     )
@@ -183,54 +345,52 @@ pub fn update_transaction(
 
 #[tauri::command]
 pub fn delete_transaction(state: State<DbState>, id: i64) -> Result<(), String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-
-    // Check if linked to a transfer
-    let transfer_id: Option<i64> = conn
-        .query_row(
-            "SELECT transfer_id FROM transactions WHERE id = ?1",
-            [id],
-            |r| r.get(0),
-        )
-        .map_err(|e| e.to_string())?;
-
-    if let Some(tid) = transfer_id {
-        // Delete all transaction legs of this transfer
-        conn.execute(
-            "DELETE FROM transactions WHERE transfer_id = ?1",
-            [tid],
-        )
-        .map_err(|e| e.to_string())?;
-        conn.execute("DELETE FROM transfers WHERE id = ?1", [tid])
-            .map_err(|e| e.to_string())?;
-    } else {
-        conn.execute("DELETE FROM transactions WHERE id = ?1", [id])
+    with_transaction(&state, |conn| {
+        // Check if linked to a transfer or an investment trade
+        let (transfer_id, trade_id): (Option<i64>, Option<i64>) = conn
+            .query_row(
+                "SELECT transfer_id, trade_id FROM transactions WHERE id = ?1",
+                [id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
             .map_err(|e| e.to_string())?;
-    }
-    Ok(())
+
+        if let Some(tid) = transfer_id {
+            // Delete the whole transfer: both legs, its fee and their receipts
+            remove_transfer(conn, tid)?;
+        } else if let Some(trade_id) = trade_id {
+            // A trade's cash only goes away with the trade itself
+            remove_trade(conn, trade_id)?;
+        } else {
+            require_unlocked(conn, &[id])?;
+            require_no_plan(conn, id)?;
+            conn.execute("DELETE FROM attachments WHERE transaction_id = ?1", [id])
+                .map_err(|e| e.to_string())?;
+            conn.execute("DELETE FROM transactions WHERE id = ?1", [id])
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    })
 }
 
 #[tauri::command]
 pub fn process_recurring_transactions(state: State<DbState>) -> Result<i32, String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    with_transaction(&state, |conn| generate_due_recurrences(conn))
+}
 
+/// Materializes every recurrence due up to today and advances `next_due_date`.
+/// Runs inside a transaction so a failure never leaves half a series behind.
+pub(crate) fn generate_due_recurrences(conn: &Connection) -> Result<i32, String> {
     let today: String = conn
         .query_row("SELECT DATE('now')", [], |row| row.get(0))
         .map_err(|e| e.to_string())?;
 
     let due: Vec<Transaction> = {
         let mut stmt = conn
-            .prepare(
-                "SELECT t.id, t.account_id, t.category_id, t.transfer_id, t.type, t.amount,
-                 t.date, t.notes, t.is_recurring, t.recurrence_frequency, t.next_due_date,
-                 t.created_at, t.updated_at, c.name, a.name
-                 FROM transactions t
-                 LEFT JOIN categories c ON t.category_id = c.id
-                 LEFT JOIN accounts a ON t.account_id = a.id
-                 WHERE t.is_recurring = 1 AND t.next_due_date IS NOT NULL AND t.next_due_date <= ?1",
-            )
+            .prepare(&format!(
+                "{TRANSACTION_SELECT}
+                 WHERE t.is_recurring = 1 AND t.next_due_date IS NOT NULL AND t.next_due_date <= ?1"
+            ))
             .map_err(|e| e.to_string())?;
         let x: Vec<Transaction> = stmt
             .query_map([today.as_str()], |row| row_to_transaction(row))
@@ -247,9 +407,22 @@ pub fn process_recurring_transactions(state: State<DbState>) -> Result<i32, Stri
 
         while next_date <= today {
             conn.execute(
-                "INSERT INTO transactions (account_id, category_id, type, amount, date, notes, is_recurring)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0)",
-                rusqlite::params![t.account_id, t.category_id, t.tx_type, t.amount, next_date, t.notes],
+                "INSERT INTO transactions (account_id, category_id, payee_id, type, amount, date, notes, is_recurring)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0)",
+                rusqlite::params![t.account_id, t.category_id, t.payee_id, t.tx_type, t.amount.minor, next_date, t.notes],
+            )
+            .map_err(|e| e.to_string())?;
+            let occurrence_id = conn.last_insert_rowid();
+            conn.execute(
+                "INSERT INTO transaction_splits (transaction_id, category_id, amount, memo)
+                 SELECT ?1, category_id, amount, memo FROM transaction_splits WHERE transaction_id = ?2",
+                [occurrence_id, t.id],
+            )
+            .map_err(|e| e.to_string())?;
+            conn.execute(
+                "INSERT INTO transaction_tags (transaction_id, tag_id)
+                 SELECT ?1, tag_id FROM transaction_tags WHERE transaction_id = ?2",
+                [occurrence_id, t.id],
             )
             .map_err(|e| e.to_string())?;
 
@@ -279,3 +452,128 @@ pub fn process_recurring_transactions(state: State<DbState>) -> Result<i32, Stri
 
     Ok(count)
 }
+
+#[tauri::command]
+pub fn list_transaction_splits(
+    state: State<DbState>,
+    transaction_id: i64,
+) -> Result<Vec<TransactionSplit>, String> {
+    let guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
+    load_splits(conn, transaction_id)
+}
+
+#[tauri::command]
+pub fn create_transaction_splits(
+    state: State<DbState>,
+    transaction_id: i64,
+    splits: Vec<TransactionSplitInput>,
+) -> Result<Vec<TransactionSplit>, String> {
+    if splits.is_empty() {
+        return Err("At least one split line is required".into());
+    }
+    with_transaction(&state, |conn| {
+        if !load_splits(conn, transaction_id)?.is_empty() {
+            return Err("Transaction is already split; update the existing splits instead".into());
+        }
+        replace_splits(conn, transaction_id, &splits)
+    })
+}
+
+/// Replaces all split lines. Passing an empty list turns the transaction back
+/// into a single-category entry.
+#[tauri::command]
+pub fn update_transaction_splits(
+    state: State<DbState>,
+    transaction_id: i64,
+    splits: Vec<TransactionSplitInput>,
+) -> Result<Vec<TransactionSplit>, String> {
+    with_transaction(&state, |conn| replace_splits(conn, transaction_id, &splits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{open_test_db, run_in_transaction};
+
+    #[test]
+    fn recurring_generation_rolls_back_on_failure() {
+        let mut conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO transactions (account_id, type, amount, date, is_recurring, recurrence_frequency, next_due_date)
+             VALUES (1, 'expense', 50000, '2020-01-01', 1, 'monthly', '2020-02-01');
+             CREATE TRIGGER fail_second_occurrence BEFORE INSERT ON transactions
+             WHEN NEW.date = '2020-03-01'
+             BEGIN SELECT RAISE(ABORT, 'injected failure'); END;",
+        )
+        .unwrap();
+
+        let result = run_in_transaction(&mut conn, |tx| generate_due_recurrences(tx));
+        assert!(result.is_err());
+
+        let (rows, next_due_unchanged): (i64, bool) = conn
+            .query_row(
+                "SELECT COUNT(*), MAX(next_due_date = '2020-02-01') FROM transactions",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert!(next_due_unchanged);
+    }
+
+    #[test]
+    fn recurring_occurrences_keep_tags() {
+        let mut conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO tags (name) VALUES ('work'), ('reimbursable');
+             INSERT INTO transactions (account_id, type, amount, date, is_recurring, recurrence_frequency, next_due_date)
+             VALUES (1, 'expense', 50000, '2020-01-01', 1, 'monthly', '2020-02-01');
+             INSERT INTO transaction_tags (transaction_id, tag_id) VALUES (1, 1), (1, 2);",
+        )
+        .unwrap();
+        run_in_transaction(&mut conn, |tx| generate_due_recurrences(tx)).unwrap();
+
+        let occurrence = conn
+            .query_row(
+                &format!("{TRANSACTION_SELECT} WHERE t.date = '2020-02-01'"),
+                [],
+                |row| row_to_transaction(row),
+            )
+            .unwrap();
+        let mut tag_ids = occurrence.tag_ids;
+        tag_ids.sort_unstable();
+        assert_eq!(tag_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn splits_must_match_parent_and_replace_category_lines() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO categories (name, direction) VALUES ('Groceries', 'expense'), ('Health', 'expense');
+             INSERT INTO transactions (account_id, category_id, type, amount, date)
+             VALUES (1, 1, 'expense', 100000, '2025-03-01');",
+        )
+        .unwrap();
+        let line = |category_id, minor| TransactionSplitInput {
+            category_id: Some(category_id),
+            amount: Money::new(minor, "PHP"),
+            memo: None,
+        };
+
+        assert!(replace_splits(&conn, 1, &[line(1, 60000), line(2, 30000)]).is_err());
+        replace_splits(&conn, 1, &[line(1, 70000), line(2, 30000)]).unwrap();
+
+        let health: i64 = conn
+            .query_row("SELECT SUM(amount) FROM category_lines WHERE category_id = 2", [], |r| r.get(0))
+            .unwrap();
+        let lines: i64 = conn
+            .query_row("SELECT COUNT(*) FROM category_lines", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(health, 30000);
+        assert_eq!(lines, 2);
+    }
+}
@@ -1,13 +1,39 @@
+use rusqlite::{Connection, OptionalExtension};
 use tauri::State;
-use crate::db::DbState;
-use crate::models::Transfer;
+use crate::db::{with_transaction, DbState};
+use crate::commands::accounts::account_currency;
+use crate::commands::reconciliation::require_unlocked;
+use crate::logic::fx::implied_rate;
+use crate::models::{Money, Transfer, TransferFee};
+
+/// `amount` is in the source account's currency and `to_amount` in the
+/// destination's. The fee, if any, is read from its expense transaction.
+const TRANSFER_SELECT: &str =
+    "SELECT tr.id, tr.from_account_id, tr.to_account_id, tr.amount, tr.date, tr.notes,
+     tr.transfer_type, tr.created_at, a.currency, tr.to_amount, b.currency, f.amount, f.category_id
+     FROM transfers tr
+     JOIN accounts a ON a.id = tr.from_account_id
+     JOIN accounts b ON b.id = tr.to_account_id
+     LEFT JOIN transactions f ON f.id = tr.fee_transaction_id";
 
 fn row_to_transfer(row: &rusqlite::Row) -> rusqlite::Result<Transfer> {
+    let amount = Money::new(row.get(3)?, row.get::<_, String>(8)?);
+    let to_amount = Money::new(row.get(9)?, row.get::<_, String>(10)?);
+    let fee = match row.get::<_, Option<i64>>(11)? {
+        Some(minor) => Some(TransferFee {
+            amount: Money::new(minor, amount.currency.clone()),
+            category_id: row.get(12)?,
+        }),
+        None => None,
+    };
     Ok(Transfer {
         id: row.get(0)?,
         from_account_id: row.get(1)?,
         to_account_id: row.get(2)?,
-        amount: row.get(3)?,
+        rate: implied_rate(&amount, &to_amount),
+        amount,
+        to_amount,
+        fee,
         date: row.get(4)?,
         notes: row.get(5)?,
         transfer_type: row.get(6)?,
@@ -15,24 +41,134 @@ fn row_to_transfer(row: &rusqlite::Row) -> rusqlite::Result<Transfer> {
     })
 }
 
-#[tauri::command]
-pub fn create_transfer(
-    state: State<DbState>,
+/// Checks the sent and received amounts against the two accounts and
+/// returns the received amount. Without `to_amount` both accounts must share
+/// a currency and the same amount arrives.
+fn received_amount(
+    conn: &Connection,
     from_account_id: i64,
     to_account_id: i64,
-    amount: f64,
-    date: String,
-    notes: Option<String>,
-    transfer_type: Option<String>,
+    amount: &Money,
+    to_amount: Option<&Money>,
+) -> Result<Money, String> {
+    let from_currency = account_currency(conn, from_account_id)?;
+    let to_currency = account_currency(conn, to_account_id)?;
+    amount.require_currency(&from_currency)?;
+    if amount.minor <= 0 {
+        return Err("Transfer amounts must be positive".into());
+    }
+    let to_amount = match to_amount {
+        Some(to_amount) => to_amount.clone(),
+        None if from_currency == to_currency => amount.clone(),
+        None => {
+            return Err(format!(
+                "Enter the amount received in {} for this {} transfer",
+                to_currency, from_currency
+            ))
+        }
+    };
+    to_amount.require_currency(&to_currency)?;
+    if to_amount.minor <= 0 {
+        return Err("Transfer amounts must be positive".into());
+    }
+    if from_currency == to_currency && to_amount.minor != amount.minor {
+        return Err("A transfer within one currency must receive the amount sent".into());
+    }
+    Ok(to_amount)
+}
+
+/// Ids of the legs, fee expense and loan interest charge of a transfer.
+fn transfer_transaction_ids(conn: &Connection, transfer_id: i64) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM transactions WHERE transfer_id = ?1
+             UNION SELECT fee_transaction_id FROM transfers WHERE id = ?1 AND fee_transaction_id IS NOT NULL
+             UNION SELECT interest_transaction_id FROM transfers WHERE id = ?1 AND interest_transaction_id IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([transfer_id], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(ids)
+}
+
+/// Adds, updates or removes the fee expense of a transfer. A missing or zero
+/// fee removes it.
+fn set_transfer_fee(
+    conn: &Connection,
+    transfer_id: i64,
+    from_account_id: i64,
+    fee: Option<&TransferFee>,
+    date: &str,
+) -> Result<(), String> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT fee_transaction_id FROM transfers WHERE id = ?1",
+            [transfer_id],
+            |r| r.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    match (fee.filter(|f| f.amount.minor != 0), existing) {
+        (Some(fee), existing) => {
+            fee.amount.require_currency(&account_currency(conn, from_account_id)?)?;
+            if fee.amount.minor < 0 {
+                return Err("Fees must be positive".into());
+            }
+            if let Some(fee_id) = existing {
+                conn.execute(
+                    "UPDATE transactions SET amount=?1, category_id=?2, date=?3, updated_at=datetime('now')
+                     WHERE id=?4",
+                    rusqlite::params![fee.amount.minor, fee.category_id, date, fee_id],
+                )
+                .map_err(|e| e.to_string())?;
+            } else {
+                conn.execute(
+                    "INSERT INTO transactions (account_id, category_id, type, amount, date, notes)
+                     VALUES (?1, ?2, 'expense', ?3, ?4, 'Transfer fee')",
+                    rusqlite::params![from_account_id, fee.category_id, fee.amount.minor, date],
+                )
+                .map_err(|e| e.to_string())?;
+                conn.execute(
+                    "UPDATE transfers SET fee_transaction_id = ?1 WHERE id = ?2",
+                    rusqlite::params![conn.last_insert_rowid(), transfer_id],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+        }
+        // The foreign key clears fee_transaction_id.
+        (None, Some(fee_id)) => {
+            conn.execute("DELETE FROM transactions WHERE id = ?1", [fee_id])
+                .map_err(|e| e.to_string())?;
+        }
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+/// Writes the transfer row, both transaction legs and the optional fee
+/// expense. Callers run this inside `with_transaction` so a failing leg never
+/// leaves the others behind.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn insert_transfer(
+    conn: &Connection,
+    from_account_id: i64,
+    to_account_id: i64,
+    amount: &Money,
+    to_amount: Option<&Money>,
+    fee: Option<&TransferFee>,
+    date: &str,
+    notes: Option<&str>,
+    transfer_type: &str,
 ) -> Result<Transfer, String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    let ttype = transfer_type.unwrap_or_else(|| "regular".to_string());
+    let to_amount = received_amount(conn, from_account_id, to_account_id, amount, to_amount)?;
 
     conn.execute(
-        "INSERT INTO transfers (from_account_id, to_account_id, amount, date, notes, transfer_type)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-        rusqlite::params![from_account_id, to_account_id, amount, date, notes, ttype],
+        "INSERT INTO transfers (from_account_id, to_account_id, amount, to_amount, date, notes, transfer_type)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![from_account_id, to_account_id, amount.minor, to_amount.minor, date, notes, transfer_type],
     )
     .map_err(|e| e.to_string())?;
 
@@ -42,7 +178,7 @@ pub fn create_transfer(
     conn.execute(
         "INSERT INTO transactions (account_id, transfer_id, type, amount, date, notes)
          VALUES (?1, ?2, 'expense', ?3, ?4, ?5)",
-        rusqlite::params![from_account_id, transfer_id, amount, date, notes],
+        rusqlite::params![from_account_id, transfer_id, amount.minor, date, notes],
     )
     .map_err(|e| e.to_string())?;
 
@@ -50,70 +186,233 @@ pub fn create_transfer(
     conn.execute(
         "INSERT INTO transactions (account_id, transfer_id, type, amount, date, notes)
          VALUES (?1, ?2, 'income', ?3, ?4, ?5)",
-        rusqlite::params![to_account_id, transfer_id, amount, date, notes],
+        rusqlite::params![to_account_id, transfer_id, to_amount.minor, date, notes],
     )
     .map_err(|e| e.to_string())?;
 
+    set_transfer_fee(conn, transfer_id, from_account_id, fee, date)?;
+
     conn.query_row(
-        "SELECT id, from_account_id, to_account_id, amount, date, notes, transfer_type, created_at
-         FROM transfers WHERE id = ?1",
+        &format!("{TRANSFER_SELECT} WHERE tr.id = ?1"),
         [transfer_id],
         |row| row_to_transfer(row),
     )
     .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub fn delete_transfer(state: State<DbState>, id: i64) -> Result<(), String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    conn.execute("DELETE FROM transactions WHERE transfer_id = ?1", [id])
-        .map_err(|e| e.to_string())?;
+/// Deletes a transfer with its legs, its fee expense, its loan interest
+/// charge and every receipt attached to any of them.
+pub(crate) fn remove_transfer(conn: &Connection, id: i64) -> Result<(), String> {
+    require_unlocked(conn, &transfer_transaction_ids(conn, id)?)?;
+    let (fee_id, interest_id): (Option<i64>, Option<i64>) = conn
+        .query_row(
+            "SELECT fee_transaction_id, interest_transaction_id FROM transfers WHERE id = ?1",
+            [id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .unwrap_or((None, None));
+    conn.execute(
+        "DELETE FROM attachments WHERE transfer_id = ?1
+         OR transaction_id IN (SELECT id FROM transactions WHERE transfer_id = ?1 OR id IS ?2 OR id IS ?3)",
+        rusqlite::params![id, fee_id, interest_id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM transactions WHERE transfer_id = ?1 OR id IS ?2 OR id IS ?3",
+        rusqlite::params![id, fee_id, interest_id],
+    )
+    .map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM transfers WHERE id = ?1", [id])
         .map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// Moves money between two accounts. `to_amount` is required when the
+/// accounts use different currencies; `fee` is charged to the source account.
+#[tauri::command]
+pub fn create_transfer(
+    state: State<DbState>,
+    from_account_id: i64,
+    to_account_id: i64,
+    amount: Money,
+    to_amount: Option<Money>,
+    fee: Option<TransferFee>,
+    date: String,
+    notes: Option<String>,
+    transfer_type: Option<String>,
+) -> Result<Transfer, String> {
+    let ttype = transfer_type.unwrap_or_else(|| "regular".to_string());
+    with_transaction(&state, |conn| {
+        insert_transfer(
+            conn,
+            from_account_id,
+            to_account_id,
+            &amount,
+            to_amount.as_ref(),
+            fee.as_ref(),
+            &date,
+            notes.as_deref(),
+            &ttype,
+        )
+    })
+}
+
+#[tauri::command]
+pub fn delete_transfer(state: State<DbState>, id: i64) -> Result<(), String> {
+    with_transaction(&state, |conn| remove_transfer(conn, id))
+}
+
 #[tauri::command]
 pub fn get_transfer(state: State<DbState>, id: i64) -> Result<Transfer, String> {
     let guard = state.0.lock().map_err(|e| e.to_string())?;
     let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
     conn.query_row(
-        "SELECT id, from_account_id, to_account_id, amount, date, notes, transfer_type, created_at
-         FROM transfers WHERE id = ?1",
+        &format!("{TRANSFER_SELECT} WHERE tr.id = ?1"),
         [id],
         |row| row_to_transfer(row),
     )
     .map_err(|e| e.to_string())
 }
 
+/// Replaces the amounts, date, notes, type and fee of a transfer. Passing no
+/// fee removes an existing one.
 #[tauri::command]
 pub fn update_transfer(
     state: State<DbState>,
     id: i64,
-    amount: f64,
+    amount: Money,
+    to_amount: Option<Money>,
+    fee: Option<TransferFee>,
     date: String,
     notes: Option<String>,
     transfer_type: String,
 ) -> Result<Transfer, String> {
-    let guard = state.0.lock().map_err(|e| e.to_string())?;
-    let conn = guard.as_ref().ok_or_else(|| "Database is locked".to_string())?;
-    conn.execute(
-        "UPDATE transfers SET amount=?1, date=?2, notes=?3, transfer_type=?4 WHERE id=?5",
-        rusqlite::params![amount, date, notes, transfer_type, id],
-    )
-    .map_err(|e| e.to_string())?;
-    // Update both transaction legs (amount, date, notes)
-    conn.execute(
-        "UPDATE transactions SET amount=?1, date=?2, notes=?3, updated_at=datetime('now') WHERE transfer_id=?4",
-        rusqlite::params![amount, date, notes, id],
-    )
-    .map_err(|e| e.to_string())?;
-    conn.query_row(
-        "SELECT id, from_account_id, to_account_id, amount, date, notes, transfer_type, created_at
-         FROM transfers WHERE id = ?1",
-        [id],
-        |row| row_to_transfer(row),
-    )
-    .map_err(|e| e.to_string())
+    with_transaction(&state, |conn| {
+        let (from_account_id, to_account_id): (i64, i64) = conn
+            .query_row(
+                "SELECT from_account_id, to_account_id FROM transfers WHERE id = ?1",
+                [id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .map_err(|e| e.to_string())?;
+        require_unlocked(conn, &transfer_transaction_ids(conn, id)?)?;
+        let to_amount = received_amount(conn, from_account_id, to_account_id, &amount, to_amount.as_ref())?;
+        conn.execute(
+            "UPDATE transfers SET amount=?1, to_amount=?2, date=?3, notes=?4, transfer_type=?5 WHERE id=?6",
+            rusqlite::params![amount.minor, to_amount.minor, date, notes, transfer_type, id],
+        )
+        .map_err(|e| e.to_string())?;
+        // Update both transaction legs (amount, date, notes)
+        conn.execute(
+            "UPDATE transactions SET amount = CASE type WHEN 'expense' THEN ?1 ELSE ?2 END,
+             date=?3, notes=?4, updated_at=datetime('now') WHERE transfer_id=?5",
+            rusqlite::params![amount.minor, to_amount.minor, date, notes, id],
+        )
+        .map_err(|e| e.to_string())?;
+        set_transfer_fee(conn, id, from_account_id, fee.as_ref(), &date)?;
+        conn.query_row(
+            &format!("{TRANSFER_SELECT} WHERE tr.id = ?1"),
+            [id],
+            |row| row_to_transfer(row),
+        )
+        .map_err(|e| e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{open_test_db, run_in_transaction};
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0))
+            .unwrap()
+    }
+
+    fn setup() -> Connection {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type) VALUES ('Wallet', 'cash');
+             INSERT INTO accounts (name, type) VALUES ('Bank', 'debit');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn create_transfer_writes_both_legs() {
+        let mut conn = setup();
+        let amount = Money::new(15000, "PHP");
+        run_in_transaction(&mut conn, |tx| {
+            insert_transfer(tx, 1, 2, &amount, None, None, "2025-01-15", None, "regular")
+        })
+        .unwrap();
+        assert_eq!(count(&conn, "transfers"), 1);
+        assert_eq!(count(&conn, "transactions"), 2);
+    }
+
+    #[test]
+    fn create_transfer_rolls_back_when_a_leg_fails() {
+        let mut conn = setup();
+        // Fail the income leg, after the transfer row and expense leg are written.
+        conn.execute_batch(
+            "CREATE TRIGGER fail_income_leg BEFORE INSERT ON transactions
+             WHEN NEW.type = 'income'
+             BEGIN SELECT RAISE(ABORT, 'injected failure'); END;",
+        )
+        .unwrap();
+
+        let amount = Money::new(15000, "PHP");
+        let result = run_in_transaction(&mut conn, |tx| {
+            insert_transfer(tx, 1, 2, &amount, None, None, "2025-01-15", None, "regular")
+        });
+        assert!(result.is_err());
+        assert_eq!(count(&conn, "transfers"), 0);
+        assert_eq!(count(&conn, "transactions"), 0);
+    }
+
+    #[test]
+    fn cross_currency_transfer_records_both_amounts_and_fee() {
+        let mut conn = setup();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, currency) VALUES ('USD Savings', 'savings', 'USD');
+             INSERT INTO categories (name, direction) VALUES ('Bank Fees', 'expense');",
+        )
+        .unwrap();
+        let sent = Money::new(10000, "USD");
+        let received = Money::new(575000, "PHP");
+        let fee = TransferFee { amount: Money::new(300, "USD"), category_id: Some(1) };
+
+        // The received amount is required across currencies.
+        assert!(run_in_transaction(&mut conn, |tx| {
+            insert_transfer(tx, 3, 2, &sent, None, None, "2025-01-15", None, "regular")
+        })
+        .is_err());
+
+        let transfer = run_in_transaction(&mut conn, |tx| {
+            insert_transfer(tx, 3, 2, &sent, Some(&received), Some(&fee), "2025-01-15", None, "regular")
+        })
+        .unwrap();
+        assert_eq!(transfer.to_amount, received);
+        assert_eq!(transfer.rate, 57.5);
+        assert_eq!(transfer.fee.as_ref().unwrap().amount.minor, 300);
+        let legs: Vec<(i64, i64, Option<i64>)> = conn
+            .prepare("SELECT account_id, amount, transfer_id FROM transactions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        // The fee is an ordinary expense so it counts as spending.
+        assert_eq!(legs, vec![(3, 10000, Some(1)), (2, 575000, Some(1)), (3, 300, None)]);
+
+        run_in_transaction(&mut conn, |tx| set_transfer_fee(tx, 1, 3, None, "2025-01-15")).unwrap();
+        assert_eq!(count(&conn, "transactions"), 2);
+
+        run_in_transaction(&mut conn, |tx| remove_transfer(tx, 1)).unwrap();
+        assert_eq!(count(&conn, "transfers"), 0);
+        assert_eq!(count(&conn, "transactions"), 0);
+    }
 }
@@ -0,0 +1,361 @@
+use rusqlite::{Connection, Result};
+use std::collections::BTreeSet;
+
+/// A single schema step. Versions are 1-based and must stay contiguous; the
+/// applied version is tracked in `PRAGMA user_version`.
+#[derive(Clone, Copy)]
+struct Migration {
+    version: i64,
+    name: &'static str,
+    sql: &'static str,
+}
+
+/// Ordered list of embedded migrations. Never edit a migration that has
+/// shipped — add a new one instead.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("../../sql/migrations/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "money_minor_units",
+        sql: include_str!("../../sql/migrations/0002_money_minor_units.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "transaction_splits",
+        sql: include_str!("../../sql/migrations/0003_transaction_splits.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "tags",
+        sql: include_str!("../../sql/migrations/0004_tags.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "payees",
+        sql: include_str!("../../sql/migrations/0005_payees.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "rules",
+        sql: include_str!("../../sql/migrations/0006_rules.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "csv_import",
+        sql: include_str!("../../sql/migrations/0007_csv_import.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "ofx_import",
+        sql: include_str!("../../sql/migrations/0008_ofx_import.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "duplicates",
+        sql: include_str!("../../sql/migrations/0009_duplicates.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "attachments",
+        sql: include_str!("../../sql/migrations/0010_attachments.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "exchange_rates",
+        sql: include_str!("../../sql/migrations/0011_exchange_rates.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "transfer_fx",
+        sql: include_str!("../../sql/migrations/0012_transfer_fx.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "account_archived_at",
+        sql: include_str!("../../sql/migrations/0013_account_archived_at.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "reconciliation",
+        sql: include_str!("../../sql/migrations/0014_reconciliation.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "installments",
+        sql: include_str!("../../sql/migrations/0015_installments.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "loans",
+        sql: include_str!("../../sql/migrations/0016_loans.sql"),
+    },
+    Migration {
+        version: 17,
+        name: "investments",
+        sql: include_str!("../../sql/migrations/0017_investments.sql"),
+    },
+    Migration {
+        version: 18,
+        name: "savings_plans",
+        sql: include_str!("../../sql/migrations/0018_savings_plans.sql"),
+    },
+    Migration {
+        version: 19,
+        name: "interest",
+        sql: include_str!("../../sql/migrations/0019_interest.sql"),
+    },
+    Migration {
+        version: 20,
+        name: "budgets",
+        sql: include_str!("../../sql/migrations/0020_budgets.sql"),
+    },
+    Migration {
+        version: 21,
+        name: "envelopes",
+        sql: include_str!("../../sql/migrations/0021_envelopes.sql"),
+    },
+    Migration {
+        version: 22,
+        name: "category_tree",
+        sql: include_str!("../../sql/migrations/0022_category_tree.sql"),
+    },
+    Migration {
+        version: 23,
+        name: "budget_currency",
+        sql: include_str!("../../sql/migrations/0023_budget_currency.sql"),
+    },
+    Migration {
+        version: 24,
+        name: "envelope_currency",
+        sql: include_str!("../../sql/migrations/0024_envelope_currency.sql"),
+    },
+];
+
+pub fn current_version(conn: &Connection) -> Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |r| r.get(0))
+}
+
+/// Brings the database up to the last embedded migration.
+/// All pending steps run inside one transaction, so a failure leaves the
+/// database at its previous version. Foreign key enforcement is disabled while
+/// migrating (it cannot be toggled inside a transaction) so steps may rebuild
+/// tables, and the result is checked with `foreign_key_check` before commit.
+/// Only violations the migrations introduce fail the upgrade; orphans the
+/// database already had are left for the user to clean up.
+pub fn run_migrations(conn: &mut Connection) -> Result<()> {
+    migrate(conn, MIGRATIONS)
+}
+
+fn migrate(conn: &mut Connection, migrations: &[Migration]) -> Result<()> {
+    let current = current_version(conn)?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+
+    if current > latest {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_MISMATCH),
+            Some(format!(
+                "Database schema version {current} is newer than this version of Accrue supports ({latest}). Please update the app."
+            )),
+        ));
+    }
+
+    if current < latest {
+        conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+        let result = apply_pending(conn, migrations, current);
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        result?;
+    } else {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    }
+    Ok(())
+}
+
+/// Rows that reference a missing parent, as (table, rowid, parent table).
+fn foreign_key_violations(conn: &Connection) -> Result<BTreeSet<(String, i64, String)>> {
+    let mut stmt = conn.prepare("SELECT \"table\", rowid, parent FROM pragma_foreign_key_check")?;
+    let violations = stmt
+        .query_map([], |r| Ok((r.get(0)?, r.get::<_, Option<i64>>(1)?.unwrap_or(0), r.get(2)?)))?
+        .collect::<Result<_>>()?;
+    Ok(violations)
+}
+
+fn apply_pending(conn: &mut Connection, migrations: &[Migration], current: i64) -> Result<()> {
+    let tx = conn.transaction()?;
+    let existing = foreign_key_violations(&tx)?;
+    for m in migrations.iter().filter(|m| m.version > current) {
+        tx.execute_batch(m.sql).map_err(|e| {
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+                Some(format!("Migration {} ({}) failed: {e}", m.version, m.name)),
+            )
+        })?;
+        tx.pragma_update(None, "user_version", m.version)?;
+    }
+
+    let introduced: Vec<String> = foreign_key_violations(&tx)?
+        .difference(&existing)
+        .map(|(table, rowid, parent)| format!("{table} row {rowid} (missing {parent})"))
+        .collect();
+    if !introduced.is_empty() {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+            Some(format!(
+                "Migration left {} foreign key violation(s): {}",
+                introduced.len(),
+                introduced.join(", ")
+            )),
+        ));
+    }
+
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEPS: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "owners",
+            sql: "CREATE TABLE owners (id INTEGER PRIMARY KEY);",
+        },
+        Migration {
+            version: 2,
+            name: "pets",
+            sql: "CREATE TABLE pets (id INTEGER PRIMARY KEY, owner_id INTEGER REFERENCES owners(id));",
+        },
+    ];
+
+    #[test]
+    fn applies_pending_steps_and_bumps_the_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn, &STEPS[..1]).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        conn.execute("INSERT INTO owners (id) VALUES (1)", []).unwrap();
+
+        migrate(&mut conn, STEPS).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 2);
+        let owners: i64 = conn.query_row("SELECT COUNT(*) FROM owners", [], |r| r.get(0)).unwrap();
+        assert_eq!(owners, 1);
+        let foreign_keys: i64 = conn.query_row("PRAGMA foreign_keys", [], |r| r.get(0)).unwrap();
+        assert_eq!(foreign_keys, 1);
+    }
+
+    #[test]
+    fn refuses_a_database_newer_than_the_binary() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 3).unwrap();
+        assert!(migrate(&mut conn, STEPS).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 3);
+    }
+
+    #[test]
+    fn a_failing_step_rolls_back_every_step() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let broken = [
+            Migration { version: 1, name: "owners", sql: STEPS[0].sql },
+            Migration { version: 2, name: "broken", sql: "CREATE TABLE pets (;" },
+        ];
+        let error = migrate(&mut conn, &broken).unwrap_err().to_string();
+        assert!(error.contains("Migration 2 (broken)"), "{error}");
+        assert_eq!(current_version(&conn).unwrap(), 0);
+        let tables: i64 = conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |r| r.get(0)).unwrap();
+        assert_eq!(tables, 0);
+    }
+
+    #[test]
+    fn only_new_foreign_key_violations_roll_back() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn, STEPS).unwrap();
+        // An orphan left by a build that never enforced foreign keys.
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO pets (id, owner_id) VALUES (1, 42);",
+        )
+        .unwrap();
+
+        let tidy = [
+            STEPS[0],
+            STEPS[1],
+            Migration { version: 3, name: "names", sql: "ALTER TABLE owners ADD COLUMN name TEXT;" },
+        ];
+        migrate(&mut conn, &tidy).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 3);
+
+        let orphaning = [
+            tidy[0],
+            tidy[1],
+            tidy[2],
+            Migration {
+                version: 4,
+                name: "orphans",
+                sql: "INSERT INTO pets (id, owner_id) VALUES (2, 7);",
+            },
+        ];
+        let error = migrate(&mut conn, &orphaning).unwrap_err().to_string();
+        assert!(error.contains("pets row 2"), "{error}");
+        assert_eq!(current_version(&conn).unwrap(), 3);
+        let pets: i64 = conn.query_row("SELECT COUNT(*) FROM pets", [], |r| r.get(0)).unwrap();
+        assert_eq!(pets, 1);
+    }
+
+    /// A database as the app created it before migrations were tracked.
+    fn baseline_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn
+    }
+
+    #[test]
+    fn converts_baseline_money_to_minor_units() {
+        let mut conn = baseline_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, currency, opening_balance) VALUES ('Wallet', 'cash', 'PHP', 1500.25);
+             INSERT INTO accounts (name, type, currency, opening_balance) VALUES ('Yen', 'cash', 'JPY', 1200);
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 99.99, '2025-01-02');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'expense', 500, '2025-01-02');",
+        )
+        .unwrap();
+
+        migrate(&mut conn, &MIGRATIONS[..2]).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 2);
+        let opening: i64 = conn
+            .query_row("SELECT opening_balance FROM accounts WHERE id = 1", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(opening, 150_025);
+        let amounts: Vec<i64> = conn
+            .prepare("SELECT amount FROM transactions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(amounts, vec![9_999, 500]);
+    }
+
+    #[test]
+    fn a_failed_upgrade_leaves_no_payees_table() {
+        let mut conn = baseline_db();
+        let mut steps = MIGRATIONS[..5].to_vec();
+        steps.push(Migration { version: 6, name: "broken", sql: "CREATE TABLE rules (;" });
+
+        assert!(migrate(&mut conn, &steps).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 0);
+        let payees: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'payees'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(payees, 0);
+
+        steps.pop();
+        migrate(&mut conn, &steps).unwrap();
+        let payee_id: Option<i64> = conn
+            .query_row("SELECT 1 FROM pragma_table_info('transactions') WHERE name = 'payee_id'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(payee_id, Some(1));
+    }
+}
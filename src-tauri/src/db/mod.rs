@@ -1,9 +1,34 @@
+pub mod migrations;
+
 use rusqlite::{Connection, Result};
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
 
 pub struct DbState(pub Mutex<Option<Connection>>);
 
+/// Runs `f` inside a single SQLite transaction on the unlocked database.
+/// Every command that issues more than one write should go through this so a
+/// failure midway leaves nothing behind.
+pub fn with_transaction<T>(
+    state: &DbState,
+    f: impl FnOnce(&rusqlite::Transaction) -> std::result::Result<T, String>,
+) -> std::result::Result<T, String> {
+    let mut guard = state.0.lock().map_err(|e| e.to_string())?;
+    let conn = guard.as_mut().ok_or_else(|| "Database is locked".to_string())?;
+    run_in_transaction(conn, f)
+}
+
+/// Commits when `f` succeeds; otherwise the transaction is rolled back on drop.
+pub fn run_in_transaction<T>(
+    conn: &mut Connection,
+    f: impl FnOnce(&rusqlite::Transaction) -> std::result::Result<T, String>,
+) -> std::result::Result<T, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let value = f(&tx)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(value)
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
 pub struct AuthSettings {
     pub password_hash: String,
@@ -47,6 +72,7 @@ pub fn get_db_path(app: &AppHandle) -> std::path::PathBuf {
 
 /// Opens (or creates) the encrypted SQLite database with the given 32-byte key.
 /// The PRAGMA key must be set as the very first operation on the connection.
+/// Pending schema migrations are applied before the connection is returned.
 pub fn open_encrypted_db(app: &AppHandle, key_bytes: &[u8]) -> Result<Connection> {
     let data_dir = app
         .path()
@@ -54,10 +80,10 @@ pub fn open_encrypted_db(app: &AppHandle, key_bytes: &[u8]) -> Result<Connection
         .expect("failed to get app data dir");
     std::fs::create_dir_all(&data_dir).expect("failed to create app data dir");
     let db_path = data_dir.join("accrue.sqlite");
-    let conn = Connection::open(&db_path)?;
+    let mut conn = Connection::open(&db_path)?;
     let key_hex = hex::encode(key_bytes);
     conn.execute_batch(&format!("PRAGMA key = \"x'{key_hex}'\";"))?;
-    run_schema(&conn)?;
+    migrations::run_migrations(&mut conn)?;
     seed_categories(&conn)?;
     Ok(conn)
 }
@@ -99,92 +125,80 @@ pub fn migrate_plaintext_to_encrypted(app: &AppHandle, key_bytes: &[u8]) -> Resu
     Ok(())
 }
 
-fn run_schema(conn: &Connection) -> Result<()> {
-    conn.execute_batch(
-        "PRAGMA foreign_keys = ON;
-
-        CREATE TABLE IF NOT EXISTS accounts (
-          id INTEGER PRIMARY KEY AUTOINCREMENT,
-          name TEXT NOT NULL,
-          type TEXT NOT NULL CHECK (type IN ('cash','debit','credit','savings','investment')),
-          subtype TEXT,
-          currency TEXT NOT NULL DEFAULT 'PHP',
-          opening_balance REAL NOT NULL DEFAULT 0,
-          credit_limit REAL,
-          billing_cycle_day INTEGER,
-          payment_due_day INTEGER,
-          is_active INTEGER NOT NULL DEFAULT 1,
-          created_at TEXT NOT NULL DEFAULT (datetime('now')),
-          updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-        );
-
-        CREATE TABLE IF NOT EXISTS categories (
-          id INTEGER PRIMARY KEY AUTOINCREMENT,
-          name TEXT NOT NULL,
-          direction TEXT NOT NULL CHECK (direction IN ('income','expense','both')),
-          icon TEXT,
-          is_archived INTEGER NOT NULL DEFAULT 0,
-          created_at TEXT NOT NULL DEFAULT (datetime('now'))
-        );
-
-        CREATE TABLE IF NOT EXISTS transfers (
-          id INTEGER PRIMARY KEY AUTOINCREMENT,
-          from_account_id INTEGER NOT NULL REFERENCES accounts(id),
-          to_account_id INTEGER NOT NULL REFERENCES accounts(id),
-          amount REAL NOT NULL,
-          date TEXT NOT NULL,
-          notes TEXT,
-          transfer_type TEXT NOT NULL DEFAULT 'regular' CHECK (transfer_type IN ('regular','credit_payment')),
-          created_at TEXT NOT NULL DEFAULT (datetime('now'))
-        );
-
-        CREATE TABLE IF NOT EXISTS transactions (
-          id INTEGER PRIMARY KEY AUTOINCREMENT,
-          account_id INTEGER NOT NULL REFERENCES accounts(id),
-          category_id INTEGER REFERENCES categories(id),
-          transfer_id INTEGER REFERENCES transfers(id),
-          type TEXT NOT NULL CHECK (type IN ('income','expense')),
-          amount REAL NOT NULL,
-          date TEXT NOT NULL,
-          notes TEXT,
-          is_recurring INTEGER NOT NULL DEFAULT 0,
-          recurrence_frequency TEXT CHECK (recurrence_frequency IN ('daily','weekly','monthly','yearly')),
-          next_due_date TEXT,
-          created_at TEXT NOT NULL DEFAULT (datetime('now')),
-          updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-        );",
-    )?;
-    Ok(())
-}
+/// Name, direction and icon of a default category, with the name and icon of
+/// each of its subcategories.
+type DefaultCategory = (&'static str, &'static str, &'static str, &'static [(&'static str, &'static str)]);
 
-fn seed_categories(conn: &Connection) -> Result<()> {
+pub(crate) fn seed_categories(conn: &Connection) -> Result<()> {
     let count: i64 =
         conn.query_row("SELECT COUNT(*) FROM categories", [], |r| r.get(0))?;
     if count > 0 {
         return Ok(());
     }
 
-    let defaults = vec![
-        ("Salary", "income", "💼"),
-        ("Freelance", "income", "💻"),
-        ("Investments", "income", "📈"),
-        ("Other Income", "income", "💰"),
-        ("Food & Dining", "expense", "🍽️"),
-        ("Transportation", "expense", "🚗"),
-        ("Shopping", "expense", "🛍️"),
-        ("Utilities", "expense", "💡"),
-        ("Entertainment", "expense", "🎬"),
-        ("Health", "expense", "🏥"),
-        ("Education", "expense", "📚"),
-        ("Housing", "expense", "🏠"),
-        ("Other Expense", "expense", "💸"),
+    let defaults: &[DefaultCategory] = &[
+        ("Salary", "income", "💼", &[]),
+        ("Freelance", "income", "💻", &[]),
+        ("Investments", "income", "📈", &[("Dividends", "💵"), ("Interest", "🏦")]),
+        ("Other Income", "income", "💰", &[]),
+        ("Food & Dining", "expense", "🍽️", &[("Groceries", "🛒"), ("Restaurants", "🍜"), ("Coffee", "☕")]),
+        ("Transportation", "expense", "🚗", &[("Fuel", "⛽"), ("Public Transit", "🚌"), ("Ride Hailing", "🚕"), ("Parking & Tolls", "🅿️")]),
+        ("Shopping", "expense", "🛍️", &[("Clothing", "👕"), ("Electronics", "📱"), ("Household", "🧺")]),
+        ("Utilities", "expense", "💡", &[("Electricity", "🔌"), ("Water", "🚰"), ("Internet", "🌐"), ("Mobile", "📶")]),
+        ("Entertainment", "expense", "🎬", &[("Streaming", "📺"), ("Events", "🎟️")]),
+        ("Health", "expense", "🏥", &[("Medicine", "💊"), ("Consultations", "🩺")]),
+        ("Education", "expense", "📚", &[("Tuition", "🎓"), ("Books & Supplies", "📖")]),
+        ("Housing", "expense", "🏠", &[("Rent", "🔑"), ("Repairs", "🔧")]),
+        ("Other Expense", "expense", "💸", &[]),
     ];
 
-    for (name, direction, icon) in defaults {
+    for &(name, direction, icon, children) in defaults {
         conn.execute(
             "INSERT INTO categories (name, direction, icon) VALUES (?1, ?2, ?3)",
             (name, direction, icon),
         )?;
+        let parent_id = conn.last_insert_rowid();
+        for &(child, child_icon) in children {
+            conn.execute(
+                "INSERT INTO categories (name, direction, icon, parent_id) VALUES (?1, ?2, ?3, ?4)",
+                (child, direction, child_icon, parent_id),
+            )?;
+        }
     }
     Ok(())
 }
+
+/// In-memory database with every migration applied, for unit tests.
+#[cfg(test)]
+pub(crate) fn open_test_db() -> Connection {
+    let mut conn = Connection::open_in_memory().expect("open in-memory db");
+    migrations::run_migrations(&mut conn).expect("migrate test db");
+    conn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_transaction_rolls_back_on_error() {
+        let state = DbState(Mutex::new(Some(open_test_db())));
+        let result: std::result::Result<(), String> = with_transaction(&state, |tx| {
+            tx.execute(
+                "INSERT INTO categories (name, direction) VALUES ('Temp', 'expense')",
+                [],
+            )
+            .map_err(|e| e.to_string())?;
+            Err("injected failure".into())
+        });
+        assert!(result.is_err());
+
+        let guard = state.0.lock().unwrap();
+        let count: i64 = guard
+            .as_ref()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM categories", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
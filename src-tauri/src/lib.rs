@@ -36,9 +36,11 @@ pub fn run() {
             commands::auth::reset_all_data_with_wipe,
             // Account commands
             commands::accounts::list_accounts,
+            commands::accounts::get_accounts_as_of,
             commands::accounts::get_account,
             commands::accounts::create_account,
             commands::accounts::update_account,
+            commands::accounts::link_ofx_account,
             commands::accounts::archive_account,
             commands::accounts::restore_account,
             commands::accounts::delete_account,
@@ -48,21 +50,149 @@ pub fn run() {
             commands::transactions::update_transaction,
             commands::transactions::delete_transaction,
             commands::transactions::process_recurring_transactions,
+            commands::transactions::list_transaction_splits,
+            commands::transactions::create_transaction_splits,
+            commands::transactions::update_transaction_splits,
+            // Duplicate commands
+            commands::duplicates::find_duplicates,
+            commands::duplicates::merge_duplicates,
+            commands::duplicates::dismiss_duplicates,
+            // Import commands
+            commands::imports::list_import_profiles,
+            commands::imports::create_import_profile,
+            commands::imports::update_import_profile,
+            commands::imports::delete_import_profile,
+            commands::imports::pick_import_file,
+            commands::imports::preview_csv_import,
+            commands::imports::import_transactions_csv,
+            commands::imports::preview_ofx_import,
+            commands::imports::import_ofx,
+            commands::imports::preview_qif_import,
+            commands::imports::import_qif,
+            commands::imports::list_import_batches,
+            commands::imports::rollback_import_batch,
+            // Payee commands
+            commands::payees::list_payees,
+            commands::payees::create_payee,
+            commands::payees::rename_payee,
+            commands::payees::merge_payees,
+            commands::payees::delete_payee,
+            commands::payees::list_payee_aliases,
+            commands::payees::create_payee_alias,
+            commands::payees::delete_payee_alias,
+            commands::payees::resolve_payee,
+            // Rule commands
+            commands::rules::list_rules,
+            commands::rules::create_rule,
+            commands::rules::update_rule,
+            commands::rules::delete_rule,
+            commands::rules::preview_rule,
+            commands::rules::apply_rules_retroactively,
+            commands::rules::export_rules_json,
+            commands::rules::import_rules_json,
+            // Tag commands
+            commands::tags::list_tags,
+            commands::tags::create_tag,
+            commands::tags::update_tag,
+            commands::tags::delete_tag,
+            commands::tags::set_transaction_tags,
             // Transfer commands
             commands::transfers::create_transfer,
             commands::transfers::delete_transfer,
             commands::transfers::get_transfer,
             commands::transfers::update_transfer,
+            // Credit card commands
+            commands::credit_cards::get_credit_card_summary,
+            commands::credit_cards::list_card_payments_due,
+            // Installment commands
+            commands::installments::list_installment_plans,
+            commands::installments::create_installment_plan,
+            commands::installments::pay_off_installment_plan,
+            commands::installments::delete_installment_plan,
+            commands::installments::process_installments,
+            // Interest commands
+            commands::interest::get_interest_settings,
+            commands::interest::set_interest_settings,
+            commands::interest::delete_interest_settings,
+            commands::interest::accrue_interest,
+            // Investment commands
+            commands::investments::list_securities,
+            commands::investments::create_security,
+            commands::investments::delete_security,
+            commands::investments::list_security_prices,
+            commands::investments::set_security_price,
+            commands::investments::delete_security_price,
+            commands::investments::import_security_prices_csv,
+            commands::investments::list_investment_trades,
+            commands::investments::record_investment_trade,
+            commands::investments::delete_investment_trade,
+            commands::investments::get_investment_portfolio,
+            // Loan commands
+            commands::loans::get_loan,
+            commands::loans::set_loan_terms,
+            commands::loans::get_loan_schedule,
+            commands::loans::record_loan_payment,
+            // Savings plan commands
+            commands::savings::get_savings_plan,
+            commands::savings::set_savings_plan,
+            commands::savings::delete_savings_plan,
+            commands::savings::set_savings_rate,
+            commands::savings::delete_savings_rate,
+            commands::savings::get_savings_projection,
+            commands::savings::record_savings_dividend,
+            // Reconciliation commands
+            commands::reconciliation::start_reconciliation,
+            commands::reconciliation::get_reconciliation,
+            commands::reconciliation::set_transactions_cleared,
+            commands::reconciliation::finish_reconciliation,
+            commands::reconciliation::cancel_reconciliation,
+            commands::reconciliation::list_reconciliations,
+            commands::reconciliation::unlock_transaction,
+            // Attachment commands
+            commands::attachments::pick_attachment_file,
+            commands::attachments::list_attachments,
+            commands::attachments::add_attachment,
+            commands::attachments::get_attachment_data,
+            commands::attachments::get_attachment_thumbnail,
+            commands::attachments::save_attachment,
+            commands::attachments::delete_attachment,
             // Category commands
             commands::categories::list_categories,
             commands::categories::create_category,
             commands::categories::update_category,
+            commands::categories::move_category,
             commands::categories::archive_category,
             commands::categories::restore_category,
+            // Budget commands
+            commands::budgets::list_budgets,
+            commands::budgets::set_budget,
+            commands::budgets::delete_budget,
+            commands::budgets::copy_budgets,
+            commands::budgets::get_budget_status,
+            // Envelope commands
+            commands::envelopes::get_envelope_mode,
+            commands::envelopes::set_envelope_mode,
+            commands::envelopes::list_envelope_moves,
+            commands::envelopes::move_envelope_money,
+            commands::envelopes::delete_envelope_move,
+            commands::envelopes::cover_overspending,
+            commands::envelopes::get_envelope_month,
+            // Currency commands
+            commands::currencies::get_base_currency,
+            commands::currencies::set_base_currency,
+            commands::currencies::list_exchange_rates,
+            commands::currencies::set_exchange_rate,
+            commands::currencies::delete_exchange_rate,
+            commands::currencies::import_exchange_rates_csv,
             // Report commands
             commands::reports::get_dashboard,
             commands::reports::get_spending_breakdown,
             commands::reports::get_monthly_trends,
+            commands::reports::get_tag_report,
+            commands::reports::get_top_payees,
+            commands::reports::get_fx_gains,
+            commands::reports::get_balance_history,
+            commands::reports::get_net_worth_history,
             // Data commands
             commands::data::export_transactions_csv,
             commands::data::backup_database,
@@ -0,0 +1,66 @@
+use std::io::Cursor;
+use image::ImageFormat;
+
+/// Largest single file that can be attached.
+pub const MAX_ATTACHMENT_BYTES: u64 = 10 * 1024 * 1024;
+/// Cap on everything stored, so the vault (and its backups) stay manageable.
+pub const MAX_TOTAL_ATTACHMENT_BYTES: i64 = 1024 * 1024 * 1024;
+/// Longest side of a generated thumbnail, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// Identifies a supported file from its leading bytes rather than its name.
+pub fn detect_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+/// PNG thumbnail no larger than `THUMBNAIL_SIZE` on either side. Only PNG and
+/// JPEG are decoded; other types, and images that fail to decode, get none.
+pub fn make_thumbnail(bytes: &[u8], mime: &str) -> Option<Vec<u8>> {
+    let format = match mime {
+        "image/png" => ImageFormat::Png,
+        "image/jpeg" => ImageFormat::Jpeg,
+        _ => return None,
+    };
+    let image = image::load_from_memory_with_format(bytes, format).ok()?;
+    let mut out = Cursor::new(Vec::new());
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut out, ImageFormat::Png)
+        .ok()?;
+    Some(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnails_fit_the_box_and_keep_aspect_ratio() {
+        let mut png = Cursor::new(Vec::new());
+        image::RgbImage::new(1024, 512)
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+        assert_eq!(detect_mime(&png), Some("image/png"));
+
+        let thumbnail = make_thumbnail(&png, "image/png").unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (256, 128));
+
+        assert_eq!(detect_mime(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(make_thumbnail(b"%PDF-1.7\n", "application/pdf"), None);
+        assert_eq!(detect_mime(b"hello"), None);
+    }
+}
@@ -1,41 +1,222 @@
 use rusqlite::Connection;
-use crate::models::Account;
-
-pub fn compute_balance(conn: &Connection, account: &Account) -> f64 {
-    if account.account_type == "credit" {
-        // For credit: amount owed = sum(expenses) - sum(income payments)
-        let expenses: f64 = conn
-            .query_row(
-                "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE account_id = ?1 AND type = 'expense'",
-                [account.id],
-                |r| r.get(0),
-            )
-            .unwrap_or(0.0);
-        let income: f64 = conn
-            .query_row(
-                "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE account_id = ?1 AND type = 'income'",
-                [account.id],
-                |r| r.get(0),
-            )
-            .unwrap_or(0.0);
-        account.opening_balance + expenses - income
+use crate::models::{Account, Money};
+
+/// Whether balances of this account type are amounts owed.
+pub fn is_liability(account_type: &str) -> bool {
+    matches!(account_type, "credit" | "loan")
+}
+
+/// Balance after `income` and `expenses` have been posted on top of an
+/// opening balance. Credit and loan accounts track the amount owed, so
+/// spending grows the balance and payments shrink it; every other type is
+/// the reverse.
+pub fn apply_activity(account_type: &str, opening: i64, income: i64, expenses: i64) -> i64 {
+    if is_liability(account_type) {
+        // For credit and loans: amount owed = sum(expenses) - sum(income payments)
+        opening + expenses - income
     } else {
         // For cash/debit/savings/investment:
         // opening_balance + income - expenses
-        let income: f64 = conn
-            .query_row(
-                "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE account_id = ?1 AND type = 'income'",
-                [account.id],
-                |r| r.get(0),
-            )
-            .unwrap_or(0.0);
-        let expenses: f64 = conn
-            .query_row(
-                "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE account_id = ?1 AND type = 'expense'",
-                [account.id],
-                |r| r.get(0),
-            )
-            .unwrap_or(0.0);
-        account.opening_balance + income - expenses
+        opening + income - expenses
+    }
+}
+
+/// Balance of `account` including every transaction up to and including
+/// `as_of`, or all of them when `as_of` is `None`.
+pub fn compute_balance(conn: &Connection, account: &Account, as_of: Option<&str>) -> Money {
+    let income: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions
+             WHERE account_id = ?1 AND type = 'income' AND (?2 IS NULL OR date <= ?2)",
+            rusqlite::params![account.id, as_of],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+    let expenses: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions
+             WHERE account_id = ?1 AND type = 'expense' AND (?2 IS NULL OR date <= ?2)",
+            rusqlite::params![account.id, as_of],
+            |r| r.get(0),
+        )
+        .unwrap_or(0);
+
+    let minor = apply_activity(&account.account_type, account.opening_balance.minor, income, expenses);
+    Money::new(minor, account.currency.clone())
+}
+
+/// Spacing of the points in a balance history.
+pub const GRANULARITIES: [&str; 3] = ["daily", "weekly", "monthly"];
+
+/// Sample dates from `?1` to `?2` (inclusive) for granularity `?3`: every
+/// day, every 7th day starting a week in, or every month end. The last
+/// point is clamped to `?2` so the series always ends on the requested date.
+const POINTS_CTE: &str = "WITH RECURSIVE raw_points(d) AS (
+       SELECT CASE ?3 WHEN 'daily' THEN ?1
+                      WHEN 'weekly' THEN DATE(?1, '+6 days')
+                      ELSE DATE(?1, 'start of month', '+1 month', '-1 day') END
+       UNION ALL
+       SELECT CASE ?3 WHEN 'daily' THEN DATE(d, '+1 day')
+                      WHEN 'weekly' THEN DATE(d, '+7 days')
+                      ELSE DATE(d, '+1 day', '+1 month', '-1 day') END
+       FROM raw_points WHERE d < ?2
+     ),
+     points(d) AS (SELECT MIN(d, ?2) FROM raw_points)";
+
+/// One account's balance at the end of one sample date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceSample {
+    pub date: String,
+    pub account_id: i64,
+    pub account_type: String,
+    pub balance: Money,
+}
+
+fn check_range(date_from: &str, date_to: &str, granularity: &str) -> Result<(), String> {
+    if !GRANULARITIES.contains(&granularity) {
+        return Err(format!("Unknown granularity \"{}\"", granularity));
+    }
+    if date_from > date_to {
+        return Err("The start date must be on or before the end date".into());
+    }
+    Ok(())
+}
+
+/// The dates a history from `date_from` to `date_to` is sampled at.
+pub fn sample_dates(
+    conn: &Connection,
+    date_from: &str,
+    date_to: &str,
+    granularity: &str,
+) -> Result<Vec<String>, String> {
+    check_range(date_from, date_to, granularity)?;
+    let mut stmt = conn
+        .prepare(&format!("{POINTS_CTE} SELECT d FROM points ORDER BY d"))
+        .map_err(|e| e.to_string())?;
+    let dates = stmt
+        .query_map([date_from, date_to, granularity], |r| r.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(dates)
+}
+
+/// Balances at each sample date for one account, or for every account when
+/// `account_id` is `None`. Running totals come from window functions over
+/// each account's daily activity. An account only has samples from the day
+/// it was opened (its creation or first transaction, whichever is earlier)
+/// until the day it was archived.
+pub fn balance_samples(
+    conn: &Connection,
+    account_id: Option<i64>,
+    date_from: &str,
+    date_to: &str,
+    granularity: &str,
+) -> Result<Vec<BalanceSample>, String> {
+    check_range(date_from, date_to, granularity)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{POINTS_CTE},
+             daily AS (
+               SELECT account_id, date,
+                 SUM(CASE type WHEN 'income' THEN amount ELSE 0 END) AS income,
+                 SUM(CASE type WHEN 'expense' THEN amount ELSE 0 END) AS expenses
+               FROM transactions
+               WHERE date <= ?2 AND (?4 IS NULL OR account_id = ?4)
+               GROUP BY account_id, date
+             ),
+             running AS (
+               SELECT account_id, date,
+                 SUM(income) OVER w AS income,
+                 SUM(expenses) OVER w AS expenses,
+                 LEAD(date) OVER (PARTITION BY account_id ORDER BY date) AS next_date
+               FROM daily
+               WINDOW w AS (PARTITION BY account_id ORDER BY date ROWS UNBOUNDED PRECEDING)
+             ),
+             spans AS (
+               SELECT a.id,
+                 MIN(DATE(a.created_at), COALESCE(MIN(t.date), DATE(a.created_at))) AS opened,
+                 a.archived_at
+               FROM accounts a
+               LEFT JOIN transactions t ON t.account_id = a.id
+               WHERE ?4 IS NULL OR a.id = ?4
+               GROUP BY a.id
+             )
+             SELECT p.d, a.id, a.type, a.currency, a.opening_balance,
+               COALESCE(r.income, 0), COALESCE(r.expenses, 0)
+             FROM points p
+             JOIN spans s ON s.opened <= p.d AND (s.archived_at IS NULL OR s.archived_at > p.d)
+             JOIN accounts a ON a.id = s.id
+             LEFT JOIN running r ON r.account_id = a.id AND r.date <= p.d
+               AND (r.next_date IS NULL OR r.next_date > p.d)
+             ORDER BY p.d, a.id"
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let samples = stmt
+        .query_map(rusqlite::params![date_from, date_to, granularity, account_id], |r| {
+            let account_type: String = r.get(2)?;
+            let balance = apply_activity(&account_type, r.get(4)?, r.get(5)?, r.get(6)?);
+            Ok(BalanceSample {
+                date: r.get(0)?,
+                account_id: r.get(1)?,
+                balance: Money::new(balance, r.get::<_, String>(3)?),
+                account_type,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn samples_follow_running_balances_and_account_lifetimes() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, opening_balance, created_at) VALUES ('Bank', 'debit', 100000, '2025-01-01 08:00:00');
+             INSERT INTO accounts (name, type, created_at) VALUES ('Card', 'credit', '2025-02-10 08:00:00');
+             INSERT INTO accounts (name, type, created_at, is_active, archived_at)
+               VALUES ('Old Wallet', 'cash', '2025-06-01 08:00:00', 0, '2025-02-15');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'income', 50000, '2025-01-20');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 20000, '2025-02-05');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (2, 'expense', 7500, '2025-02-12');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (3, 'income', 3000, '2025-01-25');",
+        )
+        .unwrap();
+
+        assert_eq!(
+            sample_dates(&conn, "2025-01-15", "2025-03-10", "monthly").unwrap(),
+            vec!["2025-01-31", "2025-02-28", "2025-03-10"]
+        );
+
+        let samples = balance_samples(&conn, None, "2025-01-15", "2025-03-10", "monthly").unwrap();
+        let summary: Vec<(&str, i64, i64)> = samples
+            .iter()
+            .map(|s| (s.date.as_str(), s.account_id, s.balance.minor))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("2025-01-31", 1, 150000),
+                // Open from its first transaction, before its creation date.
+                ("2025-01-31", 3, 3000),
+                ("2025-02-28", 1, 130000),
+                // Credit balances are the amount owed.
+                ("2025-02-28", 2, 7500),
+                ("2025-03-10", 1, 130000),
+                ("2025-03-10", 2, 7500),
+            ]
+        );
+
+        let weekly = balance_samples(&conn, Some(1), "2025-01-15", "2025-01-31", "weekly").unwrap();
+        let dates: Vec<&str> = weekly.iter().map(|s| s.date.as_str()).collect();
+        assert_eq!(dates, vec!["2025-01-21", "2025-01-28", "2025-01-31"]);
+        assert!(balance_samples(&conn, None, "2025-01-15", "2025-03-10", "hourly").is_err());
     }
 }
@@ -0,0 +1,182 @@
+use rusqlite::Connection;
+use crate::logic::balance::compute_balance;
+use crate::models::{minor_unit_exponent, Account, CardStatement, Money};
+
+/// Share of the statement balance due as a minimum payment, in percent.
+pub const MINIMUM_PAYMENT_PERCENT: i64 = 3;
+/// Smallest minimum payment, in major units of the card's currency. Balances
+/// below it are due in full.
+pub const MINIMUM_PAYMENT_FLOOR: i64 = 500;
+/// Days from closing to due date for cards with no payment due day set.
+pub const DEFAULT_GRACE_DAYS: i64 = 21;
+
+/// Minimum payment on a statement: `MINIMUM_PAYMENT_PERCENT` of the balance
+/// (rounded up to a whole minor unit) but at least `MINIMUM_PAYMENT_FLOOR`,
+/// and never more than the balance itself.
+pub fn minimum_payment(statement_balance: &Money) -> Money {
+    let balance = statement_balance.minor.max(0);
+    let floor = MINIMUM_PAYMENT_FLOOR * 10i64.pow(minor_unit_exponent(&statement_balance.currency));
+    let percent = (balance * MINIMUM_PAYMENT_PERCENT + 99) / 100;
+    Money::new(percent.max(floor).min(balance), statement_balance.currency.clone())
+}
+
+/// Day `day` of the month `months` away from `date`'s month, clamped to the
+/// last day of that month (so day 31 closes on 28 February).
+pub fn day_in_month(conn: &Connection, date: &str, months: i64, day: i64) -> Result<String, String> {
+    conn.query_row(
+        "SELECT MIN(DATE(?1, 'start of month', ?2, ?3), DATE(?1, 'start of month', ?2, '+1 month', '-1 day'))",
+        rusqlite::params![date, format!("{:+} months", months), format!("+{} days", day - 1)],
+        |r| r.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn add_days(conn: &Connection, date: &str, days: i64) -> Result<String, String> {
+    conn.query_row("SELECT DATE(?1, ?2)", rusqlite::params![date, format!("{:+} days", days)], |r| r.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// Due date of the statement closing on `closing_date`: the first
+/// `payment_due_day` after closing, or `DEFAULT_GRACE_DAYS` later.
+fn due_date(conn: &Connection, closing_date: &str, payment_due_day: Option<i64>) -> Result<String, String> {
+    match payment_due_day {
+        Some(day) => {
+            let same_month = day_in_month(conn, closing_date, 0, day)?;
+            if same_month.as_str() > closing_date {
+                Ok(same_month)
+            } else {
+                day_in_month(conn, closing_date, 1, day)
+            }
+        }
+        None => add_days(conn, closing_date, DEFAULT_GRACE_DAYS),
+    }
+}
+
+/// Closing date of the cycle containing `date` (on or after it).
+pub fn next_closing_date(conn: &Connection, billing_cycle_day: i64, date: &str) -> Result<String, String> {
+    let this_month = day_in_month(conn, date, 0, billing_cycle_day)?;
+    if this_month.as_str() >= date {
+        Ok(this_month)
+    } else {
+        day_in_month(conn, date, 1, billing_cycle_day)
+    }
+}
+
+/// Payments into the card from `credit_payment` transfers dated after
+/// `after` up to and including `until`, in the card's currency.
+fn payments_between(conn: &Connection, account_id: i64, after: &str, until: &str) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(to_amount), 0) FROM transfers
+         WHERE to_account_id = ?1 AND transfer_type = 'credit_payment' AND date > ?2 AND date <= ?3",
+        rusqlite::params![account_id, after, until],
+        |r| r.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// The last `count` closed statements of a credit card as of `today`,
+/// newest first. The statement balance is the amount owed at closing;
+/// payments count toward it from the day after closing until the next
+/// closing, so a late payment still settles it.
+pub fn card_statements(
+    conn: &Connection,
+    account: &Account,
+    today: &str,
+    count: i64,
+) -> Result<Vec<CardStatement>, String> {
+    let cycle_day = account
+        .billing_cycle_day
+        .ok_or_else(|| format!("Set a billing cycle day for {} first", account.name))?;
+    if !(1..=31).contains(&cycle_day) {
+        return Err("Billing cycle days run from 1 to 31".into());
+    }
+
+    // Closings from the newest one on or before today, going back one month at a time.
+    let upcoming = next_closing_date(conn, cycle_day, today)?;
+    let first_offset = if upcoming.as_str() == today { 0 } else { -1 };
+    let mut statements = Vec::new();
+    for k in 0..count.max(0) {
+        let offset = first_offset - k;
+        let closing_date = day_in_month(conn, today, offset, cycle_day)?;
+        let previous_closing = day_in_month(conn, today, offset - 1, cycle_day)?;
+        let next_closing = day_in_month(conn, today, offset + 1, cycle_day)?;
+        let due = due_date(conn, &closing_date, account.payment_due_day)?;
+
+        let balance = compute_balance(conn, account, Some(&closing_date));
+        let paid_until = if next_closing.as_str() < today { next_closing.as_str() } else { today };
+        let payments = payments_between(conn, account.id, &closing_date, paid_until)?;
+        let remaining = (balance.minor - payments).max(0);
+        let status = if remaining == 0 {
+            "paid"
+        } else if today > due.as_str() {
+            "overdue"
+        } else {
+            "due"
+        };
+
+        statements.push(CardStatement {
+            period_start: add_days(conn, &previous_closing, 1)?,
+            closing_date,
+            due_date: due,
+            minimum_payment: minimum_payment(&balance),
+            payments: Money::new(payments, account.currency.clone()),
+            amount_remaining: Money::new(remaining, account.currency.clone()),
+            statement_balance: balance,
+            status: status.to_string(),
+        });
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_test_db;
+
+    #[test]
+    fn minimum_payment_uses_percent_with_a_floor() {
+        assert_eq!(minimum_payment(&Money::new(5_000_000, "PHP")).minor, 150_000);
+        assert_eq!(minimum_payment(&Money::new(1_000_000, "PHP")).minor, 50_000);
+        assert_eq!(minimum_payment(&Money::new(30_000, "PHP")).minor, 30_000);
+        assert_eq!(minimum_payment(&Money::new(-100, "PHP")).minor, 0);
+    }
+
+    #[test]
+    fn statements_close_on_the_cycle_day_and_apply_payments() {
+        let conn = open_test_db();
+        conn.execute_batch(
+            "INSERT INTO accounts (name, type, credit_limit, billing_cycle_day, payment_due_day)
+               VALUES ('Card', 'credit', 10000000, 31, 15);
+             INSERT INTO accounts (name, type, opening_balance) VALUES ('Bank', 'debit', 10000000);
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 2000000, '2025-01-20');
+             INSERT INTO transactions (account_id, type, amount, date) VALUES (1, 'expense', 500000, '2025-02-10');
+             INSERT INTO transfers (from_account_id, to_account_id, amount, to_amount, date, transfer_type)
+               VALUES (2, 1, 1500000, 1500000, '2025-02-12', 'credit_payment');
+             INSERT INTO transactions (account_id, transfer_id, type, amount, date) VALUES (1, 1, 'income', 1500000, '2025-02-12');",
+        )
+        .unwrap();
+        let card: Account = conn
+            .query_row(
+                &format!("{} WHERE id = 1", crate::commands::accounts::ACCOUNT_SELECT),
+                [],
+                crate::commands::accounts::row_to_account,
+            )
+            .unwrap();
+
+        let statements = card_statements(&conn, &card, "2025-03-05", 2).unwrap();
+        // Day 31 closes on the last day of shorter months.
+        assert_eq!(statements[0].closing_date, "2025-02-28");
+        assert_eq!(statements[0].period_start, "2025-02-01");
+        assert_eq!(statements[0].due_date, "2025-03-15");
+        assert_eq!(statements[0].statement_balance.minor, 1_000_000);
+        assert_eq!(statements[0].status, "due");
+
+        assert_eq!(statements[1].closing_date, "2025-01-31");
+        assert_eq!(statements[1].due_date, "2025-02-15");
+        assert_eq!(statements[1].statement_balance.minor, 2_000_000);
+        assert_eq!(statements[1].payments.minor, 1_500_000);
+        assert_eq!(statements[1].amount_remaining.minor, 500_000);
+        assert_eq!(statements[1].minimum_payment.minor, 60_000);
+        assert_eq!(statements[1].status, "overdue");
+    }
+}
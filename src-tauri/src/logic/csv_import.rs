@@ -0,0 +1,418 @@
+use crate::models::{minor_unit_exponent, CsvMapping, ImportPreview, ImportRow, Money};
+
+const DELIMITER_CANDIDATES: [char; 4] = [',', ';', '\t', '|'];
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Counts `ch` outside quoted sections of a single line.
+fn count_unquoted(line: &str, ch: char, quote: char) -> usize {
+    let mut in_quotes = false;
+    line.chars()
+        .filter(|&c| {
+            if c == quote {
+                in_quotes = !in_quotes;
+            }
+            c == ch && !in_quotes
+        })
+        .count()
+}
+
+/// Picks the quote character: whichever of `"` and `'` opens more fields.
+pub fn detect_quote(text: &str) -> char {
+    let opens = |q: char| {
+        let mut count = 0usize;
+        for line in text.lines().take(20) {
+            let mut prev: Option<char> = None;
+            for c in line.chars() {
+                if c == q && (prev.is_none() || prev.is_some_and(|p| DELIMITER_CANDIDATES.contains(&p))) {
+                    count += 1;
+                }
+                prev = Some(c);
+            }
+        }
+        count
+    };
+    if opens('\'') > opens('"') {
+        '\''
+    } else {
+        '"'
+    }
+}
+
+/// Chooses the candidate that splits the first lines into the same, largest
+/// number of fields. Falls back to a comma.
+pub fn detect_delimiter(text: &str) -> char {
+    let quote = detect_quote(text);
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).take(10).collect();
+    DELIMITER_CANDIDATES
+        .iter()
+        .copied()
+        .filter_map(|d| {
+            let counts: Vec<usize> = lines.iter().map(|l| count_unquoted(l, d, quote)).collect();
+            let first = *counts.first()?;
+            // Tolerate ragged preamble/footer lines but require a clear majority.
+            let agreeing = counts.iter().filter(|&&c| c == first).count();
+            (first > 0 && agreeing * 2 > counts.len()).then_some((d, first))
+        })
+        .max_by_key(|&(_, count)| count)
+        .map_or(',', |(d, _)| d)
+}
+
+/// Splits CSV text into records, honouring quoted fields (which may contain
+/// delimiters, doubled quotes and line breaks). Each record carries the
+/// 1-based line it starts on.
+pub fn split_records(text: &str, delimiter: char, quote: char) -> Vec<(i64, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1i64;
+    let mut record_line = 1i64;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == quote {
+                if chars.peek() == Some(&quote) {
+                    field.push(quote);
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+            continue;
+        }
+        match c {
+            c if c == quote && field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+    records
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Reads between `min` and `max` leading ASCII digits.
+fn take_digits(s: &str, min: usize, max: usize) -> Option<(i64, usize)> {
+    let len = s.chars().take(max).take_while(|c| c.is_ascii_digit()).count();
+    if len < min {
+        return None;
+    }
+    s[..len].parse().ok().map(|n| (n, len))
+}
+
+/// Parses `value` with a pattern such as `MM/DD/YYYY` or `DD-MMM-YY` and
+/// returns an ISO `YYYY-MM-DD` date. Anything after the date that starts with
+/// a space or `T` (a time of day) is ignored.
+pub fn parse_date(value: &str, format: &str) -> Result<String, String> {
+    let invalid = || format!("\"{}\" does not match date format {}", value, format);
+    let value = value.trim();
+    let mut rest = value;
+    let mut fmt = format;
+    let (mut year, mut month, mut day) = (None, None, None);
+
+    while !fmt.is_empty() {
+        if let Some(f) = fmt.strip_prefix("YYYY") {
+            let (n, len) = take_digits(rest, 4, 4).ok_or_else(invalid)?;
+            year = Some(n);
+            rest = &rest[len..];
+            fmt = f;
+        } else if let Some(f) = fmt.strip_prefix("MMM") {
+            let len = rest.chars().take_while(|c| c.is_ascii_alphabetic()).count();
+            let name = rest[..len].to_lowercase();
+            let idx = MONTHS
+                .iter()
+                .position(|m| len >= 3 && name.starts_with(m))
+                .ok_or_else(invalid)?;
+            month = Some(idx as i64 + 1);
+            rest = &rest[len..];
+            fmt = f;
+        } else if let Some(f) = fmt.strip_prefix("YY") {
+            let (n, len) = take_digits(rest, 2, 2).ok_or_else(invalid)?;
+            year = Some(if n < 70 { 2000 + n } else { 1900 + n });
+            rest = &rest[len..];
+            fmt = f;
+        } else if let Some(f) = fmt.strip_prefix("MM").or_else(|| fmt.strip_prefix('M')) {
+            let (n, len) = take_digits(rest, 1, 2).ok_or_else(invalid)?;
+            month = Some(n);
+            rest = &rest[len..];
+            fmt = f;
+        } else if let Some(f) = fmt.strip_prefix("DD").or_else(|| fmt.strip_prefix('D')) {
+            let (n, len) = take_digits(rest, 1, 2).ok_or_else(invalid)?;
+            day = Some(n);
+            rest = &rest[len..];
+            fmt = f;
+        } else {
+            let literal = fmt.chars().next().unwrap_or_default();
+            rest = rest.strip_prefix(literal).ok_or_else(invalid)?;
+            fmt = &fmt[literal.len_utf8()..];
+        }
+    }
+    if !(rest.is_empty() || rest.starts_with(' ') || rest.starts_with('T')) {
+        return Err(invalid());
+    }
+
+    let (year, month, day) = (year.ok_or_else(invalid)?, month.ok_or_else(invalid)?, day.ok_or_else(invalid)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(format!("\"{}\" is not a valid date", value));
+    }
+    Ok(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Parses a bank-formatted amount into minor units. Accepts thousands
+/// separators, currency codes/symbols, a leading or trailing minus and
+/// accounting-style parentheses. Returns `None` for an empty cell.
+pub fn parse_amount(raw: &str, decimal_separator: char, exponent: u32) -> Result<Option<i64>, String> {
+    let invalid = || format!("\"{}\" is not a valid amount", raw.trim());
+    let mut s = raw.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    let mut negative = false;
+    if let Some(inner) = s.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        negative = true;
+        s = inner;
+    }
+
+    let mut whole = String::new();
+    let mut fraction = String::new();
+    let mut in_fraction = false;
+    for c in s.chars() {
+        match c {
+            '-' | '\u{2212}' => negative = true,
+            '+' => {}
+            c if c == decimal_separator => {
+                if in_fraction {
+                    return Err(invalid());
+                }
+                in_fraction = true;
+            }
+            c if c.is_ascii_digit() => {
+                if in_fraction {
+                    fraction.push(c)
+                } else {
+                    whole.push(c)
+                }
+            }
+            ',' | '.' | ' ' | '\'' | '\u{a0}' => {}
+            c if c.is_alphabetic() || "₱$€£¥".contains(c) => {}
+            _ => return Err(invalid()),
+        }
+    }
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+
+    let exponent = exponent as usize;
+    let trimmed = fraction.trim_end_matches('0');
+    if trimmed.len() > exponent {
+        return Err(format!("\"{}\" has more decimal places than the currency allows", raw.trim()));
+    }
+    let digits = format!("{}{:0<width$}", whole, trimmed, width = exponent);
+    let minor: i64 = if digits.is_empty() { 0 } else { digits.parse().map_err(|_| invalid())? };
+    Ok(Some(if negative { -minor } else { minor }))
+}
+
+fn cell(fields: &[String], column: i64) -> Result<&str, String> {
+    usize::try_from(column)
+        .ok()
+        .and_then(|i| fields.get(i))
+        .map(|s| s.trim())
+        .ok_or_else(|| format!("Missing column {}", column + 1))
+}
+
+fn parse_row(fields: &[String], mapping: &CsvMapping, currency: &str) -> Result<ImportRow, String> {
+    let decimal = mapping.decimal_separator.chars().next().unwrap_or('.');
+    let exponent = minor_unit_exponent(currency);
+
+    let date = parse_date(cell(fields, mapping.date_column)?, &mapping.date_format)?;
+    let description = match mapping.description_column {
+        Some(col) => Some(cell(fields, col)?.to_string()).filter(|d| !d.is_empty()),
+        None => None,
+    };
+
+    let signed = if let Some(col) = mapping.amount_column {
+        let amount = parse_amount(cell(fields, col)?, decimal, exponent)?.ok_or("No amount")?;
+        if mapping.negate_amounts { -amount } else { amount }
+    } else {
+        let debit = match mapping.debit_column {
+            Some(col) => parse_amount(cell(fields, col)?, decimal, exponent)?,
+            None => None,
+        };
+        let credit = match mapping.credit_column {
+            Some(col) => parse_amount(cell(fields, col)?, decimal, exponent)?,
+            None => None,
+        };
+        if debit.is_none() && credit.is_none() {
+            return Err("No amount".into());
+        }
+        credit.unwrap_or(0).abs() - debit.unwrap_or(0).abs()
+    };
+    if signed == 0 {
+        return Err("Amount is zero".into());
+    }
+
+    Ok(ImportRow {
+        line: 0,
+        date: Some(date),
+        description,
+        tx_type: Some(if signed < 0 { "expense" } else { "income" }.to_string()),
+        amount: Some(Money::new(signed.abs(), currency)),
+        external_id: None,
+        duplicate_of: None,
+        error: None,
+    })
+}
+
+/// Parses a CSV export using `mapping`. Amounts are read in `currency` (the
+/// target account's). Blank lines are dropped; rows that fail to parse are
+/// returned with an error instead of aborting the whole file.
+pub fn parse_csv(text: &str, mapping: &CsvMapping, currency: &str) -> Result<ImportPreview, String> {
+    if mapping.amount_column.is_none() && mapping.debit_column.is_none() && mapping.credit_column.is_none() {
+        return Err("Map an amount column or debit/credit columns".into());
+    }
+    let text = text.trim_start_matches('\u{feff}');
+    let skip = usize::try_from(mapping.skip_rows).unwrap_or(0);
+    let body_start = text.split_inclusive('\n').take(skip).map(str::len).sum::<usize>();
+    let body = &text[body_start..];
+
+    let delimiter = match mapping.delimiter.as_deref().and_then(|d| d.chars().next()) {
+        Some(d) => d,
+        None => detect_delimiter(body),
+    };
+    let quote = detect_quote(body);
+    let mut records = split_records(body, delimiter, quote)
+        .into_iter()
+        .filter(|(_, fields)| fields.iter().any(|f| !f.trim().is_empty()))
+        .peekable();
+
+    let headers = if mapping.has_header {
+        records
+            .next()
+            .map(|(_, fields)| fields.into_iter().map(|f| f.trim().to_string()).collect())
+            .unwrap_or_default()
+    } else {
+        let width = records.peek().map_or(0, |(_, f)| f.len());
+        (1..=width).map(|i| format!("Column {}", i)).collect()
+    };
+
+    let rows = records
+        .map(|(line, fields)| {
+            let line = line + skip as i64;
+            match parse_row(&fields, mapping, currency) {
+                Ok(row) => ImportRow { line, ..row },
+                Err(error) => ImportRow {
+                    line,
+                    date: None,
+                    description: None,
+                    tx_type: None,
+                    amount: None,
+                    external_id: None,
+                    duplicate_of: None,
+                    error: Some(error),
+                },
+            }
+        })
+        .collect();
+
+    Ok(ImportPreview {
+        delimiter: delimiter.to_string(),
+        headers,
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> CsvMapping {
+        CsvMapping {
+            delimiter: None,
+            has_header: true,
+            skip_rows: 0,
+            date_column: 0,
+            date_format: "MM/DD/YYYY".into(),
+            description_column: Some(1),
+            amount_column: None,
+            debit_column: Some(2),
+            credit_column: Some(3),
+            negate_amounts: false,
+            decimal_separator: ".".into(),
+        }
+    }
+
+    #[test]
+    fn parses_amount_formats() {
+        assert_eq!(parse_amount("1,234.50", '.', 2), Ok(Some(123450)));
+        assert_eq!(parse_amount("(1,234.5)", '.', 2), Ok(Some(-123450)));
+        assert_eq!(parse_amount("PHP -75", '.', 2), Ok(Some(-7500)));
+        assert_eq!(parse_amount("1.234,56", ',', 2), Ok(Some(123456)));
+        assert_eq!(parse_amount("12-", '.', 0), Ok(Some(-12)));
+        assert_eq!(parse_amount("", '.', 2), Ok(None));
+        assert!(parse_amount("1.234", '.', 2).is_err());
+    }
+
+    #[test]
+    fn parses_date_patterns() {
+        assert_eq!(parse_date("1/5/2025", "MM/DD/YYYY").unwrap(), "2025-01-05");
+        assert_eq!(parse_date("05-Jan-25", "DD-MMM-YY").unwrap(), "2025-01-05");
+        assert_eq!(parse_date("2025-01-05T08:00:00", "YYYY-MM-DD").unwrap(), "2025-01-05");
+        assert!(parse_date("02/30/2025", "MM/DD/YYYY").is_err());
+        assert!(parse_date("2025/01/05", "MM/DD/YYYY").is_err());
+    }
+
+    #[test]
+    fn parses_semicolon_file_with_quotes_and_debit_credit_columns() {
+        let text = "Date;Description;Debit;Credit\r\n\
+                    01/05/2025;\"JOLLIBEE; AYALA\";1,250.00;\r\n\
+                    \r\n\
+                    01/06/2025;\"Salary \"\"Jan\"\"\";;45,000.00\r\n\
+                    Total;;1,250.00;45,000.00\r\n";
+        let preview = parse_csv(text, &mapping(), "PHP").unwrap();
+        assert_eq!(preview.delimiter, ";");
+        assert_eq!(preview.headers, vec!["Date", "Description", "Debit", "Credit"]);
+        assert_eq!(preview.rows.len(), 3);
+
+        let lunch = &preview.rows[0];
+        assert_eq!(lunch.description.as_deref(), Some("JOLLIBEE; AYALA"));
+        assert_eq!(lunch.tx_type.as_deref(), Some("expense"));
+        assert_eq!(lunch.amount, Some(Money::new(125000, "PHP")));
+
+        let salary = &preview.rows[1];
+        assert_eq!(salary.line, 4);
+        assert_eq!(salary.description.as_deref(), Some("Salary \"Jan\""));
+        assert_eq!(salary.tx_type.as_deref(), Some("income"));
+
+        assert!(preview.rows[2].error.is_some());
+    }
+}
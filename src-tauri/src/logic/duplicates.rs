@@ -0,0 +1,114 @@
+use std::collections::{BTreeMap, HashSet};
+
+/// Days apart two rows may be and still count as the same purchase.
+pub const DEFAULT_WINDOW_DAYS: i64 = 3;
+/// Score (out of 100) from which a pair is reported as a probable duplicate.
+pub const DEFAULT_MIN_SCORE: i64 = 60;
+
+/// What scoring looks at beyond account, type and amount, which candidate
+/// queries already require to be equal.
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateFields<'a> {
+    pub payee_id: Option<i64>,
+    pub notes: Option<&'a str>,
+    pub external_id: Option<&'a str>,
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of words the two notes have in common, from 0.0 to 1.0.
+fn notes_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Scores how likely two rows with the same account, type and amount are the
+/// same purchase, from 0 to 100. Rows carrying different bank ids are never
+/// duplicates; otherwise the score starts at 40, rises with date proximity,
+/// a shared payee and similar notes, and drops when the payees differ.
+pub fn score_pair(a: &DuplicateFields, b: &DuplicateFields, days_apart: i64) -> i64 {
+    if let (Some(x), Some(y)) = (a.external_id, b.external_id) {
+        if x != y {
+            return 0;
+        }
+    }
+    let mut score = 40 + (30 - 10 * days_apart.abs()).max(0);
+    match (a.payee_id, b.payee_id) {
+        (Some(x), Some(y)) if x == y => score += 20,
+        (Some(_), Some(_)) => score -= 30,
+        _ => {}
+    }
+    if let (Some(x), Some(y)) = (a.notes, b.notes) {
+        score += (notes_similarity(x, y) * 10.0).round() as i64;
+    }
+    score.clamp(0, 100)
+}
+
+/// Groups pairs that share a row into clusters, each sorted by id. Clusters
+/// come back ordered by their smallest id.
+pub fn cluster_pairs(pairs: &[(i64, i64)]) -> Vec<Vec<i64>> {
+    let mut parent: BTreeMap<i64, i64> = BTreeMap::new();
+    fn root(parent: &mut BTreeMap<i64, i64>, id: i64) -> i64 {
+        let mut current = id;
+        while let Some(&next) = parent.get(&current) {
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+        parent.insert(id, current);
+        current
+    }
+
+    for &(a, b) in pairs {
+        parent.entry(a).or_insert(a);
+        parent.entry(b).or_insert(b);
+        let (ra, rb) = (root(&mut parent, a), root(&mut parent, b));
+        if ra != rb {
+            parent.insert(ra.max(rb), ra.min(rb));
+        }
+    }
+
+    let ids: Vec<i64> = parent.keys().copied().collect();
+    let mut clusters: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for id in ids {
+        let r = root(&mut parent, id);
+        clusters.entry(r).or_default().push(id);
+    }
+    clusters.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_favour_close_dates_and_matching_details() {
+        let manual = DuplicateFields { payee_id: Some(1), notes: Some("Jollibee lunch"), external_id: None };
+        let imported = DuplicateFields { payee_id: Some(1), notes: Some("JOLLIBEE #12"), external_id: Some("A1") };
+        assert_eq!(score_pair(&manual, &imported, 0), 93);
+        assert_eq!(score_pair(&manual, &imported, 1), 83);
+        assert_eq!(score_pair(&manual, &imported, 5), 63);
+
+        let other_payee = DuplicateFields { payee_id: Some(2), ..manual.clone() };
+        assert!(score_pair(&manual, &other_payee, 0) < DEFAULT_MIN_SCORE);
+
+        let other_fitid = DuplicateFields { external_id: Some("A2"), ..imported.clone() };
+        assert_eq!(score_pair(&imported, &other_fitid, 0), 0);
+    }
+
+    #[test]
+    fn clusters_join_chained_pairs() {
+        let clusters = cluster_pairs(&[(5, 9), (2, 3), (9, 12), (3, 7)]);
+        assert_eq!(clusters, vec![vec![2, 3, 7], vec![5, 9, 12]]);
+    }
+}
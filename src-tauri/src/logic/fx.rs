@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use rusqlite::Connection;
+use crate::logic::csv_import::{detect_delimiter, detect_quote, parse_date, split_records};
+use crate::models::{minor_unit_exponent, normalize_currency, Money};
+
+/// Converts `minor` units of `from` into minor units of `to`, where one unit
+/// of `from` is worth `rate` units of `to`. Rounds half away from zero.
+pub fn convert_minor(minor: i64, from: &str, to: &str, rate: f64) -> i64 {
+    let shift = minor_unit_exponent(to) as i32 - minor_unit_exponent(from) as i32;
+    (minor as f64 * rate * 10f64.powi(shift)).round() as i64
+}
+
+/// Rate implied by sending `from` and receiving `to`: units of `to`'s currency
+/// per unit of `from`'s. Zero when nothing was sent.
+pub fn implied_rate(from: &Money, to: &Money) -> f64 {
+    if from.minor == 0 {
+        return 0.0;
+    }
+    let shift = minor_unit_exponent(&from.currency) as i32 - minor_unit_exponent(&to.currency) as i32;
+    to.minor as f64 / from.minor as f64 * 10f64.powi(shift)
+}
+
+/// Every stored exchange rate, indexed for lookups by currency pair and date.
+#[derive(Debug, Default)]
+pub struct RateBook {
+    /// (base, quote) -> (date, rate) sorted by date.
+    rates: HashMap<(String, String), Vec<(String, f64)>>,
+}
+
+impl RateBook {
+    pub fn load(conn: &Connection) -> Result<Self, String> {
+        let mut stmt = conn
+            .prepare("SELECT base, quote, date, rate FROM exchange_rates ORDER BY date")
+            .map_err(|e| e.to_string())?;
+        let rows: Vec<(String, String, String, f64)> = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(Self::from_rates(rows))
+    }
+
+    pub fn from_rates(rates: impl IntoIterator<Item = (String, String, String, f64)>) -> Self {
+        let mut book = Self::default();
+        for (base, quote, date, rate) in rates {
+            book.rates.entry((base, quote)).or_default().push((date, rate));
+        }
+        for dated in book.rates.values_mut() {
+            dated.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+        book
+    }
+
+    /// The rate from the latest entry on or before `date`, or failing that
+    /// the earliest one after it, with the date of the entry used.
+    fn lookup(&self, base: &str, quote: &str, date: &str) -> Option<(&str, f64)> {
+        let dated = self.rates.get(&(base.to_string(), quote.to_string()))?;
+        dated
+            .iter()
+            .rev()
+            .find(|(d, _)| d.as_str() <= date)
+            .or_else(|| dated.first())
+            .map(|(d, r)| (d.as_str(), *r))
+    }
+
+    /// Units of `to` one unit of `from` was worth on `date`. Stored pairs are
+    /// used in either direction; when both directions are stored, the one
+    /// effective closest to `date` wins.
+    pub fn rate(&self, from: &str, to: &str, date: &str) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        let direct = self.lookup(from, to, date);
+        let inverse = self.lookup(to, from, date).map(|(d, r)| (d, 1.0 / r));
+        match (direct, inverse) {
+            (Some(a), Some(b)) => {
+                // Prefer entries on or before the date, then the closest one.
+                let pick_a = match (a.0 <= date, b.0 <= date) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    (true, true) => a.0 >= b.0,
+                    (false, false) => a.0 <= b.0,
+                };
+                Some(if pick_a { a.1 } else { b.1 })
+            }
+            (Some(a), None) => Some(a.1),
+            (None, Some(b)) => Some(b.1),
+            (None, None) => None,
+        }
+    }
+
+    /// `minor` units of `from` expressed in `to` at the rate effective on `date`.
+    pub fn convert(&self, minor: i64, from: &str, to: &str, date: &str) -> Option<i64> {
+        if from == to {
+            return Some(minor);
+        }
+        self.rate(from, to, date).map(|rate| convert_minor(minor, from, to, rate))
+    }
+}
+
+/// One parsed line of an exchange-rate CSV: `date,base,quote,rate`, meaning
+/// one unit of `base` was worth `rate` units of `quote` from `date` on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateRow {
+    pub date: String,
+    pub base: String,
+    pub quote: String,
+    pub rate: f64,
+}
+
+/// Parses an exchange-rate CSV. A header row is skipped when its rate column
+/// is not a number; any other bad row fails the whole file with its line.
+pub fn parse_rates_csv(text: &str) -> Result<Vec<RateRow>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let records = split_records(text, detect_delimiter(text), detect_quote(text));
+    let mut rows = Vec::new();
+    for (index, (line, fields)) in records.into_iter().enumerate() {
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let parsed = (|| {
+            if fields.len() < 4 {
+                return Err("Expected date, base, quote and rate columns".to_string());
+            }
+            let rate: f64 = fields[3]
+                .trim()
+                .replace(',', "")
+                .parse()
+                .map_err(|_| format!("\"{}\" is not a rate", fields[3].trim()))?;
+            if !(rate.is_finite() && rate > 0.0) {
+                return Err("Rates must be positive".to_string());
+            }
+            Ok(RateRow {
+                date: parse_date(&fields[0], "YYYY-MM-DD")?,
+                base: normalize_currency(&fields[1])?,
+                quote: normalize_currency(&fields[2])?,
+                rate,
+            })
+        })();
+        match parsed {
+            Ok(row) if row.base == row.quote => {
+                return Err(format!("Line {}: base and quote currencies are the same", line))
+            }
+            Ok(row) => rows.push(row),
+            Err(_) if index == 0 && fields.get(3).is_some_and(|f| f.trim().parse::<f64>().is_err()) => {}
+            Err(e) => return Err(format!("Line {}: {}", line, e)),
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book() -> RateBook {
+        RateBook::from_rates([
+            ("USD".into(), "PHP".into(), "2025-01-01".into(), 58.0),
+            ("USD".into(), "PHP".into(), "2025-02-01".into(), 57.5),
+            ("PHP".into(), "JPY".into(), "2025-01-15".into(), 2.7),
+        ])
+    }
+
+    #[test]
+    fn uses_the_rate_effective_on_the_date() {
+        let book = book();
+        // $100.00 on 2025-01-20 at 58 -> ₱5,800.00
+        assert_eq!(book.convert(10000, "USD", "PHP", "2025-01-20"), Some(580000));
+        assert_eq!(book.convert(10000, "USD", "PHP", "2025-02-03"), Some(575000));
+        // Before the first rate, the earliest one is used.
+        assert_eq!(book.convert(10000, "USD", "PHP", "2024-12-31"), Some(580000));
+        // Pairs work in reverse, and minor units follow each currency (JPY has none).
+        assert_eq!(book.convert(2700, "JPY", "PHP", "2025-03-01"), Some(100000));
+        assert_eq!(book.convert(100, "USD", "JPY", "2025-03-01"), None);
+        assert_eq!(book.convert(123, "PHP", "PHP", "2025-03-01"), Some(123));
+    }
+
+    #[test]
+    fn implied_rate_follows_minor_units() {
+        // $100.00 sent, ₱5,750.00 received
+        assert_eq!(implied_rate(&Money::new(10000, "USD"), &Money::new(575000, "PHP")), 57.5);
+        // ¥10,000 sent, $68.00 received
+        let rate = implied_rate(&Money::new(10000, "JPY"), &Money::new(6800, "USD"));
+        assert!((rate - 0.0068).abs() < 1e-12);
+    }
+
+    #[test]
+    fn parses_rate_csv_with_optional_header() {
+        let rows = parse_rates_csv("date,base,quote,rate\n2025-01-01,usd,PHP,58.10\n2025-01-01,JPY,PHP,0.37\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].base, "USD");
+        assert_eq!(rows[1].rate, 0.37);
+
+        assert!(parse_rates_csv("2025-01-01,USD,PHP,58\n2025-01-02,USD,PHP,abc\n").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+/// Longest installment term offered, in months.
+pub const MAX_TERM_MONTHS: i64 = 60;
+
+/// Principal billed in installment `number` (1-based) of `term`: an equal
+/// share, with the rounding remainder on the last one so the shares always
+/// add up to `principal`.
+pub fn installment_principal(principal: i64, term: i64, number: i64) -> i64 {
+    let share = principal / term;
+    if number == term {
+        principal - share * (term - 1)
+    } else {
+        share
+    }
+}
+
+/// Add-on interest billed every month: a flat percentage of the original
+/// principal, not of the declining balance. Zero for 0% plans.
+pub fn monthly_add_on_interest(principal: i64, monthly_rate_percent: f64) -> i64 {
+    (principal as f64 * monthly_rate_percent / 100.0).round() as i64
+}
+
+/// Principal still to be billed after `posted` installments.
+pub fn remaining_principal(principal: i64, term: i64, posted: i64) -> i64 {
+    principal - (1..=posted.min(term)).map(|n| installment_principal(principal, term, n)).sum::<i64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installments_add_up_to_the_principal() {
+        // ₱10,000.00 over 12 months at 0%
+        let shares: Vec<i64> = (1..=12).map(|n| installment_principal(1_000_000, 12, n)).collect();
+        assert_eq!(shares[0], 83_333);
+        assert_eq!(shares[11], 83_337);
+        assert_eq!(shares.iter().sum::<i64>(), 1_000_000);
+        assert_eq!(remaining_principal(1_000_000, 12, 11), 83_337);
+        assert_eq!(remaining_principal(1_000_000, 12, 12), 0);
+
+        // 0.69% a month add-on on ₱24,000.00 is ₱165.60 every month.
+        assert_eq!(monthly_add_on_interest(2_400_000, 0.69), 16_560);
+        assert_eq!(monthly_add_on_interest(2_400_000, 0.0), 0);
+    }
+}
@@ -0,0 +1,113 @@
+/// How the quoted rate is meant: a nominal annual rate, or the annual yield
+/// after compounding.
+pub const RATE_TYPES: [&str; 2] = ["apr", "apy"];
+
+/// How days in a period are counted against the days in a year.
+pub const DAY_COUNTS: [&str; 3] = ["actual/365", "actual/360", "30/360"];
+
+/// How often interest is compounded (and credited).
+pub const COMPOUNDING_PERIODS: [&str; 4] = ["daily", "monthly", "quarterly", "annually"];
+
+pub fn days_in_year(day_count: &str) -> f64 {
+    if day_count == "actual/365" {
+        365.0
+    } else {
+        360.0
+    }
+}
+
+/// Days from `start` up to (not including) `end` under the 30/360
+/// convention, where every month has 30 days. Dates are `YYYY-MM-DD`.
+pub fn days_360(start: &str, end: &str) -> Result<i64, String> {
+    let parts = |date: &str| -> Result<(i64, i64, i64), String> {
+        let invalid = || format!("Invalid date \"{}\"", date);
+        let year = date.get(0..4).and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+        let month = date.get(5..7).and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+        let day = date.get(8..10).and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+        Ok((year, month, day))
+    };
+    let (y1, m1, d1) = parts(start)?;
+    let (y2, m2, mut d2) = parts(end)?;
+    let d1 = d1.min(30);
+    if d1 == 30 {
+        d2 = d2.min(30);
+    }
+    Ok(360 * (y2 - y1) + 30 * (m2 - m1) + (d2 - d1))
+}
+
+fn periods_per_year(compounding: &str, day_count: &str) -> f64 {
+    match compounding {
+        "daily" => days_in_year(day_count),
+        "quarterly" => 4.0,
+        "annually" => 1.0,
+        _ => 12.0,
+    }
+}
+
+/// Nominal annual rate as a fraction. An APY is converted to the nominal
+/// rate that yields it at the given compounding.
+pub fn nominal_rate(rate_percent: f64, rate_type: &str, compounding: &str, day_count: &str) -> f64 {
+    let rate = rate_percent / 100.0;
+    if rate_type == "apy" {
+        let n = periods_per_year(compounding, day_count);
+        n * ((1.0 + rate).powf(1.0 / n) - 1.0)
+    } else {
+        rate
+    }
+}
+
+/// Interest in minor units on an average daily balance held for
+/// `accrual_days`, compounding at the account's period within that time.
+/// Negative balances earn nothing.
+pub fn accrued_interest(
+    average_balance: i64,
+    rate_percent: f64,
+    rate_type: &str,
+    compounding: &str,
+    day_count: &str,
+    accrual_days: i64,
+) -> i64 {
+    if average_balance <= 0 || accrual_days <= 0 {
+        return 0;
+    }
+    let n = periods_per_year(compounding, day_count);
+    let rate = nominal_rate(rate_percent, rate_type, compounding, day_count);
+    let years = accrual_days as f64 / days_in_year(day_count);
+    (average_balance as f64 * ((1.0 + rate / n).powf(n * years) - 1.0)).round() as i64
+}
+
+/// Tax withheld at source on `gross` interest, e.g. the 20% final tax on
+/// Philippine deposit interest.
+pub fn withholding_tax(gross: i64, tax_percent: f64) -> i64 {
+    (gross as f64 * tax_percent / 100.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interest_follows_the_day_count_and_rate_type() {
+        assert_eq!(days_360("2025-02-01", "2025-03-01").unwrap(), 30);
+        assert_eq!(days_360("2025-01-31", "2025-03-01").unwrap(), 31);
+        assert_eq!(days_360("2025-01-01", "2026-01-01").unwrap(), 360);
+
+        // ₱100,000.00 at 3.6% for a 30-day month is ₱300.
+        assert_eq!(accrued_interest(10_000_000, 3.6, "apr", "monthly", "actual/360", 30), 30_000);
+        // A 31-day month runs slightly past one compounding period.
+        assert_eq!(accrued_interest(10_000_000, 3.65, "apr", "monthly", "actual/365", 31), 31_001);
+
+        // A 4% APY compounded monthly pays 4% over a full year, to the centavo.
+        let year: i64 = (0..12)
+            .scan(10_000_000, |balance, _| {
+                let interest = accrued_interest(*balance, 4.0, "apy", "monthly", "30/360", 30);
+                *balance += interest;
+                Some(interest)
+            })
+            .sum();
+        assert_eq!(year, 400_001);
+
+        assert_eq!(accrued_interest(-5_000, 10.0, "apr", "daily", "actual/365", 30), 0);
+        assert_eq!(withholding_tax(31_000, 20.0), 6_200);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::logic::csv_import::{detect_delimiter, detect_quote, parse_date, split_records};
+use crate::models::minor_unit_exponent;
+
+/// How the cost of units sold is taken out of a holding.
+pub const COST_BASIS_METHODS: [&str; 2] = ["fifo", "average"];
+
+/// Quantities below this are treated as zero, so fractional fund units do
+/// not leave dust behind after a full sale.
+const QUANTITY_EPSILON: f64 = 1e-9;
+
+/// `quantity` units at `price` (major units each) in minor units of
+/// `currency`, rounded half away from zero.
+pub fn units_value(quantity: f64, price: f64, currency: &str) -> i64 {
+    (quantity * price * 10f64.powi(minor_unit_exponent(currency) as i32)).round() as i64
+}
+
+/// A trade as far as cost tracking is concerned. `amount` is the cash that
+/// moved: paid for a buy, received for a sell or dividend, fees included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeEntry {
+    pub kind: String,
+    pub date: String,
+    pub quantity: f64,
+    pub amount: i64,
+}
+
+/// Units still held from one purchase (or, for average cost, from the whole
+/// pool) and what they cost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenLot {
+    pub date: String,
+    pub quantity: f64,
+    pub cost: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Position {
+    pub quantity: f64,
+    pub cost_basis: i64,
+    pub realized_gain: i64,
+    pub dividends: i64,
+    /// Oldest first.
+    pub lots: Vec<OpenLot>,
+}
+
+/// Replays one security's trades (oldest first) into the units held, their
+/// cost and the gains realized by sales. FIFO sells the oldest lots first;
+/// average cost keeps a single pool, so every unit costs the same.
+pub fn build_position(trades: &[TradeEntry], method: &str) -> Result<Position, String> {
+    if !COST_BASIS_METHODS.contains(&method) {
+        return Err(format!("Unknown cost basis method \"{}\"", method));
+    }
+    let mut position = Position::default();
+    for trade in trades {
+        match trade.kind.as_str() {
+            "buy" => {
+                if method == "average" && !position.lots.is_empty() {
+                    let pool = &mut position.lots[0];
+                    pool.quantity += trade.quantity;
+                    pool.cost += trade.amount;
+                } else {
+                    position.lots.push(OpenLot {
+                        date: trade.date.clone(),
+                        quantity: trade.quantity,
+                        cost: trade.amount,
+                    });
+                }
+            }
+            "sell" => {
+                let held: f64 = position.lots.iter().map(|lot| lot.quantity).sum();
+                if trade.quantity > held + QUANTITY_EPSILON {
+                    return Err(format!("Sold {} units on {} but only {} were held", trade.quantity, trade.date, held));
+                }
+                let mut to_sell = trade.quantity;
+                let mut cost_sold = 0;
+                while to_sell > QUANTITY_EPSILON {
+                    let lot = &mut position.lots[0];
+                    let taken = to_sell.min(lot.quantity);
+                    let cost = if lot.quantity - taken <= QUANTITY_EPSILON {
+                        lot.cost
+                    } else {
+                        (lot.cost as f64 * taken / lot.quantity).round() as i64
+                    };
+                    lot.quantity -= taken;
+                    lot.cost -= cost;
+                    cost_sold += cost;
+                    to_sell -= taken;
+                    if lot.quantity <= QUANTITY_EPSILON {
+                        position.lots.remove(0);
+                    }
+                }
+                position.realized_gain += trade.amount - cost_sold;
+            }
+            "dividend" => position.dividends += trade.amount,
+            other => return Err(format!("Unknown trade kind \"{}\"", other)),
+        }
+    }
+    position.quantity = position.lots.iter().map(|lot| lot.quantity).sum();
+    position.cost_basis = position.lots.iter().map(|lot| lot.cost).sum();
+    Ok(position)
+}
+
+/// One `date,symbol,price` row of a price file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceRow {
+    pub date: String,
+    pub symbol: String,
+    pub price: f64,
+}
+
+/// Parses `date,symbol,price` rows. A header row is skipped; any other bad
+/// row fails the whole file with its line number.
+pub fn parse_prices_csv(text: &str) -> Result<Vec<PriceRow>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let records = split_records(text, detect_delimiter(text), detect_quote(text));
+    let mut rows = Vec::new();
+    for (index, (line, fields)) in records.into_iter().enumerate() {
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let parsed = (|| {
+            if fields.len() < 3 {
+                return Err("Expected date, symbol and price columns".to_string());
+            }
+            let price: f64 = fields[2]
+                .trim()
+                .replace(',', "")
+                .parse()
+                .map_err(|_| format!("\"{}\" is not a price", fields[2].trim()))?;
+            if !(price.is_finite() && price > 0.0) {
+                return Err("Prices must be positive".to_string());
+            }
+            let symbol = fields[1].trim().to_uppercase();
+            if symbol.is_empty() {
+                return Err("Missing symbol".to_string());
+            }
+            Ok(PriceRow {
+                date: parse_date(&fields[0], "YYYY-MM-DD")?,
+                symbol,
+                price,
+            })
+        })();
+        match parsed {
+            Ok(row) => rows.push(row),
+            Err(_) if index == 0 && fields.get(2).is_some_and(|f| f.trim().parse::<f64>().is_err()) => {}
+            Err(e) => return Err(format!("Line {}: {}", line, e)),
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(kind: &str, date: &str, quantity: f64, amount: i64) -> TradeEntry {
+        TradeEntry { kind: kind.into(), date: date.into(), quantity, amount }
+    }
+
+    #[test]
+    fn fifo_and_average_cost_realize_different_gains() {
+        let trades = vec![
+            trade("buy", "2025-01-10", 100.0, 1_000_000),
+            trade("buy", "2025-02-10", 100.0, 1_500_000),
+            trade("dividend", "2025-03-01", 0.0, 20_000),
+            trade("sell", "2025-04-10", 150.0, 2_700_000),
+        ];
+
+        let fifo = build_position(&trades, "fifo").unwrap();
+        assert_eq!(fifo.quantity, 50.0);
+        // Sold all of the first lot and half the second: 1,000,000 + 750,000.
+        assert_eq!(fifo.realized_gain, 2_700_000 - 1_750_000);
+        assert_eq!(fifo.cost_basis, 750_000);
+        assert_eq!(fifo.lots, vec![OpenLot { date: "2025-02-10".into(), quantity: 50.0, cost: 750_000 }]);
+        assert_eq!(fifo.dividends, 20_000);
+
+        let average = build_position(&trades, "average").unwrap();
+        // 150 units at the pooled 12,500 per unit.
+        assert_eq!(average.realized_gain, 2_700_000 - 1_875_000);
+        assert_eq!(average.cost_basis, 625_000);
+
+        let oversold = [trade("buy", "2025-01-10", 1.0, 100), trade("sell", "2025-01-11", 2.0, 300)];
+        assert!(build_position(&oversold, "fifo").is_err());
+        assert_eq!(units_value(12.5, 101.2345, "PHP"), 126_543);
+    }
+
+    #[test]
+    fn parses_price_files() {
+        let rows = parse_prices_csv("date,symbol,price\n2025-03-31,jfc,\"1,234.50\"\n2025-03-31,BDO,150\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], PriceRow { date: "2025-03-31".into(), symbol: "JFC".into(), price: 1234.5 });
+        assert!(parse_prices_csv("2025-03-31,BDO,-1\n").is_err());
+    }
+}
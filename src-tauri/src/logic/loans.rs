@@ -0,0 +1,97 @@
+/// How often loan payments fall due.
+pub const PAYMENT_FREQUENCIES: [&str; 4] = ["weekly", "biweekly", "monthly", "quarterly"];
+
+/// Longest schedule projected, so a payment that barely covers the interest
+/// cannot run forever.
+pub const MAX_SCHEDULE_ROWS: usize = 1200;
+
+pub fn periods_per_year(frequency: &str) -> Option<i64> {
+    match frequency {
+        "weekly" => Some(52),
+        "biweekly" => Some(26),
+        "monthly" => Some(12),
+        "quarterly" => Some(4),
+        _ => None,
+    }
+}
+
+/// Interest rate per payment period as a fraction, from an annual rate in
+/// percent.
+pub fn periodic_rate(annual_rate_percent: f64, periods_per_year: i64) -> f64 {
+    annual_rate_percent / 100.0 / periods_per_year as f64
+}
+
+/// Interest on `balance` for one period, in minor units.
+pub fn period_interest(balance: i64, rate: f64) -> i64 {
+    (balance.max(0) as f64 * rate).round() as i64
+}
+
+/// Level payment that repays `principal` over `payments` periods at `rate`
+/// per period (standard annuity formula), rounded up to a whole minor unit so
+/// the loan is never left short at the end of the term.
+pub fn level_payment(principal: i64, rate: f64, payments: i64) -> i64 {
+    if payments <= 0 {
+        return principal;
+    }
+    if rate == 0.0 {
+        return (principal + payments - 1) / payments;
+    }
+    let factor = (1.0 + rate).powi(payments as i32);
+    (principal as f64 * rate * factor / (factor - 1.0)).ceil() as i64
+}
+
+/// One projected payment: the total, its principal and interest parts, and
+/// the balance left afterwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Installment {
+    pub payment: i64,
+    pub principal: i64,
+    pub interest: i64,
+    pub balance: i64,
+}
+
+/// Payments needed to clear `balance` paying `payment` each period at `rate`.
+/// The last payment is only what is left. Fails when the payment does not
+/// cover a period's interest, since the balance would never go down.
+pub fn amortize(balance: i64, rate: f64, payment: i64) -> Result<Vec<Installment>, String> {
+    let mut rows = Vec::new();
+    let mut balance = balance;
+    while balance > 0 {
+        let interest = period_interest(balance, rate);
+        if payment <= interest {
+            return Err("The payment does not cover the interest, so the loan never pays off".into());
+        }
+        if rows.len() == MAX_SCHEDULE_ROWS {
+            return Err(format!("The loan takes more than {} payments to pay off", MAX_SCHEDULE_ROWS));
+        }
+        let paid = payment.min(balance + interest);
+        balance -= paid - interest;
+        rows.push(Installment { payment: paid, principal: paid - interest, interest, balance });
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amortization_repays_the_principal() {
+        // ₱100,000.00 at 12% a year over 12 monthly payments.
+        let rate = periodic_rate(12.0, periods_per_year("monthly").unwrap());
+        let payment = level_payment(10_000_000, rate, 12);
+        assert_eq!(payment, 888_488);
+
+        let rows = amortize(10_000_000, rate, payment).unwrap();
+        assert_eq!(rows.len(), 12);
+        assert_eq!(rows[0].interest, 100_000);
+        assert_eq!(rows[0].principal, 788_488);
+        assert_eq!(rows.last().unwrap().balance, 0);
+        assert_eq!(rows.iter().map(|r| r.principal).sum::<i64>(), 10_000_000);
+
+        // Paying more each period finishes sooner.
+        assert_eq!(amortize(10_000_000, rate, 1_500_000).unwrap().len(), 7);
+        assert_eq!(level_payment(1_000_000, 0.0, 3), 333_334);
+        assert!(amortize(10_000_000, rate, 100_000).is_err());
+    }
+}
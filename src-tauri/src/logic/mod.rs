@@ -1 +1,14 @@
+pub mod attachments;
 pub mod balance;
+pub mod credit_cards;
+pub mod csv_import;
+pub mod duplicates;
+pub mod fx;
+pub mod installments;
+pub mod interest;
+pub mod investments;
+pub mod loans;
+pub mod ofx_import;
+pub mod qif_import;
+pub mod rules;
+pub mod savings;
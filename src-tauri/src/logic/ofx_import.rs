@@ -0,0 +1,226 @@
+use crate::logic::csv_import::{parse_amount, parse_date};
+use crate::models::{minor_unit_exponent, ImportRow, Money};
+
+/// One `<STMTTRN>` as read from the file, before amounts are interpreted.
+#[derive(Debug, Default)]
+pub struct OfxTransaction {
+    pub fitid: Option<String>,
+    pub date: Option<String>,
+    pub amount: Option<String>,
+    pub name: Option<String>,
+    pub memo: Option<String>,
+}
+
+/// A bank (`STMTRS`) or credit card (`CCSTMTRS`) statement.
+#[derive(Debug, Default)]
+pub struct OfxStatement {
+    pub account_id: Option<String>,
+    pub currency: Option<String>,
+    pub transactions: Vec<OfxTransaction>,
+}
+
+enum Token {
+    Open(String),
+    Close(String),
+    Text(String),
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Splits OFX into tags and text. Works for both SGML (v1, leaf elements left
+/// unclosed) and XML (v2); headers, declarations and processing instructions
+/// are dropped.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let before = rest[..start].trim();
+        if !before.is_empty() {
+            tokens.push(Token::Text(decode_entities(before)));
+        }
+        let Some(len) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = rest[start + 1..start + len].trim();
+        rest = &rest[start + len + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        let name = tag
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match name.strip_prefix('/') {
+            Some(closed) => tokens.push(Token::Close(closed.to_string())),
+            None => tokens.push(Token::Open(name)),
+        }
+    }
+    tokens
+}
+
+/// Reads every statement in an OFX/QFX file.
+pub fn parse_ofx(text: &str) -> Result<Vec<OfxStatement>, String> {
+    if !text.to_ascii_uppercase().contains("<OFX>") {
+        return Err("Not an OFX file".into());
+    }
+    let tokens = tokenize(text);
+    let mut statements = Vec::new();
+    let mut statement: Option<OfxStatement> = None;
+    let mut transaction: Option<OfxTransaction> = None;
+
+    let mut iter = tokens.iter().peekable();
+    while let Some(token) = iter.next() {
+        match token {
+            Token::Open(tag) if tag == "STMTRS" || tag == "CCSTMTRS" => {
+                statement = Some(OfxStatement::default());
+            }
+            Token::Close(tag) if tag == "STMTRS" || tag == "CCSTMTRS" => {
+                statements.extend(statement.take());
+            }
+            Token::Open(tag) if tag == "STMTTRN" => {
+                transaction = Some(OfxTransaction::default());
+            }
+            Token::Close(tag) if tag == "STMTTRN" => {
+                if let (Some(s), Some(t)) = (statement.as_mut(), transaction.take()) {
+                    s.transactions.push(t);
+                }
+            }
+            Token::Open(tag) => {
+                let Some(Token::Text(value)) = iter.peek() else {
+                    continue;
+                };
+                let value = Some(value.clone());
+                match (transaction.as_mut(), statement.as_mut()) {
+                    (Some(t), _) => match tag.as_str() {
+                        "FITID" => t.fitid = value,
+                        "DTPOSTED" => t.date = value,
+                        "TRNAMT" => t.amount = value,
+                        "NAME" => t.name = value,
+                        "MEMO" => t.memo = value,
+                        _ => {}
+                    },
+                    (None, Some(s)) => match tag.as_str() {
+                        "CURDEF" => s.currency = value,
+                        "ACCTID" => s.account_id = value,
+                        _ => {}
+                    },
+                    (None, None) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if statements.is_empty() {
+        return Err("The OFX file contains no statements".into());
+    }
+    Ok(statements)
+}
+
+/// Converts a statement's transactions into import rows in `currency`.
+/// The FITID becomes the row's external id.
+pub fn statement_rows(statement: &OfxStatement, currency: &str) -> Vec<ImportRow> {
+    let exponent = minor_unit_exponent(currency);
+    statement
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let parsed = (|| {
+                // DTPOSTED is YYYYMMDD optionally followed by time and zone.
+                let raw_date = t.date.as_deref().ok_or("Missing DTPOSTED")?;
+                let date = parse_date(raw_date.get(..8).unwrap_or(raw_date), "YYYYMMDD")?;
+                let raw_amount = t.amount.as_deref().ok_or("Missing TRNAMT")?;
+                let decimal = if raw_amount.contains(',') && !raw_amount.contains('.') { ',' } else { '.' };
+                let amount = parse_amount(raw_amount, decimal, exponent)?.ok_or("Missing TRNAMT")?;
+                if amount == 0 {
+                    return Err("Amount is zero".to_string());
+                }
+                Ok((date, amount))
+            })();
+            let description = t.name.clone().or_else(|| t.memo.clone());
+            match parsed {
+                Ok((date, amount)) => ImportRow {
+                    line: i as i64 + 1,
+                    date: Some(date),
+                    description,
+                    tx_type: Some(if amount < 0 { "expense" } else { "income" }.to_string()),
+                    amount: Some(Money::new(amount.abs(), currency)),
+                    external_id: t.fitid.clone(),
+                    duplicate_of: None,
+                    error: None,
+                },
+                Err(error) => ImportRow {
+                    line: i as i64 + 1,
+                    date: None,
+                    description,
+                    tx_type: None,
+                    amount: None,
+                    external_id: t.fitid.clone(),
+                    duplicate_of: None,
+                    error: Some(error),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sgml_v1_with_unclosed_leaves() {
+        let text = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\n\n\
+            <OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><CURDEF>PHP\n\
+            <BANKACCTFROM><BANKID>010\n<ACCTID>1234567890\n<ACCTTYPE>SAVINGS\n</BANKACCTFROM>\n\
+            <BANKTRANLIST>\n\
+            <STMTTRN><TRNTYPE>DEBIT\n<DTPOSTED>20250105120000[+8:PHT]\n<TRNAMT>-1,250.00\n\
+            <FITID>A1\n<NAME>JOLLIBEE &amp; CO\n</STMTTRN>\n\
+            <STMTTRN><TRNTYPE>CREDIT\n<DTPOSTED>20250106\n<TRNAMT>45000.00\n<FITID>A2\n<MEMO>PAYROLL\n</STMTTRN>\n\
+            </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        let statements = parse_ofx(text).unwrap();
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].account_id.as_deref(), Some("1234567890"));
+        assert_eq!(statements[0].currency.as_deref(), Some("PHP"));
+
+        let rows = statement_rows(&statements[0], "PHP");
+        assert_eq!(rows[0].date.as_deref(), Some("2025-01-05"));
+        assert_eq!(rows[0].description.as_deref(), Some("JOLLIBEE & CO"));
+        assert_eq!(rows[0].amount, Some(Money::new(125000, "PHP")));
+        assert_eq!(rows[0].tx_type.as_deref(), Some("expense"));
+        assert_eq!(rows[1].external_id.as_deref(), Some("A2"));
+        assert_eq!(rows[1].description.as_deref(), Some("PAYROLL"));
+    }
+
+    #[test]
+    fn parses_xml_v2_credit_card_statement() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <?OFX OFXHEADER="200" VERSION="220"?>
+            <OFX><CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>
+              <CURDEF>USD</CURDEF>
+              <CCACCTFROM><ACCTID>4111XXXX1111</ACCTID></CCACCTFROM>
+              <BANKTRANLIST>
+                <STMTTRN>
+                  <TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20250201</DTPOSTED>
+                  <TRNAMT>-19.99</TRNAMT><FITID>X9</FITID><NAME>NETFLIX.COM</NAME>
+                </STMTTRN>
+              </BANKTRANLIST>
+            </CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1></OFX>"#;
+        let statements = parse_ofx(text).unwrap();
+        assert_eq!(statements[0].account_id.as_deref(), Some("4111XXXX1111"));
+        let rows = statement_rows(&statements[0], "USD");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].amount, Some(Money::new(1999, "USD")));
+        assert_eq!(rows[0].external_id.as_deref(), Some("X9"));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::logic::csv_import::{parse_amount, parse_date};
+use crate::models::{minor_unit_exponent, ImportRow, Money};
+
+/// Parses the date styles Quicken and banks emit (`1/5/2025`, `01/05'25`,
+/// `1/ 5/25`, `2025-01-05`). `day_first` reads `D/M/Y` instead of `M/D/Y`.
+fn parse_qif_date(value: &str, day_first: bool) -> Result<String, String> {
+    let cleaned: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let parts: Vec<&str> = cleaned.split(['/', '-', '.', '\'']).collect();
+    let invalid = || format!("\"{}\" is not a valid date", value.trim());
+    if parts.len() != 3 {
+        return Err(invalid());
+    }
+    let numbers: Vec<i64> = parts
+        .iter()
+        .map(|p| p.parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+
+    let (year, month, day) = if parts[0].len() == 4 {
+        (numbers[0], numbers[1], numbers[2])
+    } else {
+        let year = match parts[2].len() {
+            4 => numbers[2],
+            2 if numbers[2] < 70 => 2000 + numbers[2],
+            2 => 1900 + numbers[2],
+            _ => return Err(invalid()),
+        };
+        if day_first {
+            (year, numbers[1], numbers[0])
+        } else {
+            (year, numbers[0], numbers[1])
+        }
+    };
+    parse_date(&format!("{:04}-{:02}-{:02}", year, month, day), "YYYY-MM-DD")
+}
+
+/// Reads the transactions of a QIF file into import rows in `currency`.
+/// Split lines (`S`/`$`) are ignored; the record total is imported.
+pub fn parse_qif(text: &str, day_first: bool, currency: &str) -> Result<Vec<ImportRow>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    if !text.trim_start().starts_with('!') {
+        return Err("Not a QIF file".into());
+    }
+    let exponent = minor_unit_exponent(currency);
+    let mut rows = Vec::new();
+
+    let mut start_line = 0i64;
+    let mut date: Option<&str> = None;
+    let mut amount: Option<&str> = None;
+    let mut payee: Option<&str> = None;
+    let mut memo: Option<&str> = None;
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim_end();
+        let Some(code) = line.chars().next() else {
+            continue;
+        };
+        let value = line[code.len_utf8()..].trim();
+        if start_line == 0 && code != '!' && code != '^' {
+            start_line = i as i64 + 1;
+        }
+        match code {
+            'D' => date = Some(value),
+            'T' | 'U' => amount = Some(value),
+            'P' => payee = Some(value).filter(|v| !v.is_empty()),
+            'M' => memo = Some(value).filter(|v| !v.is_empty()),
+            '^' => {
+                if start_line != 0 {
+                    let parsed = (|| {
+                        let date = parse_qif_date(date.ok_or("Missing date")?, day_first)?;
+                        let amount = parse_amount(amount.ok_or("Missing amount")?, '.', exponent)?
+                            .ok_or("Missing amount")?;
+                        if amount == 0 {
+                            return Err("Amount is zero".to_string());
+                        }
+                        Ok((date, amount))
+                    })();
+                    let description = payee.or(memo).map(String::from);
+                    rows.push(match parsed {
+                        Ok((date, amount)) => ImportRow {
+                            line: start_line,
+                            date: Some(date),
+                            description,
+                            tx_type: Some(if amount < 0 { "expense" } else { "income" }.to_string()),
+                            amount: Some(Money::new(amount.abs(), currency)),
+                            external_id: None,
+                            duplicate_of: None,
+                            error: None,
+                        },
+                        Err(error) => ImportRow {
+                            line: start_line,
+                            date: None,
+                            description,
+                            tx_type: None,
+                            amount: None,
+                            external_id: None,
+                            duplicate_of: None,
+                            error: Some(error),
+                        },
+                    });
+                }
+                start_line = 0;
+                date = None;
+                amount = None;
+                payee = None;
+                memo = None;
+            }
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bank_records() {
+        let text = "!Type:Bank\n\
+                    D1/ 5'25\nT-1,250.00\nPJOLLIBEE #12\nMLunch\n^\n\
+                    D01/06/2025\nU45,000.00\nT45,000.00\nMPayroll\nLSalary\n^\n\
+                    D13/06/2025\nT10.00\n^\n";
+        let rows = parse_qif(text, false, "PHP").unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].date.as_deref(), Some("2025-01-05"));
+        assert_eq!(rows[0].description.as_deref(), Some("JOLLIBEE #12"));
+        assert_eq!(rows[0].amount, Some(Money::new(125000, "PHP")));
+        assert_eq!(rows[1].tx_type.as_deref(), Some("income"));
+        assert_eq!(rows[1].description.as_deref(), Some("Payroll"));
+        assert!(rows[2].error.is_some());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::models::{Rule, RuleChange, Transaction};
+
+fn contains_ignore_case(haystack: Option<&str>, needle: &str) -> bool {
+    haystack.is_some_and(|h| h.to_lowercase().contains(&needle.to_lowercase()))
+}
+
+/// True when every condition set on `rule` holds for `tx`.
+pub fn rule_matches(rule: &Rule, tx: &Transaction) -> bool {
+    if let Some(ref needle) = rule.notes_contains {
+        if !contains_ignore_case(tx.notes.as_deref(), needle) {
+            return false;
+        }
+    }
+    if let Some(ref needle) = rule.payee_contains {
+        if !contains_ignore_case(tx.payee_name.as_deref(), needle) {
+            return false;
+        }
+    }
+    if let Some(ref min) = rule.amount_min {
+        if min.currency != tx.amount.currency || tx.amount.minor < min.minor {
+            return false;
+        }
+    }
+    if let Some(ref max) = rule.amount_max {
+        if max.currency != tx.amount.currency || tx.amount.minor > max.minor {
+            return false;
+        }
+    }
+    if rule.account_id.is_some_and(|id| id != tx.account_id) {
+        return false;
+    }
+    if rule.tx_type.as_ref().is_some_and(|t| *t != tx.tx_type) {
+        return false;
+    }
+    true
+}
+
+/// Works out what `rules` (already in priority order) would change on `tx`.
+/// Each field is set by the first matching rule that has an action for it;
+/// tags accumulate across rules. Fields the transaction already has are kept
+/// unless `overwrite` is set. Transfer legs are never touched, and split
+/// transactions keep their per-line categories.
+pub fn plan_changes(rules: &[Rule], tx: &Transaction, overwrite: bool) -> Option<RuleChange> {
+    if tx.transfer_id.is_some() {
+        return None;
+    }
+
+    let mut change = RuleChange {
+        transaction: tx.clone(),
+        rule_ids: Vec::new(),
+        category_id: None,
+        payee_id: None,
+        notes: None,
+        add_tag_ids: Vec::new(),
+    };
+    let mut category_open = tx.is_split == 0 && (overwrite || tx.category_id.is_none());
+    let mut payee_open = overwrite || tx.payee_id.is_none();
+    let mut notes_open = overwrite || tx.notes.as_deref().unwrap_or("").trim().is_empty();
+
+    for rule in rules.iter().filter(|r| r.is_active != 0 && rule_matches(r, tx)) {
+        let mut used = false;
+        if let Some(category_id) = rule.set_category_id.filter(|_| category_open) {
+            category_open = false;
+            if tx.category_id != Some(category_id) {
+                change.category_id = Some(category_id);
+                used = true;
+            }
+        }
+        if let Some(payee_id) = rule.set_payee_id.filter(|_| payee_open) {
+            payee_open = false;
+            if tx.payee_id != Some(payee_id) {
+                change.payee_id = Some(payee_id);
+                used = true;
+            }
+        }
+        if let Some(notes) = rule.set_notes.as_ref().filter(|_| notes_open) {
+            notes_open = false;
+            if tx.notes.as_ref() != Some(notes) {
+                change.notes = Some(notes.clone());
+                used = true;
+            }
+        }
+        for &tag_id in &rule.tag_ids {
+            if !tx.tag_ids.contains(&tag_id) && !change.add_tag_ids.contains(&tag_id) {
+                change.add_tag_ids.push(tag_id);
+                used = true;
+            }
+        }
+        if used {
+            change.rule_ids.push(rule.id);
+        }
+    }
+
+    if change.rule_ids.is_empty() {
+        None
+    } else {
+        Some(change)
+    }
+}
@@ -0,0 +1,121 @@
+/// Savings instruments a plan can describe.
+pub const SAVINGS_KINDS: [&str; 3] = ["mp2", "time_deposit", "other"];
+
+/// How often a plan expects a contribution.
+pub const CONTRIBUTION_FREQUENCIES: [&str; 4] = ["once", "monthly", "quarterly", "annually"];
+
+/// Whether credited dividends stay in the plan or are paid out each year.
+pub const PAYOUT_MODES: [&str; 2] = ["compounding", "annual_payout"];
+
+/// Longest lock-in accepted, in months.
+pub const MAX_TERM_MONTHS: i64 = 600;
+
+/// Months from the start (0 is the start month) in which a contribution is
+/// due over a term of `term_months`.
+pub fn contribution_months(frequency: &str, term_months: i64) -> Vec<i64> {
+    let step = match frequency {
+        "once" => return vec![0],
+        "quarterly" => 3,
+        "annually" => 12,
+        _ => 1,
+    };
+    (0..term_months).step_by(step).collect()
+}
+
+/// One plan year counted from the start, or the part-year before maturity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectedYear {
+    /// Months from the start to the end of this year.
+    pub months: i64,
+    pub contributions: i64,
+    pub dividend: i64,
+    /// Balance after the dividend is credited (and paid out, if it is).
+    pub balance: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Projection {
+    pub years: Vec<ProjectedYear>,
+    pub contributions: i64,
+    pub dividends: i64,
+    /// Dividends paid out of the plan along the way.
+    pub paid_out: i64,
+    /// Balance handed back at maturity.
+    pub maturity_value: i64,
+}
+
+/// Projects a plan month by month. `contributions[k]` is paid in at the
+/// start of month `k` and earns from then; `rates[k]` is the annual rate in
+/// percent for that month. Each month earns a twelfth of its rate on the
+/// balance, simple within the year; the year's dividend is credited on each
+/// anniversary and at maturity, then added to the balance when compounding
+/// or paid out otherwise.
+pub fn project(contributions: &[i64], rates: &[f64], compounding: bool) -> Projection {
+    let term = contributions.len().min(rates.len());
+    let mut projection = Projection::default();
+    let mut balance = 0;
+    let mut accrued = 0.0;
+    let mut year_contributions = 0;
+    for (month, (&contribution, &rate)) in contributions.iter().zip(rates).enumerate() {
+        balance += contribution;
+        year_contributions += contribution;
+        accrued += balance as f64 * rate / 100.0 / 12.0;
+        if (month + 1) % 12 == 0 || month + 1 == term {
+            let dividend = accrued.round() as i64;
+            if compounding {
+                balance += dividend;
+            } else {
+                projection.paid_out += dividend;
+            }
+            projection.contributions += year_contributions;
+            projection.dividends += dividend;
+            projection.years.push(ProjectedYear {
+                months: month as i64 + 1,
+                contributions: year_contributions,
+                dividend,
+                balance,
+            });
+            accrued = 0.0;
+            year_contributions = 0;
+        }
+    }
+    projection.maturity_value = balance;
+    projection
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compounding_beats_annual_payout() {
+        // ₱1,000.00 a month for two years at 7% a year.
+        let months = contribution_months("monthly", 24);
+        assert_eq!(months.len(), 24);
+        assert_eq!(contribution_months("quarterly", 12), vec![0, 3, 6, 9]);
+        assert_eq!(contribution_months("once", 60), vec![0]);
+
+        let contributions = vec![100_000; 24];
+        let rates = vec![7.0; 24];
+        let compounding = project(&contributions, &rates, true);
+        assert_eq!(compounding.years.len(), 2);
+        // First year: 7%/12 on 1,000 + 2,000 + ... + 12,000.
+        assert_eq!(compounding.years[0].dividend, 45_500);
+        assert_eq!(compounding.years[0].balance, 1_245_500);
+        assert_eq!(compounding.contributions, 2_400_000);
+        assert_eq!(compounding.maturity_value, compounding.contributions + compounding.dividends);
+
+        let payout = project(&contributions, &rates, false);
+        assert_eq!(payout.years[0].dividend, 45_500);
+        assert_eq!(payout.maturity_value, 2_400_000);
+        assert_eq!(payout.paid_out, payout.dividends);
+        assert!(compounding.dividends > payout.dividends);
+
+        // A 9-month time deposit credits its interest once, at maturity.
+        let mut deposit = vec![0; 9];
+        deposit[0] = 10_000_000;
+        let matured = project(&deposit, &[4.0; 9], true);
+        assert_eq!(matured.years.len(), 1);
+        assert_eq!(matured.maturity_value, 10_300_000);
+    }
+}
@@ -1,3 +1,7 @@
+pub mod money;
+
+pub use money::{minor_unit_exponent, normalize_currency, Money, DEFAULT_CURRENCY};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -8,13 +12,15 @@ pub struct Account {
     pub account_type: String,
     pub subtype: Option<String>,
     pub currency: String,
-    pub opening_balance: f64,
-    pub credit_limit: Option<f64>,
+    pub opening_balance: Money,
+    pub credit_limit: Option<Money>,
     pub billing_cycle_day: Option<i64>,
     pub payment_due_day: Option<i64>,
     pub is_active: i64,
     pub created_at: String,
     pub updated_at: String,
+    /// Bank account id from OFX downloads (`ACCTID`), used to route imports.
+    pub ofx_account_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,14 +31,15 @@ pub struct AccountWithBalance {
     pub account_type: String,
     pub subtype: Option<String>,
     pub currency: String,
-    pub opening_balance: f64,
-    pub credit_limit: Option<f64>,
+    pub opening_balance: Money,
+    pub credit_limit: Option<Money>,
     pub billing_cycle_day: Option<i64>,
     pub payment_due_day: Option<i64>,
     pub is_active: i64,
     pub created_at: String,
     pub updated_at: String,
-    pub balance: f64,
+    pub ofx_account_id: Option<String>,
+    pub balance: Money,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -43,6 +50,8 @@ pub struct Category {
     pub icon: Option<String>,
     pub is_archived: i64,
     pub created_at: String,
+    /// None for a top-level category.
+    pub parent_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,19 +60,613 @@ pub struct Transaction {
     pub account_id: i64,
     pub category_id: Option<i64>,
     pub transfer_id: Option<i64>,
+    pub payee_id: Option<i64>,
     #[serde(rename = "type")]
     pub tx_type: String,
-    pub amount: f64,
+    pub amount: Money,
     pub date: String,
     pub notes: Option<String>,
     pub is_recurring: i64,
     pub recurrence_frequency: Option<String>,
     pub next_due_date: Option<String>,
+    pub import_batch_id: Option<i64>,
+    /// Earlier transaction this one probably repeats, pending review.
+    pub duplicate_of: Option<i64>,
+    /// 'uncleared', 'cleared' or 'reconciled'. Reconciled rows are locked.
+    pub status: String,
+    pub reconciliation_id: Option<i64>,
+    pub installment_plan_id: Option<i64>,
+    /// 'conversion', 'principal', 'interest' or 'fee' on installment plan rows.
+    pub installment_kind: Option<String>,
+    /// Investment trade this row settles the cash of.
+    pub trade_id: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
     // Joined fields
     pub category_name: Option<String>,
     pub account_name: Option<String>,
+    pub payee_name: Option<String>,
+    pub is_split: i64,
+    pub tag_ids: Vec<i64>,
+    pub attachment_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransactionSplit {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub category_id: Option<i64>,
+    pub amount: Money,
+    pub memo: Option<String>,
+    pub created_at: String,
+    // Joined fields
+    pub category_name: Option<String>,
+}
+
+/// One line of a split as submitted by the frontend.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransactionSplitInput {
+    pub category_id: Option<i64>,
+    pub amount: Money,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Payee {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub transaction_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PayeeAlias {
+    pub id: i64,
+    pub payee_id: i64,
+    pub pattern: String,
+    pub created_at: String,
+}
+
+/// A categorization rule. Unset conditions match anything; unset actions do
+/// nothing. Amount bounds are inclusive and only match transactions in the
+/// same currency.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Rule {
+    pub id: i64,
+    pub name: String,
+    pub priority: i64,
+    pub is_active: i64,
+    // Conditions
+    pub notes_contains: Option<String>,
+    pub payee_contains: Option<String>,
+    pub amount_min: Option<Money>,
+    pub amount_max: Option<Money>,
+    pub account_id: Option<i64>,
+    pub tx_type: Option<String>,
+    // Actions
+    pub set_category_id: Option<i64>,
+    pub set_payee_id: Option<i64>,
+    pub set_notes: Option<String>,
+    pub tag_ids: Vec<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Rule fields as submitted by the frontend.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RuleInput {
+    pub name: String,
+    pub priority: i64,
+    pub is_active: bool,
+    pub notes_contains: Option<String>,
+    pub payee_contains: Option<String>,
+    pub amount_min: Option<Money>,
+    pub amount_max: Option<Money>,
+    pub account_id: Option<i64>,
+    pub tx_type: Option<String>,
+    pub set_category_id: Option<i64>,
+    pub set_payee_id: Option<i64>,
+    pub set_notes: Option<String>,
+    pub tag_ids: Vec<i64>,
+}
+
+/// The edits rules would make to one transaction. `None` fields are left as is.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RuleChange {
+    pub transaction: Transaction,
+    pub rule_ids: Vec<i64>,
+    pub category_id: Option<i64>,
+    pub payee_id: Option<i64>,
+    pub notes: Option<String>,
+    pub add_tag_ids: Vec<i64>,
+}
+
+/// Transactions that probably record the same purchase, for review.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateCluster {
+    pub transactions: Vec<Transaction>,
+    /// Highest pair score inside the cluster (0-100).
+    pub score: i64,
+    /// Row to keep when merging: the one with the most detail attached.
+    pub suggested_keep_id: i64,
+}
+
+/// One unit of `base` was worth `rate` units of `quote` from `date` until
+/// the pair's next rate.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExchangeRate {
+    pub id: i64,
+    pub base: String,
+    pub quote: String,
+    pub rate: f64,
+    pub date: String,
+    /// 'manual' or 'csv'.
+    pub source: String,
+    pub created_at: String,
+}
+
+/// One closed billing cycle of a credit card.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CardStatement {
+    pub period_start: String,
+    pub closing_date: String,
+    pub due_date: String,
+    /// Amount owed at closing.
+    pub statement_balance: Money,
+    pub minimum_payment: Money,
+    /// `credit_payment` transfers received after closing.
+    pub payments: Money,
+    pub amount_remaining: Money,
+    /// 'paid', 'due' or 'overdue'.
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreditCardSummary {
+    pub account_id: i64,
+    /// Amount owed right now.
+    pub balance: Money,
+    pub credit_limit: Option<Money>,
+    /// Limit less the balance and the installment principal not yet billed.
+    pub available_credit: Option<Money>,
+    /// Unbilled installment principal, which still holds part of the limit.
+    pub installment_principal: Money,
+    /// Balance plus unbilled installment principal, as a percentage of the limit.
+    pub utilization: Option<f64>,
+    pub next_closing_date: String,
+    /// Newest first.
+    pub statements: Vec<CardStatement>,
+}
+
+/// A card whose latest statement still has an amount due.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CardPaymentDue {
+    pub account_id: i64,
+    pub account_name: String,
+    pub statement: CardStatement,
+    /// Unpaid part of the previous statement once its due date has passed.
+    /// It is already included in the latest statement balance.
+    pub past_due: Money,
+    /// Negative once the due date has passed.
+    pub days_until_due: i64,
+}
+
+/// A card purchase converted into fixed monthly installments.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallmentPlan {
+    pub id: i64,
+    pub account_id: i64,
+    pub purchase_transaction_id: Option<i64>,
+    pub description: Option<String>,
+    pub principal: Money,
+    pub term_months: i64,
+    /// Add-on interest per month, as a percentage of the original principal.
+    pub monthly_add_on_rate: f64,
+    pub processing_fee: Money,
+    /// Category for the interest and fee charges.
+    pub category_id: Option<i64>,
+    /// Day the first installment is billed.
+    pub start_date: String,
+    /// 'active', 'paid_off' or 'completed'.
+    pub status: String,
+    pub paid_off_date: Option<String>,
+    pub created_at: String,
+    // Computed fields
+    /// Principal plus interest billed in a regular month.
+    pub monthly_payment: Money,
+    pub total_interest: Money,
+    pub installments_posted: i64,
+    pub remaining_principal: Money,
+    pub next_installment_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallmentPlanInput {
+    pub purchase_transaction_id: i64,
+    /// Defaults to the full purchase amount.
+    pub principal: Option<Money>,
+    pub term_months: i64,
+    pub monthly_add_on_rate: f64,
+    pub processing_fee: Option<Money>,
+    pub category_id: Option<i64>,
+    /// Defaults to the card's next closing date after the purchase.
+    pub start_date: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Repayment terms of a loan account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Loan {
+    pub account_id: i64,
+    /// Amount originally borrowed.
+    pub principal: Money,
+    /// Percent per year.
+    pub annual_rate: f64,
+    /// Number of payments over the full term.
+    pub term_payments: i64,
+    /// 'weekly', 'biweekly', 'monthly' or 'quarterly'.
+    pub payment_frequency: String,
+    pub first_payment_date: String,
+    /// Category the interest part of each payment is posted under.
+    pub interest_category_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+    // Computed fields
+    /// Level payment that repays the principal over the term.
+    pub regular_payment: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoanTermsInput {
+    pub principal: Money,
+    pub annual_rate: f64,
+    pub term_payments: i64,
+    pub payment_frequency: String,
+    pub first_payment_date: String,
+    pub interest_category_id: Option<i64>,
+}
+
+/// A payment into a loan, split into the interest charged and the
+/// principal repaid.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoanPayment {
+    pub transfer_id: i64,
+    pub date: String,
+    pub amount: Money,
+    pub principal: Money,
+    pub interest: Money,
+}
+
+/// One line of an amortization schedule: a payment already made, or a
+/// projected one.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AmortizationRow {
+    pub date: String,
+    pub payment: Money,
+    pub principal: Money,
+    pub interest: Money,
+    /// Amount owed after this payment.
+    pub balance: Money,
+    pub paid: bool,
+}
+
+/// A loan's payments so far followed by the projection that pays off what is
+/// left at the regular payment.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoanSchedule {
+    pub loan: Loan,
+    /// Amount owed right now.
+    pub balance: Money,
+    pub rows: Vec<AmortizationRow>,
+    pub remaining_payments: i64,
+    pub payoff_date: Option<String>,
+    /// Interest paid so far plus projected interest.
+    pub total_interest: Money,
+}
+
+/// Terms of a savings instrument (MP2, a time deposit) held in a savings
+/// account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavingsPlan {
+    pub account_id: i64,
+    /// 'mp2', 'time_deposit' or 'other'.
+    pub kind: String,
+    pub start_date: String,
+    /// Lock-in; the plan matures this many months after the start.
+    pub term_months: i64,
+    pub contribution_amount: Money,
+    /// 'once', 'monthly', 'quarterly' or 'annually'.
+    pub contribution_frequency: String,
+    /// Percent per year, used for years with no declared rate.
+    pub annual_rate: f64,
+    /// 'compounding' or 'annual_payout'.
+    pub payout_mode: String,
+    /// Where paid-out dividends go; the plan's own account when unset.
+    pub payout_account_id: Option<i64>,
+    pub dividend_category_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+    // Computed fields
+    pub maturity_date: String,
+    /// Rates declared per calendar year, oldest first.
+    pub rates: Vec<SavingsRate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavingsRate {
+    pub year: i64,
+    /// Percent per year.
+    pub rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavingsPlanInput {
+    pub kind: String,
+    pub start_date: String,
+    pub term_months: i64,
+    pub contribution_amount: Money,
+    pub contribution_frequency: String,
+    pub annual_rate: f64,
+    pub payout_mode: String,
+    pub payout_account_id: Option<i64>,
+    pub dividend_category_id: Option<i64>,
+}
+
+/// A dividend or interest credit recorded against a plan.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavingsDividend {
+    pub transaction_id: i64,
+    /// Account the income was posted to: the plan's, or its payout account.
+    pub account_id: i64,
+    pub date: String,
+    pub amount: Money,
+}
+
+/// One plan year of a projection, assuming every contribution is made.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectedSavingsYear {
+    pub end_date: String,
+    pub contributions: Money,
+    pub dividend: Money,
+    /// Balance after the dividend is credited or paid out.
+    pub balance: Money,
+}
+
+/// A scheduled contribution checked against deposits made by its due date.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContributionCheck {
+    pub date: String,
+    pub expected: Money,
+    pub cumulative_expected: Money,
+    /// Opening balance plus deposits other than dividends, up to the date.
+    pub cumulative_paid: Money,
+    pub shortfall: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavingsProjection {
+    pub plan: SavingsPlan,
+    pub as_of: String,
+    pub balance: Money,
+    pub years: Vec<ProjectedSavingsYear>,
+    pub total_contributions: Money,
+    pub total_dividends: Money,
+    /// Dividends paid out of the plan before maturity.
+    pub paid_out: Money,
+    pub maturity_value: Money,
+    pub credited: Vec<SavingsDividend>,
+    pub credited_total: Money,
+    /// Contributions due up to `as_of`.
+    pub contributions: Vec<ContributionCheck>,
+    /// How far deposits trail the plan as of `as_of`.
+    pub shortfall: Money,
+}
+
+/// How an account earns interest, or how a card charges it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterestSettings {
+    pub account_id: i64,
+    /// Percent per year.
+    pub rate: f64,
+    /// 'apr' or 'apy'.
+    pub rate_type: String,
+    /// 'actual/365', 'actual/360' or '30/360'.
+    pub day_count: String,
+    /// 'daily', 'monthly', 'quarterly' or 'annually'.
+    pub compounding: String,
+    /// Percent of gross interest withheld at source.
+    pub withholding_tax_rate: f64,
+    pub category_id: Option<i64>,
+    pub tax_category_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterestSettingsInput {
+    pub rate: f64,
+    pub rate_type: String,
+    pub day_count: String,
+    pub compounding: String,
+    pub withholding_tax_rate: f64,
+    pub category_id: Option<i64>,
+    pub tax_category_id: Option<i64>,
+}
+
+/// Interest or a finance charge worked out for one account and period.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterestAccrual {
+    pub account_id: i64,
+    pub account_name: String,
+    /// 'interest' or 'finance_charge'.
+    pub kind: String,
+    pub period_start: String,
+    pub period_end: String,
+    pub average_daily_balance: Money,
+    /// Days charged under the account's day count.
+    pub days: i64,
+    pub gross: Money,
+    pub withholding_tax: Money,
+    pub net: Money,
+    /// 'preview', 'posted' or 'already_posted'.
+    pub status: String,
+    pub transaction_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Security {
+    pub id: i64,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub currency: String,
+    pub created_at: String,
+}
+
+/// Price of one unit of a security from `date` until its next price.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecurityPrice {
+    pub id: i64,
+    pub security_id: i64,
+    pub date: String,
+    /// Major units of the security's currency; may carry more decimals than it.
+    pub price: f64,
+    /// 'manual' or 'csv'.
+    pub source: String,
+    pub created_at: String,
+}
+
+/// A buy, sell or dividend in an investment account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvestmentTrade {
+    pub id: i64,
+    pub account_id: i64,
+    pub security_id: i64,
+    /// 'buy', 'sell' or 'dividend'.
+    pub kind: String,
+    pub date: String,
+    pub quantity: f64,
+    /// Per unit, in major units.
+    pub price: f64,
+    pub fees: Money,
+    /// Cash paid for a buy, or received for a sell or dividend, fees included.
+    pub amount: Money,
+    pub notes: Option<String>,
+    pub created_at: String,
+    // Joined fields
+    pub symbol: String,
+    /// Cash transaction posted for the trade.
+    pub transaction_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TradeInput {
+    pub account_id: i64,
+    pub security_id: i64,
+    pub kind: String,
+    pub date: String,
+    /// Units bought or sold; ignored for dividends.
+    pub quantity: f64,
+    /// Per unit, in major units; ignored for dividends.
+    pub price: f64,
+    pub fees: Option<Money>,
+    /// Dividend received; buys and sells compute theirs.
+    pub amount: Option<Money>,
+    /// Income category for dividends.
+    pub category_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// Units still held from one purchase (or the whole pool, for average cost).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HoldingLot {
+    pub date: String,
+    pub quantity: f64,
+    pub cost: Money,
+}
+
+/// One security held in an investment account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Holding {
+    pub security_id: i64,
+    pub symbol: String,
+    pub name: Option<String>,
+    pub quantity: f64,
+    pub cost_basis: Money,
+    /// Latest price on or before the valuation date (or the last trade
+    /// price when none was entered).
+    pub price: Option<f64>,
+    pub price_date: Option<String>,
+    pub market_value: Money,
+    pub unrealized_gain: Money,
+    pub realized_gain: Money,
+    pub dividends: Money,
+    pub lots: Vec<HoldingLot>,
+}
+
+/// Cash and holdings of an investment account valued on `as_of`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvestmentPortfolio {
+    pub account_id: i64,
+    pub as_of: String,
+    /// 'fifo' or 'average'.
+    pub cost_basis_method: String,
+    pub cash: Money,
+    /// Securities ever traded in the account, including ones sold off.
+    pub holdings: Vec<Holding>,
+    pub market_value: Money,
+    /// Cash plus market value.
+    pub total_value: Money,
+    pub unrealized_gain: Money,
+    pub realized_gain: Money,
+    pub dividends: Money,
+}
+
+/// One statement reconciled (or being reconciled) for an account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Reconciliation {
+    pub id: i64,
+    pub account_id: i64,
+    pub statement_date: String,
+    pub statement_balance: Money,
+    /// 'open' or 'completed'.
+    pub status: String,
+    /// Cleared balance when the session was completed.
+    pub cleared_balance: Option<Money>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+/// A reconciliation with the transactions that can be ticked against it.
+/// `difference` is the statement balance minus the cleared balance; the
+/// session can be finished once it is zero.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReconciliationSession {
+    pub reconciliation: Reconciliation,
+    pub transactions: Vec<Transaction>,
+    pub cleared_balance: Money,
+    pub difference: Money,
+}
+
+/// A stored receipt or document. The file itself is fetched separately.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub transaction_id: Option<i64>,
+    pub transfer_id: Option<i64>,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Bytes.
+    pub size: i64,
+    pub has_thumbnail: bool,
+    pub created_at: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -71,19 +674,72 @@ pub struct Transfer {
     pub id: i64,
     pub from_account_id: i64,
     pub to_account_id: i64,
-    pub amount: f64,
+    /// Amount that left the source account, in its currency.
+    pub amount: Money,
+    /// Amount that arrived in the destination account, in its currency.
+    pub to_amount: Money,
+    /// Units of the destination currency received per unit sent.
+    pub rate: f64,
+    pub fee: Option<TransferFee>,
     pub date: String,
     pub notes: Option<String>,
     pub transfer_type: String,
     pub created_at: String,
 }
 
+/// A charge on the source account for making a transfer, posted as its own
+/// expense transaction.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferFee {
+    pub amount: Money,
+    pub category_id: Option<i64>,
+}
+
+/// One account's balance at the end of `date`, in the account's currency.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BalancePoint {
+    pub date: String,
+    pub account_id: i64,
+    pub balance: Money,
+}
+
+/// Net worth at the end of `date`, in the base currency. Liabilities are
+/// credit balances owed, reported as a positive amount.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NetWorthPoint {
+    pub date: String,
+    pub assets: Money,
+    pub liabilities: Money,
+    pub net_worth: Money,
+}
+
+/// Realized gain or loss on one cross-currency transfer: what arrived versus
+/// what was sent, both valued in the base currency on the transfer date.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FxGain {
+    pub transfer_id: i64,
+    pub date: String,
+    pub amount: Money,
+    pub to_amount: Money,
+    pub rate: f64,
+    /// Stored rate for the pair on that date, if there is one.
+    pub market_rate: Option<f64>,
+    pub gain: Money,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DashboardData {
-    pub net_worth: f64,
-    pub liquid_balance: f64,
-    pub monthly_income: f64,
-    pub monthly_expenses: f64,
+    /// Day the balances are as of: today, or the snapshot date asked for.
+    pub as_of: String,
+    /// Currency every total below is converted into.
+    pub base_currency: String,
+    /// Currencies left out of the totals for lack of an exchange rate.
+    pub missing_rates: Vec<String>,
+    pub net_worth: Money,
+    pub liquid_balance: Money,
+    pub monthly_income: Money,
+    pub monthly_expenses: Money,
+    /// Investment accounts are valued at market: cash plus holdings.
     pub accounts: Vec<AccountWithBalance>,
     pub recent_transactions: Vec<Transaction>,
     pub spending_by_category: Vec<CategorySpend>,
@@ -93,24 +749,242 @@ pub struct DashboardData {
 pub struct CategorySpend {
     pub category_id: Option<i64>,
     pub category_name: String,
-    pub amount: f64,
+    pub parent_id: Option<i64>,
+    pub amount: Money,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SpendingBreakdown {
     pub year: i64,
     pub month: i64,
-    pub total_income: f64,
-    pub total_expenses: f64,
+    pub total_income: Money,
+    pub total_expenses: Money,
     pub categories: Vec<CategorySpend>,
 }
 
+/// Spending limit for one expense category in one month, in the base
+/// currency at the time it was set.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Budget {
+    pub id: i64,
+    pub category_id: i64,
+    pub category_name: String,
+    /// 'YYYY-MM'.
+    pub period: String,
+    pub amount: Money,
+    /// Carry the previous month's unspent amount (or overspend) into this one.
+    pub rollover: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// How one budgeted category is doing this month.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BudgetLine {
+    pub budget_id: i64,
+    pub category_id: i64,
+    pub category_name: String,
+    /// The budget in today's base currency.
+    pub amount: Money,
+    /// Left over from last month when rolling over; negative after an overspend.
+    pub carried_over: Money,
+    /// Budget plus what was carried over.
+    pub available: Money,
+    pub spent: Money,
+    pub remaining: Money,
+    /// Spent as a percentage of what is available; `None` when nothing is.
+    pub percent_used: Option<f64>,
+    /// Spending at this month's pace through to the month end.
+    pub projected_spend: Money,
+    pub projected_overspend: Money,
+}
+
+/// A budget that has crossed 80% or 100% of what is available.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BudgetAlert {
+    pub budget_id: i64,
+    pub category_id: i64,
+    pub category_name: String,
+    /// 80 or 100.
+    pub threshold: i64,
+    pub percent_used: Option<f64>,
+    pub spent: Money,
+    pub available: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BudgetStatus {
+    pub year: i64,
+    pub month: i64,
+    pub days_in_month: i64,
+    /// Days of the month gone by: all of them for past months, none for
+    /// future ones.
+    pub days_elapsed: i64,
+    pub total_available: Money,
+    pub total_spent: Money,
+    pub categories: Vec<BudgetLine>,
+    /// Over budget first, then nearing it.
+    pub alerts: Vec<BudgetAlert>,
+}
+
+/// Money moved into, out of or between envelopes for one month. A missing
+/// side is the "to be assigned" pool.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnvelopeMove {
+    pub id: i64,
+    /// 'YYYY-MM'.
+    pub period: String,
+    pub from_category_id: Option<i64>,
+    pub to_category_id: Option<i64>,
+    /// In the base currency at the time of the move.
+    pub amount: Money,
+    pub notes: Option<String>,
+    pub created_at: String,
+}
+
+/// One expense category's envelope for a month.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Envelope {
+    pub category_id: i64,
+    pub category_name: String,
+    /// Unspent money from the months before; overspending is not carried.
+    pub carried_over: Money,
+    pub assigned: Money,
+    pub spent: Money,
+    /// Negative when overspent.
+    pub available: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnvelopeMonth {
+    pub year: i64,
+    pub month: i64,
+    /// Income received this month.
+    pub income: Money,
+    /// Income to date not yet assigned, less overspending left uncovered in
+    /// earlier months. Negative when more was assigned than received.
+    pub to_be_assigned: Money,
+    /// Overspending from last month that came out of the pool.
+    pub overspent_last_month: Money,
+    pub assigned: Money,
+    pub spent: Money,
+    pub available: Money,
+    pub envelopes: Vec<Envelope>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MonthSummary {
     pub year: i64,
     pub month: i64,
     pub label: String,
-    pub income: f64,
-    pub expenses: f64,
-    pub net: f64,
+    pub income: Money,
+    pub expenses: Money,
+    pub net: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagSummary {
+    pub tag_id: i64,
+    pub tag_name: String,
+    pub transaction_count: i64,
+    pub income: Money,
+    pub expenses: Money,
+    pub net: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PayeeSummary {
+    pub payee_id: i64,
+    pub payee_name: String,
+    pub transaction_count: i64,
+    pub total: Money,
+}
+
+/// How to read one bank's CSV export. Column indexes are 0-based.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CsvMapping {
+    /// `None` detects the delimiter from the file.
+    pub delimiter: Option<String>,
+    pub has_header: bool,
+    pub skip_rows: i64,
+    pub date_column: i64,
+    /// Pattern built from `YYYY`, `YY`, `MM`, `M`, `DD`, `D` and `MMM` (month name).
+    pub date_format: String,
+    pub description_column: Option<i64>,
+    /// A single signed amount column; otherwise `debit_column`/`credit_column`.
+    pub amount_column: Option<i64>,
+    pub debit_column: Option<i64>,
+    pub credit_column: Option<i64>,
+    /// The signed column shows spending as positive (common on card statements).
+    pub negate_amounts: bool,
+    /// `"."` or `","`; the other character is treated as a thousands separator.
+    pub decimal_separator: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportProfile {
+    pub id: i64,
+    pub name: String,
+    pub mapping: CsvMapping,
+    pub created_at: String,
+}
+
+/// One parsed line of an import file. Rows with an `error` are shown in the
+/// preview and skipped on import.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportRow {
+    pub line: i64,
+    pub date: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub tx_type: Option<String>,
+    pub amount: Option<Money>,
+    /// The bank's own transaction id (OFX `FITID`), used to skip re-imports.
+    pub external_id: Option<String>,
+    /// Existing transaction this row probably repeats (preview only).
+    pub duplicate_of: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportPreview {
+    pub delimiter: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<ImportRow>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportBatch {
+    pub id: i64,
+    pub account_id: i64,
+    pub source: String,
+    pub file_name: Option<String>,
+    pub row_count: i64,
+    pub created_at: String,
+    // Joined fields
+    pub account_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportResult {
+    /// `None` when every row was a duplicate and no batch was kept.
+    pub batch_id: Option<i64>,
+    pub account_id: i64,
+    pub imported: i64,
+    /// Rows that failed to parse.
+    pub skipped: i64,
+    /// Rows whose external id was already imported into the account.
+    pub duplicates: i64,
+    /// Imported rows flagged as probable duplicates of existing transactions.
+    pub flagged: i64,
+}
+
+/// One statement of an OFX/QFX file and the account it will be imported into.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OfxStatementPreview {
+    pub ofx_account_id: Option<String>,
+    pub currency: Option<String>,
+    /// Account linked to `ofx_account_id`, if any.
+    pub account_id: Option<i64>,
+    pub rows: Vec<ImportRow>,
 }
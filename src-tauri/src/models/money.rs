@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+/// Currency used for new accounts and for totals that span accounts.
+pub const DEFAULT_CURRENCY: &str = "PHP";
+
+/// An exact monetary amount: an integer count of the currency's minor unit
+/// (centavos for PHP) plus its ISO 4217 code.
+/// Serialized as `{ "minor": 12345, "currency": "PHP" }`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Money {
+    pub minor: i64,
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(minor: i64, currency: impl Into<String>) -> Self {
+        Self {
+            minor,
+            currency: currency.into(),
+        }
+    }
+
+    /// Fails with a user-facing message when `self` is not in `currency`.
+    pub fn require_currency(&self, currency: &str) -> Result<(), String> {
+        if self.currency == currency {
+            Ok(())
+        } else {
+            Err(format!(
+                "Amount is in {} but {} was expected",
+                self.currency, currency
+            ))
+        }
+    }
+
+    /// Formats as a plain decimal string (e.g. `-1234.50`), used for CSV export.
+    pub fn to_decimal_string(&self) -> String {
+        let exp = minor_unit_exponent(&self.currency);
+        if exp == 0 {
+            return self.minor.to_string();
+        }
+        let scale = 10i64.pow(exp);
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let abs = self.minor.unsigned_abs();
+        format!(
+            "{sign}{}.{:0width$}",
+            abs / scale as u64,
+            abs % scale as u64,
+            width = exp as usize
+        )
+    }
+}
+
+/// Trims and upper-cases an ISO 4217 code, rejecting anything that is not
+/// three letters.
+pub fn normalize_currency(code: &str) -> Result<String, String> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(code)
+    } else {
+        Err(format!("\"{}\" is not a currency code", code))
+    }
+}
+
+/// Number of decimal places in the currency's minor unit.
+/// Keep in sync with the CASE expressions in `0002_money_minor_units.sql`.
+pub fn minor_unit_exponent(currency: &str) -> u32 {
+    match currency {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" => 3,
+        _ => 2,
+    }
+}
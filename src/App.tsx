@@ -1,6 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { MemoryRouter, Routes, Route } from "react-router-dom";
-import { processRecurringTransactions } from "./lib/tauri";
+import { processInstallments, processRecurringTransactions } from "./lib/tauri";
 import { getAuthStatus, lockApp as lockAppCmd } from "./lib/auth";
 import Layout from "./components/Layout";
 import SetupWizard from "./pages/auth/SetupWizard";
@@ -41,10 +41,11 @@ function App() {
     refreshAuthStatus();
   }, [refreshAuthStatus]);
 
-  // Process recurring transactions only after unlocking
+  // Process recurring transactions and due installments only after unlocking
   useEffect(() => {
     if (appState === "unlocked") {
       processRecurringTransactions().catch(console.error);
+      processInstallments().catch(console.error);
     }
   }, [appState]);
 
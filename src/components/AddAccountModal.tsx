@@ -1,6 +1,7 @@
 import { useState } from "react";
 import { AccountWithBalance } from "../types";
 import * as api from "../lib/tauri";
+import { DEFAULT_CURRENCY, isLiability, parseMoney, toInputString } from "../lib/money";
 
 interface Props {
   editing?: AccountWithBalance | null;
@@ -12,11 +13,12 @@ export default function AddAccountModal({ editing, onClose, onSaved }: Props) {
   const [name, setName] = useState(editing?.name ?? "");
   const [accountType, setAccountType] = useState(editing?.type ?? "cash");
   const [subtype, setSubtype] = useState(editing?.subtype ?? "");
+  const [currencyCode, setCurrencyCode] = useState(editing?.currency ?? DEFAULT_CURRENCY);
   const [openingBalance, setOpeningBalance] = useState(
-    editing?.opening_balance?.toString() ?? "0"
+    editing ? toInputString(editing.opening_balance) : "0"
   );
   const [creditLimit, setCreditLimit] = useState(
-    editing?.credit_limit?.toString() ?? ""
+    editing?.credit_limit ? toInputString(editing.credit_limit) : ""
   );
   const [billingDay, setBillingDay] = useState(
     editing?.billing_cycle_day?.toString() ?? ""
@@ -32,6 +34,11 @@ export default function AddAccountModal({ editing, onClose, onSaved }: Props) {
   async function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
     if (!name) { setError("Name is required."); return; }
+    const currency = editing?.currency ?? currencyCode.trim().toUpperCase();
+    if (!/^[A-Z]{3}$/.test(currency)) { setError("Enter a 3-letter currency code."); return; }
+    const opening = parseMoney(openingBalance || "0", currency);
+    const limit = isCredit && creditLimit ? parseMoney(creditLimit, currency) : null;
+    if (!opening || (isCredit && creditLimit && !limit)) { setError("Enter a valid amount."); return; }
     setLoading(true);
     setError("");
     try {
@@ -39,15 +46,15 @@ export default function AddAccountModal({ editing, onClose, onSaved }: Props) {
         name,
         account_type: accountType,
         subtype: subtype || null,
-        opening_balance: parseFloat(openingBalance) || 0,
-        credit_limit: isCredit && creditLimit ? parseFloat(creditLimit) : null,
+        opening_balance: opening,
+        credit_limit: limit,
         billing_cycle_day: isCredit && billingDay ? parseInt(billingDay) : null,
         payment_due_day: isCredit && paymentDay ? parseInt(paymentDay) : null,
       };
       if (editing) {
         await api.updateAccount(editing.id, payload);
       } else {
-        await api.createAccount(payload);
+        await api.createAccount({ ...payload, currency });
       }
       onSaved();
     } catch (err: any) {
@@ -81,13 +88,14 @@ export default function AddAccountModal({ editing, onClose, onSaved }: Props) {
               <label>Type</label>
               <select
                 value={accountType}
-                onChange={(e) => setAccountType(e.target.value as "cash" | "debit" | "credit" | "savings" | "investment")}
+                onChange={(e) => setAccountType(e.target.value as AccountWithBalance["type"])}
               >
                 <option value="cash">Cash</option>
                 <option value="debit">Debit</option>
                 <option value="savings">Savings</option>
                 <option value="credit">Credit Card</option>
                 <option value="investment">Investment</option>
+                <option value="loan">Loan</option>
               </select>
             </div>
             <div className="form-group">
@@ -101,9 +109,21 @@ export default function AddAccountModal({ editing, onClose, onSaved }: Props) {
             </div>
           </div>
 
+          <div className="form-group">
+            <label>Currency</label>
+            <input
+              type="text"
+              maxLength={3}
+              value={currencyCode}
+              onChange={(e) => setCurrencyCode(e.target.value.toUpperCase())}
+              disabled={!!editing}
+              placeholder="PHP"
+            />
+          </div>
+
           <div className="form-group">
             <label>
-              {isCredit ? "Current Balance Owed" : "Opening Balance"}
+              {isLiability(accountType) ? "Current Balance Owed" : "Opening Balance"}
             </label>
             <input
               type="number"
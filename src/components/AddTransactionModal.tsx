@@ -1,6 +1,7 @@
 import { useState } from "react";
 import { AccountWithBalance, Category, Transaction } from "../types";
 import * as api from "../lib/tauri";
+import { DEFAULT_CURRENCY, parseMoney, toInputString } from "../lib/money";
 
 interface Props {
   accounts: AccountWithBalance[];
@@ -26,7 +27,7 @@ export default function AddTransactionModal({
   const [categoryId, setCategoryId] = useState<number | null>(
     editing?.category_id ?? null
   );
-  const [amount, setAmount] = useState(editing?.amount?.toString() ?? "");
+  const [amount, setAmount] = useState(editing ? toInputString(editing.amount) : "");
   const [date, setDate] = useState(
     editing?.date ?? new Date().toISOString().split("T")[0]
   );
@@ -48,14 +49,18 @@ export default function AddTransactionModal({
   async function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
     if (!amount || !accountId) { setError("Amount and account are required."); return; }
+    const currency = accounts.find((a) => a.id === accountId)?.currency ?? DEFAULT_CURRENCY;
+    const parsed = parseMoney(amount, currency);
+    if (!parsed) { setError("Enter a valid amount."); return; }
     setLoading(true);
     setError("");
     try {
       const payload = {
         account_id: accountId,
         category_id: categoryId,
+        payee_id: editing?.payee_id ?? null,
         tx_type: txType,
-        amount: parseFloat(amount),
+        amount: parsed,
         date,
         notes: notes || null,
         is_recurring: isRecurring,
@@ -1,6 +1,7 @@
 import { useState } from "react";
 import { AccountWithBalance, Transfer } from "../types";
 import * as api from "../lib/tauri";
+import { DEFAULT_CURRENCY, parseMoney, toInputString } from "../lib/money";
 
 interface Props {
   accounts: AccountWithBalance[];
@@ -13,7 +14,9 @@ export default function AddTransferModal({ accounts, editing, onClose, onSaved }
   const activeAccounts = accounts.filter((a) => a.is_active === 1);
   const [fromId, setFromId] = useState<number>(editing?.from_account_id ?? activeAccounts[0]?.id ?? 0);
   const [toId, setToId] = useState<number>(editing?.to_account_id ?? activeAccounts[1]?.id ?? 0);
-  const [amount, setAmount] = useState(editing ? String(editing.amount) : "");
+  const [amount, setAmount] = useState(editing ? toInputString(editing.amount) : "");
+  const [toAmount, setToAmount] = useState(editing ? toInputString(editing.to_amount) : "");
+  const [fee, setFee] = useState(editing?.fee ? toInputString(editing.fee.amount) : "");
   const [date, setDate] = useState(editing?.date ?? new Date().toISOString().split("T")[0]);
   const [notes, setNotes] = useState(editing?.notes ?? "");
   const [transferType, setTransferType] = useState<"regular" | "credit_payment">(
@@ -22,18 +25,34 @@ export default function AddTransferModal({ accounts, editing, onClose, onSaved }
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState("");
 
+  const fromCurrency = accounts.find((a) => a.id === fromId)?.currency ?? DEFAULT_CURRENCY;
+  const toCurrency = accounts.find((a) => a.id === toId)?.currency ?? DEFAULT_CURRENCY;
+  const crossCurrency = fromCurrency !== toCurrency;
+
   async function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
     if (!amount || (!editing && fromId === toId)) {
       setError("Select different from/to accounts and enter an amount.");
       return;
     }
+    const parsed = parseMoney(amount, fromCurrency);
+    if (!parsed) { setError("Enter a valid amount."); return; }
+    let parsedTo = null;
+    if (crossCurrency) {
+      parsedTo = parseMoney(toAmount, toCurrency);
+      if (!parsedTo) { setError(`Enter the amount received in ${toCurrency}.`); return; }
+    }
+    const parsedFee = fee ? parseMoney(fee, fromCurrency) : null;
+    if (fee && !parsedFee) { setError("Enter a valid fee."); return; }
+    const feeInput = parsedFee ? { amount: parsedFee, category_id: editing?.fee?.category_id ?? null } : null;
     setLoading(true);
     setError("");
     try {
       if (editing) {
         await api.updateTransfer(editing.id, {
-          amount: parseFloat(amount),
+          amount: parsed,
+          to_amount: parsedTo,
+          fee: feeInput,
           date,
           notes: notes || null,
           transfer_type: transferType,
@@ -42,7 +61,9 @@ export default function AddTransferModal({ accounts, editing, onClose, onSaved }
         await api.createTransfer({
           from_account_id: fromId,
           to_account_id: toId,
-          amount: parseFloat(amount),
+          amount: parsed,
+          to_amount: parsedTo,
+          fee: feeInput,
           date,
           notes: notes || null,
           transfer_type: transferType,
@@ -131,6 +152,33 @@ export default function AddTransferModal({ accounts, editing, onClose, onSaved }
             </div>
           </div>
 
+          {crossCurrency && (
+            <div className="form-group">
+              <label>Amount Received ({toCurrency})</label>
+              <input
+                type="number"
+                step="0.01"
+                min="0"
+                value={toAmount}
+                onChange={(e) => setToAmount(e.target.value)}
+                placeholder="0.00"
+                required
+              />
+            </div>
+          )}
+
+          <div className="form-group">
+            <label>Fee ({fromCurrency})</label>
+            <input
+              type="number"
+              step="0.01"
+              min="0"
+              value={fee}
+              onChange={(e) => setFee(e.target.value)}
+              placeholder="Optional"
+            />
+          </div>
+
           <div className="form-group">
             <label>Notes</label>
             <input
@@ -0,0 +1,37 @@
+import { Money } from "../types";
+
+export const DEFAULT_CURRENCY = "PHP";
+
+// Keep in sync with minor_unit_exponent in src-tauri/src/models/money.rs
+export const minorUnitExponent = (currency: string): number => {
+  if (["JPY", "KRW", "VND", "CLP", "ISK"].includes(currency)) return 0;
+  if (["BHD", "KWD", "OMR", "JOD", "TND"].includes(currency)) return 3;
+  return 2;
+};
+
+/** Converts to a plain number for display and charts. Never send the result back to Rust. */
+export const toMajor = (m: Money): number =>
+  m.minor / 10 ** minorUnitExponent(m.currency);
+
+/** Renders minor units as an editable decimal string, e.g. 123450 → "1234.50". */
+export const toInputString = (m: Money): string =>
+  toMajor(m).toFixed(minorUnitExponent(m.currency));
+
+/**
+ * Parses user input such as "1,234.5" into exact minor units without going
+ * through floating point. Returns null for malformed input or too many decimals.
+ */
+export function parseMoney(input: string, currency: string): Money | null {
+  const exp = minorUnitExponent(currency);
+  const cleaned = input.trim().replace(/,/g, "");
+  const match = /^(-)?(\d*)(?:\.(\d*))?$/.exec(cleaned);
+  if (!match || (!match[2] && !match[3])) return null;
+  const [, sign, whole, frac = ""] = match;
+  if (frac.length > exp) return null;
+  const minor = Number(whole || "0") * 10 ** exp + Number(frac.padEnd(exp, "0") || "0");
+  return { minor: sign ? -minor : minor, currency };
+}
+
+/** Credit card and loan balances are amounts owed. */
+export const isLiability = (accountType: string): boolean =>
+  accountType === "credit" || accountType === "loan";
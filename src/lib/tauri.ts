@@ -1,19 +1,76 @@
 import { invoke } from "@tauri-apps/api/core";
 import {
   AccountWithBalance,
+  Attachment,
+  BalancePoint,
+  Budget,
+  BudgetStatus,
+  CardPaymentDue,
   Category,
+  CostBasisMethod,
   CreateTransactionInput,
   CreateTransferInput,
+  CreditCardSummary,
+  CsvMapping,
   DashboardData,
+  DuplicateCluster,
+  EnvelopeMonth,
+  EnvelopeMove,
+  ExchangeRate,
+  FxGain,
+  HistoryGranularity,
+  ImportBatch,
+  ImportPreview,
+  ImportProfile,
+  ImportResult,
+  ImportRow,
+  InstallmentPlan,
+  InstallmentPlanInput,
+  InterestAccrual,
+  InterestSettings,
+  InterestSettingsInput,
+  InvestmentPortfolio,
+  InvestmentTrade,
+  Loan,
+  LoanPayment,
+  LoanSchedule,
+  LoanTermsInput,
+  Money,
   MonthSummary,
+  NetWorthPoint,
+  OfxStatementPreview,
+  Payee,
+  PayeeAlias,
+  PayeeSummary,
+  Reconciliation,
+  ReconciliationSession,
+  Rule,
+  RuleChange,
+  RuleInput,
+  SavingsDividend,
+  SavingsPlan,
+  SavingsPlanInput,
+  SavingsProjection,
+  Security,
+  SecurityPrice,
   SpendingBreakdown,
+  Tag,
+  TagSummary,
+  TradeInput,
   Transaction,
+  TransactionSplit,
+  TransactionSplitInput,
   Transfer,
+  TransferFee,
 } from "../types";
 
 export const listAccounts = () =>
   invoke<AccountWithBalance[]>("list_accounts");
 
+/** Accounts as they stood at the end of `date`, with balances then. */
+export const getAccountsAsOf = (date: string) =>
+  invoke<AccountWithBalance[]>("get_accounts_as_of", { date });
+
 export const getAccount = (id: number) =>
   invoke<AccountWithBalance>("get_account", { id });
 
@@ -21,10 +78,12 @@ export const createAccount = (data: {
   name: string;
   account_type: string;
   subtype?: string | null;
-  opening_balance: number;
-  credit_limit?: number | null;
+  opening_balance: Money;
+  credit_limit?: Money | null;
   billing_cycle_day?: number | null;
   payment_due_day?: number | null;
+  /** ISO 4217 code; fixed once the account exists. Defaults to PHP. */
+  currency?: string;
 }) =>
   invoke<AccountWithBalance>("create_account", {
     name: data.name,
@@ -34,6 +93,7 @@ export const createAccount = (data: {
     creditLimit: data.credit_limit,
     billingCycleDay: data.billing_cycle_day,
     paymentDueDay: data.payment_due_day,
+    currency: data.currency,
   });
 
 export const updateAccount = (
@@ -42,8 +102,8 @@ export const updateAccount = (
     name: string;
     account_type: string;
     subtype?: string | null;
-    opening_balance: number;
-    credit_limit?: number | null;
+    opening_balance: Money;
+    credit_limit?: Money | null;
     billing_cycle_day?: number | null;
     payment_due_day?: number | null;
   }
@@ -59,6 +119,10 @@ export const updateAccount = (
     paymentDueDay: data.payment_due_day,
   });
 
+/** Pass null to unlink. OFX imports are routed by this id. */
+export const linkOfxAccount = (id: number, ofxAccountId: string | null) =>
+  invoke<AccountWithBalance>("link_ofx_account", { id, ofxAccountId });
+
 export const archiveAccount = (id: number) =>
   invoke<void>("archive_account", { id });
 
@@ -75,6 +139,10 @@ export const listTransactions = (filters?: {
   date_from?: string | null;
   date_to?: string | null;
   search?: string | null;
+  payee_id?: number | null;
+  tags?: number[] | null;
+  /** "any" (default) or "all" of the given tags. */
+  tag_match?: "any" | "all" | null;
 }) => {
   const f = filters ?? {};
   return invoke<Transaction[]>("list_transactions", {
@@ -84,6 +152,9 @@ export const listTransactions = (filters?: {
     dateFrom: f.date_from,
     dateTo: f.date_to,
     search: f.search,
+    payeeId: f.payee_id,
+    tags: f.tags,
+    tagMatch: f.tag_match,
   });
 };
 
@@ -91,6 +162,7 @@ export const createTransaction = (data: CreateTransactionInput) =>
   invoke<Transaction>("create_transaction", {
     accountId: data.account_id,
     categoryId: data.category_id,
+    payeeId: data.payee_id,
     txType: data.tx_type,
     amount: data.amount,
     date: data.date,
@@ -105,6 +177,7 @@ export const updateTransaction = (id: number, data: CreateTransactionInput) =>
     id,
     accountId: data.account_id,
     categoryId: data.category_id,
+    payeeId: data.payee_id,
     txType: data.tx_type,
     amount: data.amount,
     date: data.date,
@@ -117,11 +190,95 @@ export const updateTransaction = (id: number, data: CreateTransactionInput) =>
 export const deleteTransaction = (id: number) =>
   invoke<void>("delete_transaction", { id });
 
+export const listTransactionSplits = (transactionId: number) =>
+  invoke<TransactionSplit[]>("list_transaction_splits", { transactionId });
+
+export const createTransactionSplits = (transactionId: number, splits: TransactionSplitInput[]) =>
+  invoke<TransactionSplit[]>("create_transaction_splits", { transactionId, splits });
+
+/** Replaces every split line; an empty list removes the split. */
+export const updateTransactionSplits = (transactionId: number, splits: TransactionSplitInput[]) =>
+  invoke<TransactionSplit[]>("update_transaction_splits", { transactionId, splits });
+
+export const findDuplicates = (accountId?: number, windowDays?: number, minScore?: number) =>
+  invoke<DuplicateCluster[]>("find_duplicates", { accountId, windowDays, minScore });
+
+/** Folds the duplicates into `keepId` and deletes them. */
+export const mergeDuplicates = (keepId: number, duplicateIds: number[]) =>
+  invoke<Transaction>("merge_duplicates", { keepId, duplicateIds });
+
+export const dismissDuplicates = (transactionIds: number[]) =>
+  invoke<void>("dismiss_duplicates", { transactionIds });
+
+export const listPayees = () => invoke<Payee[]>("list_payees");
+
+export const createPayee = (name: string) => invoke<Payee>("create_payee", { name });
+
+/** The old name is kept as an alias. */
+export const renamePayee = (id: number, name: string) =>
+  invoke<Payee>("rename_payee", { id, name });
+
+/** Moves the sources' transactions and aliases onto the target, then deletes them. */
+export const mergePayees = (targetId: number, sourceIds: number[]) =>
+  invoke<Payee>("merge_payees", { targetId, sourceIds });
+
+export const deletePayee = (id: number) => invoke<void>("delete_payee", { id });
+
+export const listPayeeAliases = (payeeId: number) =>
+  invoke<PayeeAlias[]>("list_payee_aliases", { payeeId });
+
+export const createPayeeAlias = (payeeId: number, pattern: string) =>
+  invoke<PayeeAlias>("create_payee_alias", { payeeId, pattern });
+
+export const deletePayeeAlias = (id: number) =>
+  invoke<void>("delete_payee_alias", { id });
+
+export const resolvePayee = (name: string) =>
+  invoke<Payee | null>("resolve_payee", { name });
+
+export const listRules = () => invoke<Rule[]>("list_rules");
+
+export const createRule = (rule: RuleInput) => invoke<Rule>("create_rule", { rule });
+
+export const updateRule = (id: number, rule: RuleInput) =>
+  invoke<Rule>("update_rule", { id, rule });
+
+export const deleteRule = (id: number) => invoke<void>("delete_rule", { id });
+
+/** Dry run: what a single rule would change. Nothing is written. */
+export const previewRule = (id: number, overwrite?: boolean) =>
+  invoke<RuleChange[]>("preview_rule", { id, overwrite });
+
+/** Returns the number of transactions changed. */
+export const applyRulesRetroactively = (overwrite?: boolean) =>
+  invoke<number>("apply_rules_retroactively", { overwrite });
+
+export const exportRulesJson = () => invoke<void>("export_rules_json");
+
+/** Returns the number of rules imported (0 if cancelled). */
+export const importRulesJson = () => invoke<number>("import_rules_json");
+
+export const listTags = () => invoke<Tag[]>("list_tags");
+
+export const createTag = (data: { name: string; color?: string | null }) =>
+  invoke<Tag>("create_tag", data);
+
+export const updateTag = (id: number, data: { name: string; color?: string | null }) =>
+  invoke<Tag>("update_tag", { id, ...data });
+
+export const deleteTag = (id: number) => invoke<void>("delete_tag", { id });
+
+/** Replaces the transaction's tags with `tagIds`. */
+export const setTransactionTags = (transactionId: number, tagIds: number[]) =>
+  invoke<void>("set_transaction_tags", { transactionId, tagIds });
+
 export const createTransfer = (data: CreateTransferInput) =>
   invoke<Transfer>("create_transfer", {
     fromAccountId: data.from_account_id,
     toAccountId: data.to_account_id,
     amount: data.amount,
+    toAmount: data.to_amount,
+    fee: data.fee,
     date: data.date,
     notes: data.notes,
     transferType: data.transfer_type,
@@ -133,21 +290,200 @@ export const deleteTransfer = (id: number) =>
 export const getTransfer = (id: number) =>
   invoke<Transfer>("get_transfer", { id });
 
-export const updateTransfer = (id: number, data: { amount: number; date: string; notes: string | null; transfer_type: string }) =>
+export const updateTransfer = (
+  id: number,
+  data: { amount: Money; to_amount: Money | null; fee: TransferFee | null; date: string; notes: string | null; transfer_type: string }
+) =>
   invoke<Transfer>("update_transfer", {
     id,
     amount: data.amount,
+    toAmount: data.to_amount,
+    fee: data.fee,
     date: data.date,
     notes: data.notes,
     transferType: data.transfer_type,
   });
 
+export const getCreditCardSummary = (accountId: number, cycles?: number) =>
+  invoke<CreditCardSummary>("get_credit_card_summary", { accountId, cycles });
+
+export const listCardPaymentsDue = (daysAhead?: number) =>
+  invoke<CardPaymentDue[]>("list_card_payments_due", { daysAhead });
+
+export const listInstallmentPlans = (accountId?: number) =>
+  invoke<InstallmentPlan[]>("list_installment_plans", { accountId });
+
+export const createInstallmentPlan = (input: InstallmentPlanInput) =>
+  invoke<InstallmentPlan>("create_installment_plan", { input });
+
+/** Bills the rest of the principal on `date` (default today). */
+export const payOffInstallmentPlan = (id: number, date?: string, preterminationFee?: Money) =>
+  invoke<InstallmentPlan>("pay_off_installment_plan", { id, date, preterminationFee });
+
+export const deleteInstallmentPlan = (id: number) =>
+  invoke<void>("delete_installment_plan", { id });
+
+/** Returns the number of installments billed. */
+export const processInstallments = () => invoke<number>("process_installments");
+
+export const getInterestSettings = (accountId: number) =>
+  invoke<InterestSettings | null>("get_interest_settings", { accountId });
+
+export const setInterestSettings = (accountId: number, input: InterestSettingsInput) =>
+  invoke<InterestSettings>("set_interest_settings", { accountId, input });
+
+export const deleteInterestSettings = (accountId: number) =>
+  invoke<void>("delete_interest_settings", { accountId });
+
+/** Posts interest for periods ending in the range; `preview` writes nothing. */
+export const accrueInterest = (dateFrom: string, dateTo: string, accountId?: number, preview?: boolean) =>
+  invoke<InterestAccrual[]>("accrue_interest", { dateFrom, dateTo, accountId, preview });
+
+export const listSecurities = () => invoke<Security[]>("list_securities");
+
+export const createSecurity = (symbol: string, currency: string, name?: string) =>
+  invoke<Security>("create_security", { symbol, name, currency });
+
+export const deleteSecurity = (id: number) => invoke<void>("delete_security", { id });
+
+export const listSecurityPrices = (securityId: number) =>
+  invoke<SecurityPrice[]>("list_security_prices", { securityId });
+
+/** Replaces any price already set for that date. */
+export const setSecurityPrice = (securityId: number, date: string, price: number) =>
+  invoke<SecurityPrice>("set_security_price", { securityId, date, price });
+
+export const deleteSecurityPrice = (id: number) => invoke<void>("delete_security_price", { id });
+
+/** Imports `date,symbol,price` rows; returns the number of prices saved. */
+export const importSecurityPricesCsv = (filePath: string) =>
+  invoke<number>("import_security_prices_csv", { filePath });
+
+export const listInvestmentTrades = (accountId: number) =>
+  invoke<InvestmentTrade[]>("list_investment_trades", { accountId });
+
+export const recordInvestmentTrade = (input: TradeInput) =>
+  invoke<InvestmentTrade>("record_investment_trade", { input });
+
+export const deleteInvestmentTrade = (id: number) => invoke<void>("delete_investment_trade", { id });
+
+/** Values holdings at `asOf` (default today) using FIFO unless told otherwise. */
+export const getInvestmentPortfolio = (accountId: number, asOf?: string, costBasisMethod?: CostBasisMethod) =>
+  invoke<InvestmentPortfolio>("get_investment_portfolio", { accountId, asOf, costBasisMethod });
+
+/** Resolves to null until terms are set. */
+export const getLoan = (accountId: number) => invoke<Loan | null>("get_loan", { accountId });
+
+export const setLoanTerms = (accountId: number, input: LoanTermsInput) =>
+  invoke<Loan>("set_loan_terms", { accountId, input });
+
+export const getLoanSchedule = (accountId: number) =>
+  invoke<LoanSchedule>("get_loan_schedule", { accountId });
+
+/** Pass `interest` to override the computed split; zero makes it an extra principal payment. */
+export const recordLoanPayment = (
+  accountId: number,
+  fromAccountId: number,
+  amount: Money,
+  date: string,
+  interest?: Money,
+  notes?: string,
+) => invoke<LoanPayment>("record_loan_payment", { accountId, fromAccountId, amount, date, interest, notes });
+
+/** Resolves to null until a plan is set up. */
+export const getSavingsPlan = (accountId: number) =>
+  invoke<SavingsPlan | null>("get_savings_plan", { accountId });
+
+export const setSavingsPlan = (accountId: number, input: SavingsPlanInput) =>
+  invoke<SavingsPlan>("set_savings_plan", { accountId, input });
+
+export const deleteSavingsPlan = (accountId: number) =>
+  invoke<void>("delete_savings_plan", { accountId });
+
+/** Rate declared for a calendar year, in percent. */
+export const setSavingsRate = (accountId: number, year: number, rate: number) =>
+  invoke<SavingsPlan>("set_savings_rate", { accountId, year, rate });
+
+export const deleteSavingsRate = (accountId: number, year: number) =>
+  invoke<SavingsPlan>("delete_savings_rate", { accountId, year });
+
+/** Projection to maturity plus contributions due by `asOf` (default today). */
+export const getSavingsProjection = (accountId: number, asOf?: string) =>
+  invoke<SavingsProjection>("get_savings_projection", { accountId, asOf });
+
+export const recordSavingsDividend = (accountId: number, amount: Money, date: string, notes?: string) =>
+  invoke<SavingsDividend>("record_savings_dividend", { accountId, amount, date, notes });
+
+export const startReconciliation = (accountId: number, statementDate: string, statementBalance: Money) =>
+  invoke<ReconciliationSession>("start_reconciliation", { accountId, statementDate, statementBalance });
+
+export const getReconciliation = (id: number) =>
+  invoke<ReconciliationSession>("get_reconciliation", { id });
+
+export const setTransactionsCleared = (transactionIds: number[], cleared: boolean) =>
+  invoke<void>("set_transactions_cleared", { transactionIds, cleared });
+
+export const finishReconciliation = (id: number) =>
+  invoke<Reconciliation>("finish_reconciliation", { id });
+
+export const cancelReconciliation = (id: number) =>
+  invoke<void>("cancel_reconciliation", { id });
+
+export const listReconciliations = (accountId: number) =>
+  invoke<Reconciliation[]>("list_reconciliations", { accountId });
+
+export const unlockTransaction = (id: number) =>
+  invoke<void>("unlock_transaction", { id });
+
+export const pickAttachmentFile = () => invoke<string | null>("pick_attachment_file");
+
+/** Pass exactly one of `transactionId` or `transferId`. */
+export const listAttachments = (owner: { transactionId?: number; transferId?: number }) =>
+  invoke<Attachment[]>("list_attachments", owner);
+
+export const addAttachment = (owner: { transactionId?: number; transferId?: number }, filePath: string) =>
+  invoke<Attachment>("add_attachment", { ...owner, filePath });
+
+/** Raw file bytes, e.g. for `new Blob([data], { type: attachment.mime_type })`. */
+export const getAttachmentData = (id: number) =>
+  invoke<ArrayBuffer>("get_attachment_data", { id });
+
+/** PNG bytes; only for attachments with `has_thumbnail`. */
+export const getAttachmentThumbnail = (id: number) =>
+  invoke<ArrayBuffer>("get_attachment_thumbnail", { id });
+
+export const saveAttachment = (id: number) => invoke<void>("save_attachment", { id });
+
+export const deleteAttachment = (id: number) => invoke<void>("delete_attachment", { id });
+
+export const getBaseCurrency = () => invoke<string>("get_base_currency");
+
+/** Currency that dashboard and report totals are converted into. */
+export const setBaseCurrency = (currency: string) =>
+  invoke<string>("set_base_currency", { currency });
+
+export const listExchangeRates = (base?: string, quote?: string) =>
+  invoke<ExchangeRate[]>("list_exchange_rates", { base, quote });
+
+/** One `base` is worth `rate` `quote` from `date` on; replaces that day's rate. */
+export const setExchangeRate = (base: string, quote: string, rate: number, date: string) =>
+  invoke<ExchangeRate>("set_exchange_rate", { base, quote, rate, date });
+
+export const deleteExchangeRate = (id: number) =>
+  invoke<void>("delete_exchange_rate", { id });
+
+/** CSV columns: date,base,quote,rate. Returns the number of rates read. */
+export const importExchangeRatesCsv = (filePath: string) =>
+  invoke<number>("import_exchange_rates_csv", { filePath });
+
 export const listCategories = () => invoke<Category[]>("list_categories");
 
 export const createCategory = (data: {
   name: string;
   direction: string;
   icon?: string | null;
+  /** Omit for a top-level category. */
+  parentId?: number | null;
 }) => invoke<Category>("create_category", data);
 
 export const updateCategory = (
@@ -155,20 +491,91 @@ export const updateCategory = (
   data: { name: string; direction: string; icon?: string | null }
 ) => invoke<Category>("update_category", { id, ...data });
 
+/** Moves the category and its subcategories; a null parent makes it top-level. */
+export const moveCategory = (id: number, parentId: number | null) =>
+  invoke<Category>("move_category", { id, parentId });
+
 export const archiveCategory = (id: number) =>
   invoke<void>("archive_category", { id });
 
 export const restoreCategory = (id: number) =>
   invoke<void>("restore_category", { id });
 
-export const getDashboard = () => invoke<DashboardData>("get_dashboard");
+/**
+ * Today's dashboard, or a snapshot at the end of `asOf`. Spending is rolled
+ * up to `categoryDepth` (0 = top-level categories) when given.
+ */
+export const getDashboard = (asOf?: string | null, categoryDepth?: number | null) =>
+  invoke<DashboardData>("get_dashboard", { asOf: asOf ?? null, categoryDepth: categoryDepth ?? null });
+
+export const listBudgets = (year: number, month: number) =>
+  invoke<Budget[]>("list_budgets", { year, month });
+
+/** Replaces the category's budget for that month. */
+export const setBudget = (categoryId: number, year: number, month: number, amount: Money, rollover?: boolean) =>
+  invoke<Budget>("set_budget", { categoryId, year, month, amount, rollover });
 
-export const getSpendingBreakdown = (year: number, month: number) =>
-  invoke<SpendingBreakdown>("get_spending_breakdown", { year, month });
+export const deleteBudget = (id: number) => invoke<void>("delete_budget", { id });
+
+/** Returns the number of budgets written; existing ones are kept unless `overwrite`. */
+export const copyBudgets = (fromYear: number, fromMonth: number, toYear: number, toMonth: number, overwrite?: boolean) =>
+  invoke<number>("copy_budgets", { fromYear, fromMonth, toYear, toMonth, overwrite });
+
+export const getBudgetStatus = (year: number, month: number) =>
+  invoke<BudgetStatus>("get_budget_status", { year, month });
+
+export const getEnvelopeMode = () => invoke<boolean>("get_envelope_mode");
+
+export const setEnvelopeMode = (enabled: boolean) => invoke<boolean>("set_envelope_mode", { enabled });
+
+export const listEnvelopeMoves = (year: number, month: number) =>
+  invoke<EnvelopeMove[]>("list_envelope_moves", { year, month });
+
+/** Leave `fromCategoryId` null to assign from the pool, `toCategoryId` null to return to it. */
+export const moveEnvelopeMoney = (
+  year: number,
+  month: number,
+  fromCategoryId: number | null,
+  toCategoryId: number | null,
+  amount: Money,
+  notes?: string | null,
+) =>
+  invoke<EnvelopeMove>("move_envelope_money", { year, month, fromCategoryId, toCategoryId, amount, notes: notes ?? null });
+
+export const deleteEnvelopeMove = (id: number) => invoke<void>("delete_envelope_move", { id });
+
+/** Moves exactly the overspent amount into the envelope, from another one or the pool. */
+export const coverOverspending = (year: number, month: number, categoryId: number, fromCategoryId?: number | null) =>
+  invoke<EnvelopeMove>("cover_overspending", { year, month, categoryId, fromCategoryId: fromCategoryId ?? null });
+
+export const getEnvelopeMonth = (year: number, month: number) =>
+  invoke<EnvelopeMonth>("get_envelope_month", { year, month });
+
+export const getSpendingBreakdown = (year: number, month: number, categoryDepth?: number | null) =>
+  invoke<SpendingBreakdown>("get_spending_breakdown", { year, month, categoryDepth: categoryDepth ?? null });
 
 export const getMonthlyTrends = (months: number) =>
   invoke<MonthSummary[]>("get_monthly_trends", { months });
 
+export const getTagReport = (dateFrom: string, dateTo: string) =>
+  invoke<TagSummary[]>("get_tag_report", { dateFrom, dateTo });
+
+export const getTopPayees = (dateFrom: string, dateTo: string, limit?: number) =>
+  invoke<PayeeSummary[]>("get_top_payees", { dateFrom, dateTo, limit });
+
+export const getFxGains = (dateFrom: string, dateTo: string) =>
+  invoke<FxGain[]>("get_fx_gains", { dateFrom, dateTo });
+
+export const getBalanceHistory = (
+  accountId: number | null,
+  dateFrom: string,
+  dateTo: string,
+  granularity: HistoryGranularity
+) => invoke<BalancePoint[]>("get_balance_history", { accountId, dateFrom, dateTo, granularity });
+
+export const getNetWorthHistory = (dateFrom: string, dateTo: string, granularity: HistoryGranularity) =>
+  invoke<NetWorthPoint[]>("get_net_worth_history", { dateFrom, dateTo, granularity });
+
 export const processRecurringTransactions = () =>
   invoke<number>("process_recurring_transactions");
 
@@ -180,3 +587,44 @@ export const backupDatabase = () => invoke<void>("backup_database");
 export const restoreDatabase = () => invoke<void>("restore_database");
 
 export const resetAllData = () => invoke<void>("reset_all_data");
+
+export const listImportProfiles = () =>
+  invoke<ImportProfile[]>("list_import_profiles");
+
+export const createImportProfile = (name: string, mapping: CsvMapping) =>
+  invoke<ImportProfile>("create_import_profile", { name, mapping });
+
+export const updateImportProfile = (id: number, name: string, mapping: CsvMapping) =>
+  invoke<ImportProfile>("update_import_profile", { id, name, mapping });
+
+export const deleteImportProfile = (id: number) =>
+  invoke<void>("delete_import_profile", { id });
+
+/** Opens a file picker; resolves to null if cancelled. */
+export const pickImportFile = () => invoke<string | null>("pick_import_file");
+
+export const previewCsvImport = (accountId: number, filePath: string, mapping: CsvMapping) =>
+  invoke<ImportPreview>("preview_csv_import", { accountId, filePath, mapping });
+
+export const importTransactionsCsv = (accountId: number, filePath: string, mapping: CsvMapping) =>
+  invoke<ImportResult>("import_transactions_csv", { accountId, filePath, mapping });
+
+export const previewOfxImport = (filePath: string) =>
+  invoke<OfxStatementPreview[]>("preview_ofx_import", { filePath });
+
+/** `accountId` receives statements not yet linked to an account. */
+export const importOfx = (filePath: string, accountId?: number) =>
+  invoke<ImportResult[]>("import_ofx", { filePath, accountId });
+
+export const previewQifImport = (accountId: number, filePath: string, dayFirst?: boolean) =>
+  invoke<ImportRow[]>("preview_qif_import", { accountId, filePath, dayFirst });
+
+export const importQif = (accountId: number, filePath: string, dayFirst?: boolean) =>
+  invoke<ImportResult>("import_qif", { accountId, filePath, dayFirst });
+
+export const listImportBatches = (accountId?: number) =>
+  invoke<ImportBatch[]>("list_import_batches", { accountId });
+
+/** Deletes the batch and every transaction it imported. */
+export const rollbackImportBatch = (id: number) =>
+  invoke<void>("rollback_import_batch", { id });
@@ -3,8 +3,9 @@ import { useParams, useNavigate } from "react-router-dom";
 import {
   LineChart, Line, XAxis, YAxis, Tooltip, ResponsiveContainer,
 } from "recharts";
-import { AccountWithBalance, Transaction } from "../types";
+import { AccountWithBalance, InvestmentPortfolio, LoanSchedule, SavingsProjection, Transaction } from "../types";
 import * as api from "../lib/tauri";
+import { isLiability, toMajor } from "../lib/money";
 import AddTransactionModal from "../components/AddTransactionModal";
 import AddAccountModal from "../components/AddAccountModal";
 import ConfirmModal from "../components/ConfirmModal";
@@ -13,7 +14,7 @@ const fmt = (n: number) =>
   n.toLocaleString("en-PH", { minimumFractionDigits: 2, maximumFractionDigits: 2 });
 
 const typeIcon: Record<string, string> = {
-  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈",
+  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈", loan: "🏠",
 };
 
 export default function AccountDetail() {
@@ -28,6 +29,9 @@ export default function AccountDetail() {
   const [showEditAccount, setShowEditAccount] = useState(false);
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false);
   const [confirmDeleteTx, setConfirmDeleteTx] = useState<Transaction | null>(null);
+  const [loanSchedule, setLoanSchedule] = useState<LoanSchedule | null>(null);
+  const [portfolio, setPortfolio] = useState<InvestmentPortfolio | null>(null);
+  const [savings, setSavings] = useState<SavingsProjection | null>(null);
 
   async function load() {
     if (!id) return;
@@ -41,22 +45,40 @@ export default function AccountDetail() {
     setTransactions(txns);
     setCategories(cats);
     setAccounts(accs);
+    setLoanSchedule(
+      acct.type === "loan" ? await api.getLoanSchedule(acct.id).catch(() => null) : null
+    );
+    setPortfolio(
+      acct.type === "investment" ? await api.getInvestmentPortfolio(acct.id).catch(() => null) : null
+    );
+    setSavings(
+      acct.type === "savings" ? await api.getSavingsProjection(acct.id).catch(() => null) : null
+    );
   }
 
   useEffect(() => { load(); }, [id]);
 
+  async function toggleStatus(t: Transaction) {
+    if (t.status === "reconciled") {
+      await api.unlockTransaction(t.id);
+    } else {
+      await api.setTransactionsCleared([t.id], t.status !== "cleared");
+    }
+    load();
+  }
+
   if (!account) return <div className="empty-state"><p>Loading…</p></div>;
 
   // Build running balance chart data
   const sortedTxns = [...transactions].reverse();
-  let running = account.opening_balance;
+  let running = account.opening_balance.minor;
   const chartData = sortedTxns.map((t) => {
-    if (account.type === "credit") {
-      running += t.type === "expense" ? t.amount : -t.amount;
+    if (isLiability(account.type)) {
+      running += t.type === "expense" ? t.amount.minor : -t.amount.minor;
     } else {
-      running += t.type === "income" ? t.amount : -t.amount;
+      running += t.type === "income" ? t.amount.minor : -t.amount.minor;
     }
-    return { date: t.date, balance: running };
+    return { date: t.date, balance: toMajor({ minor: running, currency: account.currency }) };
   });
 
   return (
@@ -90,8 +112,8 @@ export default function AccountDetail() {
       <div className="card-grid" style={{ gridTemplateColumns: "repeat(3, 1fr)" }}>
         <div className="stat-card">
           <div className="stat-label">Balance</div>
-          <div className={`stat-value ${account.type === "credit" ? "expense" : "income"}`}>
-            ₱{fmt(account.balance)}
+          <div className={`stat-value ${isLiability(account.type) ? "expense" : "income"}`}>
+            ₱{fmt(toMajor(account.balance))}
           </div>
         </div>
         <div className="stat-card">
@@ -103,7 +125,36 @@ export default function AccountDetail() {
         {account.credit_limit && (
           <div className="stat-card">
             <div className="stat-label">Credit Limit</div>
-            <div className="stat-value" style={{ fontSize: 18 }}>₱{fmt(account.credit_limit)}</div>
+            <div className="stat-value" style={{ fontSize: 18 }}>₱{fmt(toMajor(account.credit_limit))}</div>
+          </div>
+        )}
+        {loanSchedule && (
+          <div className="stat-card">
+            <div className="stat-label">Projected Payoff</div>
+            <div className="stat-value" style={{ fontSize: 18 }}>{loanSchedule.payoff_date ?? "—"}</div>
+            <div style={{ fontSize: 12, color: "var(--text-muted)" }}>
+              {loanSchedule.remaining_payments} payments of ₱{fmt(toMajor(loanSchedule.loan.regular_payment))}
+            </div>
+          </div>
+        )}
+        {portfolio && portfolio.holdings.length > 0 && (
+          <div className="stat-card">
+            <div className="stat-label">Market Value</div>
+            <div className="stat-value" style={{ fontSize: 18 }}>₱{fmt(toMajor(portfolio.total_value))}</div>
+            <div style={{ fontSize: 12, color: "var(--text-muted)" }}>
+              Unrealized {portfolio.unrealized_gain.minor >= 0 ? "+" : ""}₱{fmt(toMajor(portfolio.unrealized_gain))}
+            </div>
+          </div>
+        )}
+        {savings && (
+          <div className="stat-card">
+            <div className="stat-label">Maturity Value</div>
+            <div className="stat-value" style={{ fontSize: 18 }}>₱{fmt(toMajor(savings.maturity_value))}</div>
+            <div style={{ fontSize: 12, color: savings.shortfall.minor > 0 ? "var(--expense)" : "var(--text-muted)" }}>
+              {savings.shortfall.minor > 0
+                ? `₱${fmt(toMajor(savings.shortfall))} behind plan`
+                : `Matures ${savings.plan.maturity_date}`}
+            </div>
           </div>
         )}
       </div>
@@ -133,6 +184,7 @@ export default function AccountDetail() {
             <table>
               <thead>
                 <tr>
+                  <th title="Cleared / reconciled">✓</th>
                   <th>Date</th>
                   <th>Category</th>
                   <th>Notes</th>
@@ -144,25 +196,35 @@ export default function AccountDetail() {
               <tbody>
                 {transactions.map((t) => (
                   <tr key={t.id}>
+                    <td>
+                      <button
+                        className="btn btn-sm"
+                        title={t.status === "reconciled" ? "Reconciled — click to unlock" : "Toggle cleared"}
+                        onClick={() => toggleStatus(t)}
+                      >
+                        {t.status === "reconciled" ? "🔒" : t.status === "cleared" ? "C" : "·"}
+                      </button>
+                    </td>
                     <td>{t.date}</td>
                     <td>{t.category_name ?? "—"}</td>
                     <td>{t.notes ?? ""} {t.transfer_id && <span className="badge badge-transfer">Transfer</span>}</td>
                     <td><span className={`badge badge-${t.type}`}>{t.type}</span></td>
                     <td className={`text-right ${t.type === "income" ? "text-income" : "text-expense"}`}>
-                      {t.type === "income" ? "+" : "-"}₱{fmt(t.amount)}
+                      {t.type === "income" ? "+" : "-"}₱{fmt(toMajor(t.amount))}
                     </td>
                     <td>
                       <div style={{ display: "flex", gap: 4 }}>
                         <button
                           className="btn btn-sm"
                           onClick={() => { setEditing(t); setShowModal(true); }}
-                          disabled={!!t.transfer_id}
+                          disabled={!!t.transfer_id || t.status === "reconciled"}
                         >
                           Edit
                         </button>
                         <button
                           className="btn btn-sm btn-danger"
                           onClick={() => setConfirmDeleteTx(t)}
+                          disabled={t.status === "reconciled"}
                         >
                           Del
                         </button>
@@ -2,16 +2,17 @@ import { useEffect, useState } from "react";
 import { useNavigate } from "react-router-dom";
 import { AccountWithBalance } from "../types";
 import * as api from "../lib/tauri";
+import { isLiability, toMajor } from "../lib/money";
 import AddAccountModal from "../components/AddAccountModal";
 
 const fmt = (n: number) =>
   n.toLocaleString("en-PH", { minimumFractionDigits: 2, maximumFractionDigits: 2 });
 
 const typeIcon: Record<string, string> = {
-  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈",
+  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈", loan: "🏠",
 };
 
-const typeOrder = ["cash", "debit", "savings", "credit", "investment"];
+const typeOrder = ["cash", "debit", "savings", "credit", "loan", "investment"];
 
 export default function Accounts() {
   const [accounts, setAccounts] = useState<AccountWithBalance[]>([]);
@@ -62,12 +63,12 @@ export default function Accounts() {
                   onClick={() => navigate(`/accounts/${a.id}`)}
                 >
                   <div className="stat-label">{a.name}</div>
-                  <div className={`stat-value ${a.type === "credit" ? "expense" : "income"}`}>
-                    ₱{fmt(a.balance)}
+                  <div className={`stat-value ${isLiability(a.type) ? "expense" : "income"}`}>
+                    ₱{fmt(toMajor(a.balance))}
                   </div>
                   {a.type === "credit" && a.credit_limit && (
                     <div className="text-muted" style={{ fontSize: 12, marginTop: 4 }}>
-                      Limit: ₱{fmt(a.credit_limit)}
+                      Limit: ₱{fmt(toMajor(a.credit_limit))}
                     </div>
                   )}
                 </div>
@@ -4,8 +4,9 @@ import {
   BarChart, Bar, XAxis, YAxis, Tooltip, ResponsiveContainer,
   PieChart, Pie, Cell,
 } from "recharts";
-import { DashboardData } from "../types";
+import { BudgetAlert, CardPaymentDue, DashboardData, Money } from "../types";
 import * as api from "../lib/tauri";
+import { isLiability, toMajor } from "../lib/money";
 import AddTransactionModal from "../components/AddTransactionModal";
 import AddTransferModal from "../components/AddTransferModal";
 
@@ -14,32 +15,47 @@ const COLORS = ["#4f8ef7","#22c55e","#f59e0b","#ef4444","#8b5cf6","#06b6d4","#f9
 const fmt = (n: number) =>
   n.toLocaleString("en-PH", { minimumFractionDigits: 2, maximumFractionDigits: 2 });
 
+const symbol = (currency: string) => (currency === "PHP" ? "₱" : `${currency} `);
+
+const show = (m: Money) => `${symbol(m.currency)}${fmt(toMajor(m))}`;
+
 const accountTypeIcon: Record<string, string> = {
-  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈",
+  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈", loan: "🏠",
 };
 
 export default function Dashboard() {
   const [data, setData] = useState<DashboardData | null>(null);
   const [categories, setCategories] = useState<any[]>([]);
+  const [cardsDue, setCardsDue] = useState<CardPaymentDue[]>([]);
+  const [budgetAlerts, setBudgetAlerts] = useState<BudgetAlert[]>([]);
   const [showTxModal, setShowTxModal] = useState(false);
   const [showTransferModal, setShowTransferModal] = useState(false);
+  const [asOf, setAsOf] = useState("");
   const navigate = useNavigate();
 
   async function load() {
-    const [d, cats] = await Promise.all([api.getDashboard(), api.listCategories()]);
+    const [d, cats, due] = await Promise.all([
+      api.getDashboard(asOf || null),
+      api.listCategories(),
+      api.listCardPaymentsDue(),
+    ]);
     setData(d);
     setCategories(cats);
+    setCardsDue(due);
+    const [year, month] = d.as_of.split("-").map(Number);
+    const budgets = await api.getBudgetStatus(year, month).catch(() => null);
+    setBudgetAlerts(budgets?.alerts ?? []);
   }
 
-  useEffect(() => { load(); }, []);
+  useEffect(() => { load(); }, [asOf]);
 
   if (!data) {
     return <div className="empty-state"><p>Loading…</p></div>;
   }
 
   const barData = [
-    { name: "Income", amount: data.monthly_income, fill: "#22c55e" },
-    { name: "Expenses", amount: data.monthly_expenses, fill: "#ef4444" },
+    { name: "Income", amount: toMajor(data.monthly_income), fill: "#22c55e" },
+    { name: "Expenses", amount: toMajor(data.monthly_expenses), fill: "#ef4444" },
   ];
 
   return (
@@ -47,6 +63,17 @@ export default function Dashboard() {
       <div className="page-header">
         <h1 className="page-title">Dashboard</h1>
         <div style={{ display: "flex", gap: 8 }}>
+          <input
+            type="date"
+            value={asOf}
+            onChange={(e) => setAsOf(e.target.value)}
+            title="Show the dashboard as of a past date"
+          />
+          {asOf && (
+            <button className="btn" onClick={() => setAsOf("")}>
+              Today
+            </button>
+          )}
           <button className="btn" onClick={() => setShowTransferModal(true)}>
             ↔ Transfer
           </button>
@@ -60,26 +87,71 @@ export default function Dashboard() {
       <div className="card-grid">
         <div className="stat-card">
           <div className="stat-label">Liquid</div>
-          <div className={`stat-value ${data.liquid_balance >= 0 ? "income" : "expense"}`}>
-            ₱{fmt(data.liquid_balance)}
+          <div className={`stat-value ${data.liquid_balance.minor >= 0 ? "income" : "expense"}`}>
+            {show(data.liquid_balance)}
           </div>
         </div>
         <div className="stat-card">
           <div className="stat-label">Net Worth</div>
-          <div className={`stat-value ${data.net_worth >= 0 ? "income" : "expense"}`}>
-            ₱{fmt(data.net_worth)}
+          <div className={`stat-value ${data.net_worth.minor >= 0 ? "income" : "expense"}`}>
+            {show(data.net_worth)}
           </div>
         </div>
         <div className="stat-card">
           <div className="stat-label">Month Income</div>
-          <div className="stat-value income">₱{fmt(data.monthly_income)}</div>
+          <div className="stat-value income">{show(data.monthly_income)}</div>
         </div>
         <div className="stat-card">
           <div className="stat-label">Month Expenses</div>
-          <div className="stat-value expense">₱{fmt(data.monthly_expenses)}</div>
+          <div className="stat-value expense">{show(data.monthly_expenses)}</div>
         </div>
       </div>
 
+      {data.missing_rates.length > 0 && (
+        <p className="text-muted" style={{ marginBottom: 12 }}>
+          Totals leave out {data.missing_rates.join(", ")} amounts: add an exchange rate to {data.base_currency}.
+        </p>
+      )}
+
+      {cardsDue.length > 0 && (
+        <div className="card" style={{ marginBottom: 16 }}>
+          <div style={{ fontWeight: 700, marginBottom: 12 }}>Card Payments</div>
+          {cardsDue.map((c) => (
+            <div key={c.account_id} style={{ display: "flex", justifyContent: "space-between", marginBottom: 6 }}>
+              <span>
+                {c.account_name}{" "}
+                <span className="text-muted">
+                  due {c.statement.due_date}
+                  {c.statement.status === "overdue" && " (overdue)"}
+                  {c.past_due.minor > 0 && ` · ${show(c.past_due)} past due`}
+                </span>
+              </span>
+              <span className="text-expense">
+                {show(c.statement.amount_remaining)}{" "}
+                <span className="text-muted">min {show(c.statement.minimum_payment)}</span>
+              </span>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {budgetAlerts.length > 0 && (
+        <div className="card" style={{ marginBottom: 16 }}>
+          <div style={{ fontWeight: 700, marginBottom: 12 }}>Budgets</div>
+          {budgetAlerts.map((a) => (
+            <div key={a.budget_id} style={{ display: "flex", justifyContent: "space-between", marginBottom: 6 }}>
+              <span>
+                {a.category_name}{" "}
+                <span className="text-muted">{a.threshold === 100 ? "over budget" : "nearing its limit"}</span>
+              </span>
+              <span className={a.threshold === 100 ? "text-expense" : undefined}>
+                {show(a.spent)} <span className="text-muted">of {show(a.available)}</span>
+              </span>
+            </div>
+          ))}
+        </div>
+      )}
+
       <div className="grid-2">
         {/* Accounts */}
         <div className="card">
@@ -96,8 +168,8 @@ export default function Dashboard() {
                 <span>
                   {accountTypeIcon[a.type] ?? "🏦"} {a.name}
                 </span>
-                <span className={a.type === "credit" ? "text-expense" : "text-income"}>
-                  ₱{fmt(a.balance)}
+                <span className={isLiability(a.type) ? "text-expense" : "text-income"}>
+                  {show(a.balance)}
                 </span>
               </div>
             ))
@@ -112,7 +184,7 @@ export default function Dashboard() {
               <BarChart data={barData}>
                 <XAxis dataKey="name" />
                 <YAxis />
-                <Tooltip formatter={(v: number | undefined) => v !== undefined ? `₱${fmt(v)}` : ""} />
+                <Tooltip formatter={(v: number | undefined) => v !== undefined ? `${symbol(data.base_currency)}${fmt(v)}` : ""} />
                 <Bar dataKey="amount" fill="#4f8ef7">
                   {barData.map((entry, i) => (
                     <Cell key={i} fill={entry.fill} />
@@ -135,7 +207,7 @@ export default function Dashboard() {
               <ResponsiveContainer width="100%" height="100%">
                 <PieChart>
                   <Pie
-                    data={data.spending_by_category}
+                    data={data.spending_by_category.map((c) => ({ ...c, amount: toMajor(c.amount) }))}
                     dataKey="amount"
                     nameKey="category_name"
                     cx="50%"
@@ -150,7 +222,7 @@ export default function Dashboard() {
                       <Cell key={i} fill={COLORS[i % COLORS.length]} />
                     ))}
                   </Pie>
-                  <Tooltip formatter={(v: number | undefined) => v !== undefined ? `₱${fmt(v)}` : ""} />
+                  <Tooltip formatter={(v: number | undefined) => v !== undefined ? `${symbol(data.base_currency)}${fmt(v)}` : ""} />
                 </PieChart>
               </ResponsiveContainer>
             </div>
@@ -174,7 +246,7 @@ export default function Dashboard() {
                       </td>
                       <td className="text-right">
                         <span className={t.type === "income" ? "text-income" : "text-expense"}>
-                          {t.type === "income" ? "+" : "-"}₱{fmt(t.amount)}
+                          {t.type === "income" ? "+" : "-"}{show(t.amount)}
                         </span>
                         {t.transfer_id && (
                           <div><span className="badge badge-transfer" style={{ fontSize: 10 }}>Transfer</span></div>
@@ -4,6 +4,7 @@ import {
 } from "recharts";
 import { MonthSummary } from "../types";
 import * as api from "../lib/tauri";
+import { toMajor } from "../lib/money";
 
 const fmt = (n: number) =>
   n.toLocaleString("en-PH", { minimumFractionDigits: 2, maximumFractionDigits: 2 });
@@ -43,7 +44,7 @@ export default function MonthlyTrends() {
             <div style={{ fontWeight: 700, marginBottom: 12 }}>Income vs Expenses</div>
             <div className="chart-container" style={{ height: 320 }}>
               <ResponsiveContainer width="100%" height="100%">
-                <BarChart data={data}>
+                <BarChart data={data.map((m) => ({ ...m, income: toMajor(m.income), expenses: toMajor(m.expenses) }))}>
                   <XAxis dataKey="label" tick={{ fontSize: 11 }} />
                   <YAxis />
                   <Tooltip formatter={(v: number | undefined) => v !== undefined ? `₱${fmt(v)}` : ""} />
@@ -71,10 +72,10 @@ export default function MonthlyTrends() {
                   {[...data].reverse().map((m) => (
                     <tr key={m.label}>
                       <td>{m.label}</td>
-                      <td className="text-right text-income">₱{fmt(m.income)}</td>
-                      <td className="text-right text-expense">₱{fmt(m.expenses)}</td>
-                      <td className={`text-right ${m.net >= 0 ? "text-income" : "text-expense"}`}>
-                        ₱{fmt(m.net)}
+                      <td className="text-right text-income">₱{fmt(toMajor(m.income))}</td>
+                      <td className="text-right text-expense">₱{fmt(toMajor(m.expenses))}</td>
+                      <td className={`text-right ${m.net.minor >= 0 ? "text-income" : "text-expense"}`}>
+                        ₱{fmt(toMajor(m.net))}
                       </td>
                     </tr>
                   ))}
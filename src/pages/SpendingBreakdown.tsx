@@ -4,6 +4,7 @@ import {
 } from "recharts";
 import { SpendingBreakdown as SpendingBreakdownData } from "../types";
 import * as api from "../lib/tauri";
+import { toMajor } from "../lib/money";
 
 const COLORS = ["#4f8ef7","#22c55e","#f59e0b","#ef4444","#8b5cf6","#06b6d4","#f97316","#84cc16"];
 
@@ -51,16 +52,16 @@ export default function SpendingBreakdown() {
           <div className="card-grid">
             <div className="stat-card">
               <div className="stat-label">Total Income</div>
-              <div className="stat-value income">₱{fmt(data.total_income)}</div>
+              <div className="stat-value income">₱{fmt(toMajor(data.total_income))}</div>
             </div>
             <div className="stat-card">
               <div className="stat-label">Total Expenses</div>
-              <div className="stat-value expense">₱{fmt(data.total_expenses)}</div>
+              <div className="stat-value expense">₱{fmt(toMajor(data.total_expenses))}</div>
             </div>
             <div className="stat-card">
               <div className="stat-label">Net</div>
-              <div className={`stat-value ${data.total_income - data.total_expenses >= 0 ? "income" : "expense"}`}>
-                ₱{fmt(data.total_income - data.total_expenses)}
+              <div className={`stat-value ${data.total_income.minor - data.total_expenses.minor >= 0 ? "income" : "expense"}`}>
+                ₱{fmt(toMajor({ ...data.total_income, minor: data.total_income.minor - data.total_expenses.minor }))}
               </div>
             </div>
           </div>
@@ -75,7 +76,7 @@ export default function SpendingBreakdown() {
                   <ResponsiveContainer width="100%" height="100%">
                     <PieChart>
                       <Pie
-                        data={data.categories}
+                        data={data.categories.map((c) => ({ ...c, amount: toMajor(c.amount) }))}
                         dataKey="amount"
                         nameKey="category_name"
                         cx="50%"
@@ -117,10 +118,10 @@ export default function SpendingBreakdown() {
                             {c.category_name}
                           </div>
                         </td>
-                        <td className="text-right text-expense">₱{fmt(c.amount)}</td>
+                        <td className="text-right text-expense">₱{fmt(toMajor(c.amount))}</td>
                         <td className="text-right text-muted">
-                          {data.total_expenses > 0
-                            ? ((c.amount / data.total_expenses) * 100).toFixed(1) + "%"
+                          {data.total_expenses.minor > 0
+                            ? ((c.amount.minor / data.total_expenses.minor) * 100).toFixed(1) + "%"
                             : "0%"}
                         </td>
                       </tr>
@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react";
 import { AccountWithBalance, Category, Transaction, Transfer } from "../types";
 import * as api from "../lib/tauri";
+import { toMajor } from "../lib/money";
 import AddTransactionModal from "../components/AddTransactionModal";
 import AddTransferModal from "../components/AddTransferModal";
 import ConfirmModal from "../components/ConfirmModal";
@@ -131,7 +132,7 @@ export default function Transactions() {
                       <span className={`badge badge-${t.type}`}>{t.type}</span>
                     </td>
                     <td className={`text-right ${t.type === "income" ? "text-income" : "text-expense"}`}>
-                      {t.type === "income" ? "+" : "-"}₱{fmt(t.amount)}
+                      {t.type === "income" ? "+" : "-"}₱{fmt(toMajor(t.amount))}
                     </td>
                     <td>
                       <div style={{ display: "flex", gap: 4 }}>
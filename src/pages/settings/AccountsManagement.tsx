@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react";
 import { AccountWithBalance } from "../../types";
 import * as api from "../../lib/tauri";
+import { isLiability, toMajor } from "../../lib/money";
 import AddAccountModal from "../../components/AddAccountModal";
 import ConfirmModal from "../../components/ConfirmModal";
 
@@ -8,7 +9,7 @@ const fmt = (n: number) =>
   n.toLocaleString("en-PH", { minimumFractionDigits: 2, maximumFractionDigits: 2 });
 
 const typeIcon: Record<string, string> = {
-  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈",
+  cash: "💵", debit: "💳", credit: "💰", savings: "🏦", investment: "📈", loan: "🏠",
 };
 
 export default function AccountsManagement() {
@@ -76,10 +77,10 @@ export default function AccountsManagement() {
                     {a.subtype && <span className="text-muted"> ({a.subtype})</span>}
                   </td>
                   <td style={{ textTransform: "capitalize" }}>{a.type}</td>
-                  <td className={`text-right ${a.type === "credit" ? "text-expense" : "text-income"}`}>
-                    ₱{fmt(a.balance)}
+                  <td className={`text-right ${isLiability(a.type) ? "text-expense" : "text-income"}`}>
+                    ₱{fmt(toMajor(a.balance))}
                   </td>
-                  <td className="text-right text-muted">₱{fmt(a.opening_balance)}</td>
+                  <td className="text-right text-muted">₱{fmt(toMajor(a.opening_balance))}</td>
                   <td>
                     {a.is_active === 1 ? (
                       <span className="badge" style={{ background: "#dcfce7", color: "#16a34a" }}>Active</span>
@@ -4,14 +4,16 @@ import * as api from "../../lib/tauri";
 
 interface CategoryModalProps {
   editing?: Category | null;
+  categories: Category[];
   onClose: () => void;
   onSaved: () => void;
 }
 
-function CategoryModal({ editing, onClose, onSaved }: CategoryModalProps) {
+function CategoryModal({ editing, categories, onClose, onSaved }: CategoryModalProps) {
   const [name, setName] = useState(editing?.name ?? "");
   const [direction, setDirection] = useState(editing?.direction ?? "expense");
   const [icon, setIcon] = useState(editing?.icon ?? "");
+  const [parentId, setParentId] = useState<number | null>(editing?.parent_id ?? null);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState("");
 
@@ -23,8 +25,11 @@ function CategoryModal({ editing, onClose, onSaved }: CategoryModalProps) {
     try {
       if (editing) {
         await api.updateCategory(editing.id, { name, direction, icon: icon || null });
+        if (parentId !== editing.parent_id) {
+          await api.moveCategory(editing.id, parentId);
+        }
       } else {
-        await api.createCategory({ name, direction, icon: icon || null });
+        await api.createCategory({ name, direction, icon: icon || null, parentId });
       }
       onSaved();
     } catch (err: any) {
@@ -57,6 +62,20 @@ function CategoryModal({ editing, onClose, onSaved }: CategoryModalProps) {
               <input type="text" value={icon} onChange={(e) => setIcon(e.target.value)} placeholder="e.g. 🍔" />
             </div>
           </div>
+          <div className="form-group">
+            <label>Parent</label>
+            <select
+              value={parentId ?? ""}
+              onChange={(e) => setParentId(e.target.value ? Number(e.target.value) : null)}
+            >
+              <option value="">None (top level)</option>
+              {categories
+                .filter((c) => c.id !== editing?.id && c.is_archived === 0)
+                .map((c) => (
+                  <option key={c.id} value={c.id}>{c.name}</option>
+                ))}
+            </select>
+          </div>
           {error && <p style={{ color: "var(--expense)", marginBottom: 12 }}>{error}</p>}
           <div className="form-actions">
             <button type="button" className="btn" onClick={onClose}>Cancel</button>
@@ -70,6 +89,23 @@ function CategoryModal({ editing, onClose, onSaved }: CategoryModalProps) {
   );
 }
 
+/** Categories with each one's subcategories right after it, and their depth. */
+function inTreeOrder(cats: Category[]): [Category, number][] {
+  const ids = new Set(cats.map((c) => c.id));
+  const ordered: [Category, number][] = [];
+  const visit = (parentId: number | null, depth: number) => {
+    for (const c of cats) {
+      const parent = c.parent_id !== null && ids.has(c.parent_id) ? c.parent_id : null;
+      if (parent === parentId && c.id !== parentId) {
+        ordered.push([c, depth]);
+        visit(c.id, depth + 1);
+      }
+    }
+  };
+  visit(null, 0);
+  return ordered;
+}
+
 export default function Categories() {
   const [categories, setCategories] = useState<Category[]>([]);
   const [showModal, setShowModal] = useState(false);
@@ -98,10 +134,10 @@ export default function Categories() {
         ) : (
           <table>
             <tbody>
-              {cats.map((c) => (
+              {inTreeOrder(cats).map(([c, depth]) => (
                 <tr key={c.id} style={{ opacity: c.is_archived ? 0.5 : 1 }}>
                   <td style={{ width: 32 }}>{c.icon ?? ""}</td>
-                  <td>{c.name} {c.is_archived === 1 && <span className="badge" style={{ background: "#e5e7eb", color: "#6b7280" }}>archived</span>}</td>
+                  <td style={{ paddingLeft: depth * 20 }}>{c.name} {c.is_archived === 1 && <span className="badge" style={{ background: "#e5e7eb", color: "#6b7280" }}>archived</span>}</td>
                   <td>
                     <div style={{ display: "flex", gap: 4, justifyContent: "flex-end" }}>
                       <button
@@ -164,6 +200,7 @@ export default function Categories() {
       {showModal && (
         <CategoryModal
           editing={editing}
+          categories={categories}
           onClose={() => setShowModal(false)}
           onSaved={() => { setShowModal(false); load(); }}
         />
@@ -11,23 +11,30 @@ export interface SecuritySettings {
   auto_lock_minutes: number;
 }
 
+/** Exact amount in the currency's minor unit (centavos for PHP). */
+export interface Money {
+  minor: number;
+  currency: string;
+}
+
 export interface Account {
   id: number;
   name: string;
-  type: "cash" | "debit" | "credit" | "savings" | "investment";
+  type: "cash" | "debit" | "credit" | "savings" | "investment" | "loan";
   subtype: string | null;
   currency: string;
-  opening_balance: number;
-  credit_limit: number | null;
+  opening_balance: Money;
+  credit_limit: Money | null;
   billing_cycle_day: number | null;
   payment_due_day: number | null;
   is_active: number;
   created_at: string;
   updated_at: string;
+  ofx_account_id: string | null;
 }
 
 export interface AccountWithBalance extends Account {
-  balance: number;
+  balance: Money;
 }
 
 export interface Category {
@@ -37,6 +44,8 @@ export interface Category {
   icon: string | null;
   is_archived: number;
   created_at: string;
+  /** Null for a top-level category. */
+  parent_id: number | null;
 }
 
 export interface Transaction {
@@ -45,40 +54,482 @@ export interface Transaction {
   category_id: number | null;
   transfer_id: number | null;
   type: "income" | "expense";
-  amount: number;
+  amount: Money;
   date: string;
   notes: string | null;
   is_recurring: number;
   recurrence_frequency: string | null;
   next_due_date: string | null;
+  import_batch_id: number | null;
+  /** Earlier transaction this one probably repeats, pending review. */
+  duplicate_of: number | null;
+  /** Reconciled rows are locked until unlocked. */
+  status: TransactionStatus;
+  reconciliation_id: number | null;
+  installment_plan_id: number | null;
+  installment_kind: InstallmentKind | null;
+  /** Investment trade whose cash this row settles. */
+  trade_id: number | null;
   created_at: string;
   updated_at: string;
   category_name: string | null;
   account_name: string | null;
+  payee_id: number | null;
+  payee_name: string | null;
+  is_split: number;
+  tag_ids: number[];
+  attachment_count: number;
+}
+
+export interface TransactionSplit {
+  id: number;
+  transaction_id: number;
+  category_id: number | null;
+  amount: Money;
+  memo: string | null;
+  created_at: string;
+  category_name: string | null;
+}
+
+export interface TransactionSplitInput {
+  category_id: number | null;
+  amount: Money;
+  memo: string | null;
+}
+
+/** One closed billing cycle of a credit card. */
+export interface CardStatement {
+  period_start: string;
+  closing_date: string;
+  due_date: string;
+  statement_balance: Money;
+  minimum_payment: Money;
+  payments: Money;
+  amount_remaining: Money;
+  status: "paid" | "due" | "overdue";
+}
+
+export interface CreditCardSummary {
+  account_id: number;
+  balance: Money;
+  credit_limit: Money | null;
+  /** Limit less the balance and unbilled installment principal. */
+  available_credit: Money | null;
+  /** Installment principal not yet billed; it still holds part of the limit. */
+  installment_principal: Money;
+  /** Percent of the limit in use, unbilled installments included. */
+  utilization: number | null;
+  next_closing_date: string;
+  statements: CardStatement[];
+}
+
+export interface CardPaymentDue {
+  account_id: number;
+  account_name: string;
+  statement: CardStatement;
+  /** Overdue remainder of the previous statement (already in the latest balance). */
+  past_due: Money;
+  days_until_due: number;
+}
+
+export type InstallmentKind = "conversion" | "principal" | "interest" | "fee";
+
+/** A card purchase converted into fixed monthly installments. */
+export interface InstallmentPlan {
+  id: number;
+  account_id: number;
+  purchase_transaction_id: number | null;
+  description: string | null;
+  principal: Money;
+  term_months: number;
+  /** Add-on interest per month, as a percent of the original principal. */
+  monthly_add_on_rate: number;
+  processing_fee: Money;
+  /** Category for interest and fees. */
+  category_id: number | null;
+  start_date: string;
+  status: "active" | "paid_off" | "completed";
+  paid_off_date: string | null;
+  created_at: string;
+  monthly_payment: Money;
+  total_interest: Money;
+  installments_posted: number;
+  remaining_principal: Money;
+  next_installment_date: string | null;
+}
+
+export interface InstallmentPlanInput {
+  purchase_transaction_id: number;
+  /** Defaults to the full purchase amount. */
+  principal: Money | null;
+  term_months: number;
+  monthly_add_on_rate: number;
+  processing_fee: Money | null;
+  category_id: number | null;
+  /** Defaults to the card's next closing date after the purchase. */
+  start_date: string | null;
+  description: string | null;
+}
+
+export interface InterestSettings {
+  account_id: number;
+  /** Percent per year. */
+  rate: number;
+  rate_type: "apr" | "apy";
+  day_count: "actual/365" | "actual/360" | "30/360";
+  compounding: "daily" | "monthly" | "quarterly" | "annually";
+  /** Percent of gross interest withheld, e.g. 20 for the PH final tax. */
+  withholding_tax_rate: number;
+  category_id: number | null;
+  tax_category_id: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface InterestSettingsInput {
+  rate: number;
+  rate_type: InterestSettings["rate_type"];
+  day_count: InterestSettings["day_count"];
+  compounding: InterestSettings["compounding"];
+  withholding_tax_rate: number;
+  category_id: number | null;
+  tax_category_id: number | null;
+}
+
+export interface InterestAccrual {
+  account_id: number;
+  account_name: string;
+  kind: "interest" | "finance_charge";
+  period_start: string;
+  period_end: string;
+  average_daily_balance: Money;
+  days: number;
+  gross: Money;
+  withholding_tax: Money;
+  net: Money;
+  status: "preview" | "posted" | "already_posted";
+  transaction_id: number | null;
+}
+
+export type SavingsKind = "mp2" | "time_deposit" | "other";
+export type ContributionFrequency = "once" | "monthly" | "quarterly" | "annually";
+export type PayoutMode = "compounding" | "annual_payout";
+
+export interface SavingsRate {
+  year: number;
+  /** Percent per year. */
+  rate: number;
+}
+
+export interface SavingsPlan {
+  account_id: number;
+  kind: SavingsKind;
+  start_date: string;
+  /** Lock-in; matures this many months after the start. */
+  term_months: number;
+  contribution_amount: Money;
+  contribution_frequency: ContributionFrequency;
+  /** Percent per year, for years with no declared rate. */
+  annual_rate: number;
+  payout_mode: PayoutMode;
+  payout_account_id: number | null;
+  dividend_category_id: number | null;
+  created_at: string;
+  updated_at: string;
+  maturity_date: string;
+  rates: SavingsRate[];
+}
+
+export interface SavingsPlanInput {
+  kind: SavingsKind;
+  start_date: string;
+  term_months: number;
+  contribution_amount: Money;
+  contribution_frequency: ContributionFrequency;
+  annual_rate: number;
+  payout_mode: PayoutMode;
+  payout_account_id: number | null;
+  dividend_category_id: number | null;
+}
+
+export interface SavingsDividend {
+  transaction_id: number;
+  /** The plan's account, or its payout account. */
+  account_id: number;
+  date: string;
+  amount: Money;
+}
+
+export interface ProjectedSavingsYear {
+  end_date: string;
+  contributions: Money;
+  dividend: Money;
+  balance: Money;
+}
+
+export interface ContributionCheck {
+  date: string;
+  expected: Money;
+  cumulative_expected: Money;
+  cumulative_paid: Money;
+  shortfall: Money;
+}
+
+export interface SavingsProjection {
+  plan: SavingsPlan;
+  as_of: string;
+  balance: Money;
+  /** Assumes every contribution is made. */
+  years: ProjectedSavingsYear[];
+  total_contributions: Money;
+  total_dividends: Money;
+  paid_out: Money;
+  maturity_value: Money;
+  credited: SavingsDividend[];
+  credited_total: Money;
+  contributions: ContributionCheck[];
+  shortfall: Money;
+}
+
+export interface Security {
+  id: number;
+  symbol: string;
+  name: string | null;
+  currency: string;
+  created_at: string;
+}
+
+/** Price of one unit from `date` until the next price. */
+export interface SecurityPrice {
+  id: number;
+  security_id: number;
+  date: string;
+  /** Major units; may carry more decimals than the currency. */
+  price: number;
+  source: "manual" | "csv";
+  created_at: string;
+}
+
+export type TradeKind = "buy" | "sell" | "dividend";
+
+export interface InvestmentTrade {
+  id: number;
+  account_id: number;
+  security_id: number;
+  kind: TradeKind;
+  date: string;
+  quantity: number;
+  /** Per unit, in major units. */
+  price: number;
+  fees: Money;
+  /** Cash paid (buy) or received (sell, dividend), fees included. */
+  amount: Money;
+  notes: string | null;
+  created_at: string;
+  symbol: string;
+  transaction_id: number | null;
+}
+
+export interface TradeInput {
+  account_id: number;
+  security_id: number;
+  kind: TradeKind;
+  date: string;
+  quantity: number;
+  price: number;
+  fees: Money | null;
+  /** Dividend received; buys and sells compute theirs. */
+  amount: Money | null;
+  /** Income category for dividends. */
+  category_id: number | null;
+  notes: string | null;
+}
+
+export type CostBasisMethod = "fifo" | "average";
+
+export interface HoldingLot {
+  date: string;
+  quantity: number;
+  cost: Money;
+}
+
+export interface Holding {
+  security_id: number;
+  symbol: string;
+  name: string | null;
+  quantity: number;
+  cost_basis: Money;
+  /** Latest price on or before the valuation date, or the last trade price. */
+  price: number | null;
+  price_date: string | null;
+  market_value: Money;
+  unrealized_gain: Money;
+  realized_gain: Money;
+  dividends: Money;
+  lots: HoldingLot[];
+}
+
+export interface InvestmentPortfolio {
+  account_id: number;
+  as_of: string;
+  cost_basis_method: CostBasisMethod;
+  cash: Money;
+  holdings: Holding[];
+  market_value: Money;
+  /** Cash plus market value. */
+  total_value: Money;
+  unrealized_gain: Money;
+  realized_gain: Money;
+  dividends: Money;
+}
+
+export type PaymentFrequency = "weekly" | "biweekly" | "monthly" | "quarterly";
+
+/** Repayment terms of a loan account. */
+export interface Loan {
+  account_id: number;
+  /** Amount originally borrowed. */
+  principal: Money;
+  /** Percent per year. */
+  annual_rate: number;
+  term_payments: number;
+  payment_frequency: PaymentFrequency;
+  first_payment_date: string;
+  interest_category_id: number | null;
+  created_at: string;
+  updated_at: string;
+  /** Level payment that repays the principal over the term. */
+  regular_payment: Money;
+}
+
+export interface LoanTermsInput {
+  principal: Money;
+  annual_rate: number;
+  term_payments: number;
+  payment_frequency: PaymentFrequency;
+  first_payment_date: string;
+  interest_category_id: number | null;
+}
+
+export interface LoanPayment {
+  transfer_id: number;
+  date: string;
+  amount: Money;
+  principal: Money;
+  interest: Money;
+}
+
+/** A payment already made (`paid`) or a projected one. */
+export interface AmortizationRow {
+  date: string;
+  payment: Money;
+  principal: Money;
+  interest: Money;
+  /** Amount owed after this payment. */
+  balance: Money;
+  paid: boolean;
+}
+
+export interface LoanSchedule {
+  loan: Loan;
+  balance: Money;
+  rows: AmortizationRow[];
+  remaining_payments: number;
+  payoff_date: string | null;
+  /** Interest paid so far plus projected interest. */
+  total_interest: Money;
+}
+
+export type TransactionStatus = "uncleared" | "cleared" | "reconciled";
+
+/** One statement reconciled (or in progress) for an account. */
+export interface Reconciliation {
+  id: number;
+  account_id: number;
+  statement_date: string;
+  statement_balance: Money;
+  status: "open" | "completed";
+  cleared_balance: Money | null;
+  created_at: string;
+  completed_at: string | null;
+}
+
+/** Finishable once `difference` (statement minus cleared balance) is zero. */
+export interface ReconciliationSession {
+  reconciliation: Reconciliation;
+  transactions: Transaction[];
+  cleared_balance: Money;
+  difference: Money;
 }
 
 export interface Transfer {
   id: number;
   from_account_id: number;
   to_account_id: number;
-  amount: number;
+  /** Sent, in the source account's currency. */
+  amount: Money;
+  /** Received, in the destination account's currency. */
+  to_amount: Money;
+  /** Destination units received per source unit sent. */
+  rate: number;
+  fee: TransferFee | null;
   date: string;
   notes: string | null;
   transfer_type: "regular" | "credit_payment";
   created_at: string;
 }
 
+export interface TransferFee {
+  amount: Money;
+  category_id: number | null;
+}
+
+export type HistoryGranularity = "daily" | "weekly" | "monthly";
+
+/** One account's balance at the end of `date`, in its own currency. */
+export interface BalancePoint {
+  date: string;
+  account_id: number;
+  balance: Money;
+}
+
+/** Net worth at the end of `date`, in the base currency. */
+export interface NetWorthPoint {
+  date: string;
+  assets: Money;
+  liabilities: Money;
+  net_worth: Money;
+}
+
+/** Realized gain or loss on a cross-currency transfer, in the base currency. */
+export interface FxGain {
+  transfer_id: number;
+  date: string;
+  amount: Money;
+  to_amount: Money;
+  rate: number;
+  market_rate: number | null;
+  gain: Money;
+}
+
 export interface CategorySpend {
   category_id: number | null;
   category_name: string;
-  amount: number;
+  parent_id: number | null;
+  amount: Money;
 }
 
 export interface DashboardData {
-  net_worth: number;
-  liquid_balance: number;
-  monthly_income: number;
-  monthly_expenses: number;
+  /** Day the balances are as of. */
+  as_of: string;
+  /** Currency every total is converted into. */
+  base_currency: string;
+  /** Currencies left out of the totals for lack of an exchange rate. */
+  missing_rates: string[];
+  net_worth: Money;
+  liquid_balance: Money;
+  monthly_income: Money;
+  monthly_expenses: Money;
   accounts: AccountWithBalance[];
   recent_transactions: Transaction[];
   spending_by_category: CategorySpend[];
@@ -87,25 +538,221 @@ export interface DashboardData {
 export interface SpendingBreakdown {
   year: number;
   month: number;
-  total_income: number;
-  total_expenses: number;
+  total_income: Money;
+  total_expenses: Money;
   categories: CategorySpend[];
 }
 
+export interface Tag {
+  id: number;
+  name: string;
+  color: string | null;
+  created_at: string;
+}
+
+export interface Payee {
+  id: number;
+  name: string;
+  created_at: string;
+  transaction_count: number;
+}
+
+export interface PayeeAlias {
+  id: number;
+  payee_id: number;
+  pattern: string;
+  created_at: string;
+}
+
+/** Unset conditions match anything; unset actions do nothing. */
+export interface Rule {
+  id: number;
+  name: string;
+  priority: number;
+  is_active: number;
+  notes_contains: string | null;
+  payee_contains: string | null;
+  amount_min: Money | null;
+  amount_max: Money | null;
+  account_id: number | null;
+  tx_type: string | null;
+  set_category_id: number | null;
+  set_payee_id: number | null;
+  set_notes: string | null;
+  tag_ids: number[];
+  created_at: string;
+  updated_at: string;
+}
+
+export interface RuleInput {
+  name: string;
+  priority: number;
+  is_active: boolean;
+  notes_contains: string | null;
+  payee_contains: string | null;
+  amount_min: Money | null;
+  amount_max: Money | null;
+  account_id: number | null;
+  tx_type: string | null;
+  set_category_id: number | null;
+  set_payee_id: number | null;
+  set_notes: string | null;
+  tag_ids: number[];
+}
+
+export interface RuleChange {
+  transaction: Transaction;
+  rule_ids: number[];
+  category_id: number | null;
+  payee_id: number | null;
+  notes: string | null;
+  add_tag_ids: number[];
+}
+
+export interface ExchangeRate {
+  id: number;
+  base: string;
+  quote: string;
+  /** Units of `quote` per one `base`. */
+  rate: number;
+  date: string;
+  source: "manual" | "csv";
+  created_at: string;
+}
+
+export interface Attachment {
+  id: number;
+  transaction_id: number | null;
+  transfer_id: number | null;
+  file_name: string;
+  mime_type: string;
+  /** Bytes. */
+  size: number;
+  has_thumbnail: boolean;
+  created_at: string;
+}
+
+export interface DuplicateCluster {
+  transactions: Transaction[];
+  score: number;
+  suggested_keep_id: number;
+}
+
+export interface PayeeSummary {
+  payee_id: number;
+  payee_name: string;
+  transaction_count: number;
+  total: Money;
+}
+
+export interface TagSummary {
+  tag_id: number;
+  tag_name: string;
+  transaction_count: number;
+  income: Money;
+  expenses: Money;
+  net: Money;
+}
+
+/** Monthly limit for an expense category, in the base currency. */
+export interface Budget {
+  id: number;
+  category_id: number;
+  category_name: string;
+  /** YYYY-MM */
+  period: string;
+  amount: Money;
+  rollover: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface BudgetLine {
+  budget_id: number;
+  category_id: number;
+  category_name: string;
+  amount: Money;
+  /** Negative after last month ran over. */
+  carried_over: Money;
+  available: Money;
+  spent: Money;
+  remaining: Money;
+  /** Null when nothing is available. */
+  percent_used: number | null;
+  projected_spend: Money;
+  projected_overspend: Money;
+}
+
+export interface BudgetAlert {
+  budget_id: number;
+  category_id: number;
+  category_name: string;
+  threshold: 80 | 100;
+  percent_used: number | null;
+  spent: Money;
+  available: Money;
+}
+
+export interface BudgetStatus {
+  year: number;
+  month: number;
+  days_in_month: number;
+  days_elapsed: number;
+  total_available: Money;
+  total_spent: Money;
+  categories: BudgetLine[];
+  alerts: BudgetAlert[];
+}
+
+export interface EnvelopeMove {
+  id: number;
+  period: string;
+  /** Null when the money came from the to-be-assigned pool. */
+  from_category_id: number | null;
+  /** Null when the money went back to the pool. */
+  to_category_id: number | null;
+  amount: Money;
+  notes: string | null;
+  created_at: string;
+}
+
+export interface Envelope {
+  category_id: number;
+  category_name: string;
+  carried_over: Money;
+  assigned: Money;
+  spent: Money;
+  /** Negative when overspent. */
+  available: Money;
+}
+
+export interface EnvelopeMonth {
+  year: number;
+  month: number;
+  income: Money;
+  to_be_assigned: Money;
+  overspent_last_month: Money;
+  assigned: Money;
+  spent: Money;
+  available: Money;
+  envelopes: Envelope[];
+}
+
 export interface MonthSummary {
   year: number;
   month: number;
   label: string;
-  income: number;
-  expenses: number;
-  net: number;
+  income: Money;
+  expenses: Money;
+  net: Money;
 }
 
 export interface CreateTransactionInput {
   account_id: number;
   category_id: number | null;
+  payee_id?: number | null;
   tx_type: string;
-  amount: number;
+  amount: Money;
   date: string;
   notes: string | null;
   is_recurring: boolean;
@@ -116,8 +763,82 @@ export interface CreateTransactionInput {
 export interface CreateTransferInput {
   from_account_id: number;
   to_account_id: number;
-  amount: number;
+  amount: Money;
+  /** Required when the accounts use different currencies. */
+  to_amount: Money | null;
+  fee: TransferFee | null;
   date: string;
   notes: string | null;
   transfer_type: string | null;
 }
+
+/** How to read one bank's CSV export. Column indexes are 0-based. */
+export interface CsvMapping {
+  /** null detects the delimiter from the file. */
+  delimiter: string | null;
+  has_header: boolean;
+  skip_rows: number;
+  date_column: number;
+  /** Built from YYYY, YY, MM, M, DD, D and MMM (month name), e.g. "MM/DD/YYYY". */
+  date_format: string;
+  description_column: number | null;
+  amount_column: number | null;
+  debit_column: number | null;
+  credit_column: number | null;
+  negate_amounts: boolean;
+  decimal_separator: string;
+}
+
+export interface ImportProfile {
+  id: number;
+  name: string;
+  mapping: CsvMapping;
+  created_at: string;
+}
+
+export interface ImportRow {
+  line: number;
+  date: string | null;
+  description: string | null;
+  type: string | null;
+  amount: Money | null;
+  external_id: string | null;
+  /** Existing transaction this row probably repeats. */
+  duplicate_of: number | null;
+  error: string | null;
+}
+
+export interface ImportPreview {
+  delimiter: string;
+  headers: string[];
+  rows: ImportRow[];
+}
+
+export interface ImportBatch {
+  id: number;
+  account_id: number;
+  source: string;
+  file_name: string | null;
+  row_count: number;
+  created_at: string;
+  account_name: string | null;
+}
+
+export interface ImportResult {
+  /** Null when every row was already imported. */
+  batch_id: number | null;
+  account_id: number;
+  imported: number;
+  skipped: number;
+  duplicates: number;
+  /** Imported rows flagged as probable duplicates. */
+  flagged: number;
+}
+
+export interface OfxStatementPreview {
+  ofx_account_id: string | null;
+  currency: string | null;
+  /** Account already linked to this OFX account, if any. */
+  account_id: number | null;
+  rows: ImportRow[];
+}